@@ -2,6 +2,8 @@
 
 use crate::client::Client;
 use crate::error::Result;
+use crate::pagination::{PageParams, Paginator};
+use crate::types::Paging;
 use serde::{Deserialize, Serialize};
 
 /// Block Users API client
@@ -93,6 +95,19 @@ impl BlockApi {
         let url = format!("{}/block", self.client.base_url());
         self.client.get(&url).await
     }
+
+    /// Stream all blocked users, following paging cursors
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Page size and starting cursor
+    pub fn get_blocked_users_paginated(
+        &self,
+        params: PageParams,
+    ) -> Paginator<BlockedUsersResponse> {
+        let url = format!("{}/block", self.client.base_url());
+        Paginator::new(self.client.clone(), url, params)
+    }
 }
 
 // Request types
@@ -142,6 +157,9 @@ pub struct BlockedUsersResponse {
     /// List of blocked users
     #[serde(default)]
     pub data: Vec<BlockedUser>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// Blocked user info
@@ -2,22 +2,35 @@
 
 use crate::analytics::AnalyticsApi;
 use crate::block::BlockApi;
-use crate::error::{ApiErrorResponse, Error, Result};
+use crate::conversation::ConversationWindow;
+use crate::error::{ApiError, ApiErrorResponse, Error, Result};
 use crate::flows::FlowsApi;
 use crate::media::MediaApi;
+use crate::media_cache::MediaCache;
 use crate::messages::MessagesApi;
 use crate::phone_numbers::PhoneNumbersApi;
 use crate::products::ProductsApi;
 use crate::qr_codes::QrCodesApi;
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
 use crate::templates::TemplatesApi;
+use crate::transport::{Body, HttpRequest, HttpResponse, Multipart, ReqwestTransport, Transport};
 use crate::types::{DEFAULT_API_VERSION, GRAPH_API_URL};
 use crate::typing::TypingApi;
+use crate::uploads::UploadSessionApi;
+use crate::usage::{parse_retry_after, UsageInfo};
 use crate::waba::WabaApi;
 use crate::webhooks_management::WebhookSubscriptionsApi;
+use bytes::Bytes;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Default timeout for a whole request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// WhatsApp Cloud API client
 #[derive(Clone)]
@@ -25,12 +38,18 @@ pub struct Client {
     inner: Arc<ClientInner>,
 }
 
+#[derive(Clone)]
 struct ClientInner {
-    http: reqwest::Client,
-    access_token: String,
+    transport: Arc<dyn Transport>,
+    authorization: HeaderValue,
     phone_number_id: String,
     api_version: String,
     base_url: String,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    conversation_window: Option<ConversationWindow>,
+    media_cache: Option<MediaCache>,
+    usage: Arc<Mutex<Option<UsageInfo>>>,
 }
 
 impl Client {
@@ -48,47 +67,164 @@ impl Client {
     ///
     /// let client = Client::new("your_access_token", "your_phone_number_id");
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the HTTP client cannot be created or the access token is not
+    /// a valid header value. Use [`Client::builder`] to handle these errors.
     pub fn new(access_token: impl Into<String>, phone_number_id: impl Into<String>) -> Self {
-        Self::with_config(
-            access_token,
-            phone_number_id,
-            DEFAULT_API_VERSION,
-            GRAPH_API_URL,
-        )
+        Self::builder(access_token, phone_number_id)
+            .build()
+            .expect("Failed to create HTTP client")
     }
 
     /// Create a new client with custom API version
+    ///
+    /// # Panics
+    ///
+    /// See [`Client::new`].
     pub fn with_version(
         access_token: impl Into<String>,
         phone_number_id: impl Into<String>,
         api_version: impl Into<String>,
     ) -> Self {
-        Self::with_config(access_token, phone_number_id, api_version, GRAPH_API_URL)
+        Self::builder(access_token, phone_number_id)
+            .api_version(api_version)
+            .build()
+            .expect("Failed to create HTTP client")
     }
 
     /// Create a new client with full configuration
+    ///
+    /// # Panics
+    ///
+    /// See [`Client::new`].
     pub fn with_config(
         access_token: impl Into<String>,
         phone_number_id: impl Into<String>,
         api_version: impl Into<String>,
         base_url: impl Into<String>,
     ) -> Self {
-        let http = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
+        Self::builder(access_token, phone_number_id)
+            .api_version(api_version)
+            .base_url(base_url)
             .build()
-            .expect("Failed to create HTTP client");
+            .expect("Failed to create HTTP client")
+    }
+
+    /// Start building a client with custom HTTP settings
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wacloudapi::Client;
+    ///
+    /// # fn example() -> wacloudapi::Result<()> {
+    /// let client = Client::builder("your_access_token", "your_phone_number_id")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .connect_timeout(Duration::from_secs(2))
+    ///     .user_agent("my-bot/1.0")
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder(
+        access_token: impl Into<String>,
+        phone_number_id: impl Into<String>,
+    ) -> ClientBuilder {
+        ClientBuilder::new(access_token, phone_number_id)
+    }
 
+    /// Retry failed requests according to the given policy
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wacloudapi::retry::RetryPolicy;
+    /// use wacloudapi::Client;
+    ///
+    /// let client = Client::new("token", "phone_id").with_retry_policy(RetryPolicy::new());
+    /// assert!(client.retry_policy().is_some());
+    /// ```
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.retry_policy = Some(policy);
         Self {
-            inner: Arc::new(ClientInner {
-                http,
-                access_token: access_token.into(),
-                phone_number_id: phone_number_id.into(),
-                api_version: api_version.into(),
-                base_url: base_url.into(),
-            }),
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Get the retry policy, if retries are enabled
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.inner.retry_policy.as_ref()
+    }
+
+    /// Hold outgoing messages back according to the given rate limiter
+    ///
+    /// Every message sent through this client (and its clones) waits for
+    /// the limiter before it is submitted.
+    pub fn with_rate_limiter(self, limiter: RateLimiter) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.rate_limiter = Some(Arc::new(limiter));
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Get the rate limiter, if one is configured
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.inner.rate_limiter.as_deref()
+    }
+
+    /// Refuse free-form messages outside the customer service window
+    ///
+    /// Every non-template message sent through this client (and its clones)
+    /// to a user whose window is closed fails with
+    /// [`Error::WindowClosed`] instead of calling the API. Keep feeding
+    /// `window` with incoming webhooks; it shares its state with its clones.
+    pub fn with_conversation_window(self, window: ConversationWindow) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.conversation_window = Some(window);
+        Self {
+            inner: Arc::new(inner),
         }
     }
 
+    /// Get the conversation window guarding sends, if one is configured
+    pub fn conversation_window(&self) -> Option<&ConversationWindow> {
+        self.inner.conversation_window.as_ref()
+    }
+
+    /// Reuse uploaded media IDs and downloaded media through the given cache
+    ///
+    /// See the [`media_cache`](crate::media_cache) module for what is cached.
+    pub fn with_media_cache(self, cache: MediaCache) -> Self {
+        let mut inner = Arc::unwrap_or_clone(self.inner);
+        inner.media_cache = Some(cache);
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Get the media cache, if one is configured
+    pub fn media_cache(&self) -> Option<&MediaCache> {
+        self.inner.media_cache.as_ref()
+    }
+
+    /// Get the usage reported by the most recent response
+    ///
+    /// Updated from the `X-App-Usage` and `X-Business-Use-Case-Usage`
+    /// headers of every response that carries them, and shared between
+    /// clones of this client.
+    pub fn usage(&self) -> Option<UsageInfo> {
+        self.inner
+            .usage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     /// Get the phone number ID
     pub fn phone_number_id(&self) -> &str {
         &self.inner.phone_number_id
@@ -115,29 +251,29 @@ impl Client {
         )
     }
 
+    /// Get the access token in the `OAuth <token>` form used by the
+    /// Resumable Upload API
+    pub(crate) fn oauth_authorization(&self) -> HeaderValue {
+        let bearer = self.inner.authorization.as_bytes();
+        let token = bearer.strip_prefix(b"Bearer ").unwrap_or(bearer);
+        let mut value = HeaderValue::from_bytes(&[b"OAuth ", token].concat())
+            .expect("token was a valid header value");
+        value.set_sensitive(true);
+        value
+    }
+
     /// Get default headers for requests
     fn default_headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.inner.access_token))
-                .expect("Invalid access token"),
-        );
+        headers.insert(AUTHORIZATION, self.inner.authorization.clone());
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
         headers
     }
 
     /// Make a GET request
     pub(crate) async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        let response = self
-            .inner
-            .http
-            .get(url)
-            .headers(self.default_headers())
-            .send()
-            .await?;
-
-        self.handle_response(response).await
+        self.execute(true, || Ok(self.request(Method::GET, url)))
+            .await
     }
 
     /// Make a POST request with JSON body
@@ -146,80 +282,230 @@ impl Client {
         url: &str,
         body: &B,
     ) -> Result<T> {
-        let response = self
+        let body = Bytes::from(serde_json::to_vec(body)?);
+
+        self.execute(false, || {
+            let mut request = self.request(Method::POST, url);
+            request.body = Body::Bytes(body.clone());
+            Ok(request)
+        })
+        .await
+    }
+
+    /// Send a message to the `/messages` endpoint of this phone number
+    ///
+    /// Free-form messages are checked against the conversation window, if
+    /// one is configured. Waits for the rate limiter, if one is configured.
+    pub(crate) async fn send_message<T: DeserializeOwned, B: Serialize>(
+        &self,
+        to: &str,
+        free_form: bool,
+        body: &B,
+    ) -> Result<T> {
+        if let Some(window) = self
             .inner
-            .http
-            .post(url)
-            .headers(self.default_headers())
-            .json(body)
-            .send()
-            .await?;
+            .conversation_window
+            .as_ref()
+            .filter(|_| free_form)
+        {
+            window.check(to)?;
+        }
+        if let Some(limiter) = &self.inner.rate_limiter {
+            limiter.acquire(to).await;
+        }
 
-        self.handle_response(response).await
+        let url = format!("{}/messages", self.base_url());
+        self.post(&url, body).await
     }
 
     /// Make a POST request with form data
+    ///
+    /// The form is built by `form` for every attempt, since a streamed body
+    /// can only be sent once.
     pub(crate) async fn post_form<T: DeserializeOwned>(
         &self,
         url: &str,
-        form: reqwest::multipart::Form,
+        form: impl Fn() -> Result<Multipart>,
     ) -> Result<T> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", self.inner.access_token))
-                .expect("Invalid access token"),
-        );
-
-        let response = self
-            .inner
-            .http
-            .post(url)
-            .headers(headers)
-            .multipart(form)
-            .send()
-            .await?;
+        self.execute(false, || {
+            let form = form()?;
+            let mut request = self.request(Method::POST, url);
+            request.headers.insert(CONTENT_TYPE, form.content_type());
+            request.body = form.into_body();
+            Ok(request)
+        })
+        .await
+    }
 
-        self.handle_response(response).await
+    /// Make a POST request with form data that can only be sent once
+    ///
+    /// Not retried, since the first attempt consumes the body.
+    pub(crate) async fn post_form_once<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        form: Multipart,
+    ) -> Result<T> {
+        let mut request = self.request(Method::POST, url);
+        request.headers.insert(CONTENT_TYPE, form.content_type());
+        request.body = form.into_body();
+        self.execute_once(request).await
     }
 
     /// Make a DELETE request
     pub(crate) async fn delete<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        self.execute(true, || Ok(self.request(Method::DELETE, url)))
+            .await
+    }
+
+    /// Build a request with the default headers
+    pub(crate) fn request(&self, method: Method, url: &str) -> HttpRequest {
+        let mut request = HttpRequest::new(method, url);
+        request.headers = self.default_headers();
+        request
+    }
+
+    /// Send a request, retrying according to the configured retry policy
+    pub(crate) async fn execute<T: DeserializeOwned>(
+        &self,
+        idempotent: bool,
+        request: impl Fn() -> Result<HttpRequest>,
+    ) -> Result<T> {
+        let response = self.send(idempotent, request).await?;
+        let body = response.body.into_bytes().await?;
+        serde_json::from_slice(&body).map_err(Error::from)
+    }
+
+    /// Send a request once, without retries
+    ///
+    /// For requests whose body can only be sent once.
+    pub(crate) async fn execute_once<T: DeserializeOwned>(
+        &self,
+        request: HttpRequest,
+    ) -> Result<T> {
+        let response = self.inner.transport.send(request).await?;
+        let response = self.handle_response(response).await?;
+        let body = response.body.into_bytes().await?;
+        serde_json::from_slice(&body).map_err(Error::from)
+    }
+
+    /// Make an authenticated GET request and return the body unread
+    ///
+    /// Used for downloads, whose body is not JSON and may be large.
+    pub(crate) async fn get_body(&self, url: &str) -> Result<Body> {
         let response = self
-            .inner
-            .http
-            .delete(url)
-            .headers(self.default_headers())
-            .send()
+            .send(true, || Ok(self.request(Method::GET, url)))
             .await?;
+        Ok(response.body)
+    }
 
-        self.handle_response(response).await
+    /// Send a request until it succeeds or the retry policy gives up
+    ///
+    /// Returns the response of the first successful attempt; failed
+    /// responses are turned into errors.
+    async fn send(
+        &self,
+        idempotent: bool,
+        request: impl Fn() -> Result<HttpRequest>,
+    ) -> Result<HttpResponse> {
+        let mut attempt = 1;
+
+        loop {
+            let (status, result) = match self.inner.transport.send(request()?).await {
+                Ok(response) => {
+                    let status = response.status.as_u16();
+                    (Some(status), self.handle_response(response).await)
+                }
+                Err(e) => (None, Err(e)),
+            };
+
+            let error = match result {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let policy = match &self.inner.retry_policy {
+                Some(policy)
+                    if attempt < policy.max_attempts
+                        && policy.should_retry(status, &error, idempotent) =>
+                {
+                    policy
+                }
+                _ => return Err(error),
+            };
+
+            let mut delay = policy.delay_for(attempt);
+            if let Error::RateLimited {
+                retry_after: Some(seconds),
+                ..
+            } = error
+            {
+                // Waiting longer than the policy allows is left to the caller
+                let retry_after = Duration::from_secs(seconds);
+                if retry_after > policy.max_delay {
+                    return Err(error);
+                }
+                delay = delay.max(retry_after);
+            }
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     /// Handle API response
-    async fn handle_response<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T> {
-        let status = response.status();
-        let body = response.text().await?;
+    ///
+    /// Records the usage headers and passes successful responses through.
+    async fn handle_response(&self, response: HttpResponse) -> Result<HttpResponse> {
+        let status = response.status;
+        let usage = UsageInfo::from_headers(&response.headers);
+        let retry_after = parse_retry_after(&response.headers);
+        if let Some(usage) = &usage {
+            *self.inner.usage.lock().unwrap_or_else(|e| e.into_inner()) = Some(usage.clone());
+        }
 
         if status.is_success() {
-            serde_json::from_str(&body).map_err(Error::from)
-        } else {
-            // Try to parse error response
-            match serde_json::from_str::<ApiErrorResponse>(&body) {
-                Ok(error_response) => Err(error_response.into()),
-                Err(_) => Err(Error::Api {
-                    code: status.as_u16() as i32,
-                    message: body,
-                    error_subcode: None,
-                    error_data: None,
+            return Ok(response);
+        }
+        let body = response.body.into_bytes().await?;
+
+        // Try to parse error response
+        let error = match serde_json::from_slice::<ApiErrorResponse>(&body) {
+            Ok(error_response) => error_response.into(),
+            Err(_) if status == StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+                retry_after: None,
+                error: None,
+                usage: None,
+            },
+            Err(_) => Error::Api(Box::new(ApiError {
+                message: String::from_utf8_lossy(&body).into_owned(),
+                error_type: None,
+                code: status.as_u16() as i32,
+                error_subcode: None,
+                error_user_title: None,
+                error_user_msg: None,
+                fbtrace_id: None,
+                error_data: None,
+            })),
+        };
+
+        match error {
+            Error::RateLimited { error, .. } => Err(Error::RateLimited {
+                retry_after: retry_after.or_else(|| {
+                    usage
+                        .as_ref()
+                        .and_then(UsageInfo::estimated_time_to_regain_access)
+                        .map(|wait| wait.as_secs())
                 }),
-            }
+                error,
+                usage: usage.map(Box::new),
+            }),
+            error => Err(error),
         }
     }
 
-    /// Get the raw HTTP client (for advanced use)
-    pub fn http_client(&self) -> &reqwest::Client {
-        &self.inner.http
+    /// Get the HTTP transport used by this client
+    pub fn transport(&self) -> &dyn Transport {
+        self.inner.transport.as_ref()
     }
 
     /// Access the Messages API
@@ -285,6 +571,15 @@ impl Client {
         WabaApi::new(self.clone(), waba_id.into())
     }
 
+    /// Access the Resumable Upload API
+    ///
+    /// # Arguments
+    ///
+    /// * `app_id` - Facebook App ID
+    pub fn uploads(&self, app_id: impl Into<String>) -> UploadSessionApi {
+        UploadSessionApi::new(self.clone(), app_id.into())
+    }
+
     /// Access the Webhook Subscriptions API
     ///
     /// # Arguments
@@ -304,3 +599,217 @@ impl std::fmt::Debug for Client {
             .finish()
     }
 }
+
+/// Builder for a [`Client`] with custom HTTP settings
+///
+/// Created with [`Client::builder`].
+pub struct ClientBuilder {
+    access_token: String,
+    phone_number_id: String,
+    api_version: String,
+    base_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    transport: Option<Arc<dyn Transport>>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    conversation_window: Option<ConversationWindow>,
+    media_cache: Option<MediaCache>,
+}
+
+impl ClientBuilder {
+    /// Create a builder with the given access token and phone number ID
+    pub fn new(access_token: impl Into<String>, phone_number_id: impl Into<String>) -> Self {
+        Self {
+            access_token: access_token.into(),
+            phone_number_id: phone_number_id.into(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            base_url: GRAPH_API_URL.to_string(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: None,
+            pool_idle_timeout: None,
+            pool_max_idle_per_host: None,
+            proxy: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            transport: None,
+            retry_policy: None,
+            rate_limiter: None,
+            conversation_window: None,
+            media_cache: None,
+        }
+    }
+
+    /// Set the Graph API version (default: `v21.0`)
+    pub fn api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Set the Graph API base URL (default: `https://graph.facebook.com`)
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Set the timeout for a whole request (default: 30 seconds)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Disable the request timeout
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Set the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long idle pooled connections are kept alive
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Route requests through a proxy
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the `User-Agent` header
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn default_header(mut self, name: reqwest::header::HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Use a preconfigured `reqwest::Client`
+    ///
+    /// Timeouts, pool settings, proxy, user agent and default headers set
+    /// on this builder are ignored; configure them on the given client.
+    pub fn http_client(self, http: reqwest::Client) -> Self {
+        self.transport(ReqwestTransport::new(http))
+    }
+
+    /// Send requests through a custom transport
+    ///
+    /// Timeouts, pool settings, proxy, user agent and default headers set
+    /// on this builder are ignored; they only configure the default
+    /// `reqwest` transport.
+    pub fn transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Retry failed requests according to the given policy
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Hold outgoing messages back according to the given rate limiter
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Refuse free-form messages outside the customer service window
+    pub fn conversation_window(mut self, window: ConversationWindow) -> Self {
+        self.conversation_window = Some(window);
+        self
+    }
+
+    /// Reuse uploaded media IDs and downloaded media through the given cache
+    pub fn media_cache(mut self, cache: MediaCache) -> Self {
+        self.media_cache = Some(cache);
+        self
+    }
+
+    /// Build the client
+    ///
+    /// Fails if the access token is not a valid header value or the HTTP
+    /// client cannot be created.
+    pub fn build(self) -> Result<Client> {
+        let mut authorization = HeaderValue::from_str(&format!("Bearer {}", self.access_token))
+            .map_err(|_| {
+                Error::Config("access token contains invalid header characters".to_string())
+            })?;
+        authorization.set_sensitive(true);
+
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut builder = reqwest::Client::builder().default_headers(self.default_headers);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.pool_idle_timeout {
+                    builder = builder.pool_idle_timeout(timeout);
+                }
+                if let Some(max) = self.pool_max_idle_per_host {
+                    builder = builder.pool_max_idle_per_host(max);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+
+        Ok(Client {
+            inner: Arc::new(ClientInner {
+                transport,
+                authorization,
+                phone_number_id: self.phone_number_id,
+                api_version: self.api_version,
+                base_url: self.base_url,
+                retry_policy: self.retry_policy,
+                rate_limiter: self.rate_limiter.map(Arc::new),
+                conversation_window: self.conversation_window,
+                media_cache: self.media_cache,
+                usage: Arc::default(),
+            }),
+        })
+    }
+}
+
+impl std::fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("phone_number_id", &self.phone_number_id)
+            .field("api_version", &self.api_version)
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("user_agent", &self.user_agent)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,222 @@
+//! Tracking of the 24-hour customer service window
+//!
+//! Free-form messages (anything but templates) can only be sent to a user
+//! within 24 hours of their last message; outside of it the API rejects them
+//! with error 131047. A [`ConversationWindow`] is fed incoming-message
+//! webhooks and tells whether the window with a user is open and when it
+//! closes.
+//!
+//! Attached to a client with [`Client::with_conversation_window`], it also
+//! guards sends: free-form messages to a user whose window is closed fail
+//! with [`Error::WindowClosed`] without calling the API.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wacloudapi::conversation::ConversationWindow;
+//! use wacloudapi::webhooks::WebhookRouter;
+//! use wacloudapi::Client;
+//!
+//! let window = ConversationWindow::new();
+//!
+//! // Let the window see every incoming message
+//! let router = WebhookRouter::new().middleware(window.clone());
+//!
+//! // Refuse free-form messages outside the window
+//! let client = Client::new("token", "phone_id").with_conversation_window(window.clone());
+//!
+//! assert!(!window.is_open("628123456789"));
+//! ```
+//!
+//! [`Client::with_conversation_window`]: crate::Client::with_conversation_window
+//! [`Error::WindowClosed`]: crate::Error::WindowClosed
+
+use crate::error::{Error, Result};
+use crate::webhooks::{
+    EventKind, Middleware, MiddlewareFuture, Next, WebhookEnvelope, WebhookEvent, WebhookPayload,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long after a user's message free-form messages are allowed
+pub const CUSTOMER_SERVICE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Users held before closed windows are pruned
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Open customer service windows, by user
+///
+/// Cloning is cheap and clones share their state. Users are identified by
+/// the digits of their phone number, so `+62 811-1222-333` and
+/// `628111222333` refer to the same window.
+#[derive(Debug, Clone, Default)]
+pub struct ConversationWindow {
+    inner: Arc<Mutex<WindowState>>,
+}
+
+#[derive(Debug, Default)]
+struct WindowState {
+    /// When the window with each user closes
+    closes_at: HashMap<String, SystemTime>,
+    /// Size at which closed windows are pruned next
+    prune_at: usize,
+}
+
+impl ConversationWindow {
+    /// Create a tracker without open windows
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a message received from `wa_id` at `received_at`
+    ///
+    /// Opens or extends the window to 24 hours after `received_at`. Times
+    /// too far in the future to represent are ignored.
+    pub fn record_incoming(&self, wa_id: &str, received_at: SystemTime) {
+        if let Some(closes_at) = received_at.checked_add(CUSTOMER_SERVICE_WINDOW) {
+            self.extend(wa_id, closes_at);
+        }
+    }
+
+    /// Record the expiration of a service conversation with `wa_id`
+    ///
+    /// Use the `expiration_timestamp` of a
+    /// [`ConversationInfo`](crate::webhooks::ConversationInfo) whose origin
+    /// is `service` (or `user_initiated`); conversations of other origins do
+    /// not allow free-form messages.
+    pub fn record_conversation(&self, wa_id: &str, expires_at: SystemTime) {
+        self.extend(wa_id, expires_at);
+    }
+
+    /// Apply an incoming message or status event; other events are ignored
+    ///
+    /// Incoming messages open the window from their timestamp (or now, if
+    /// the envelope has none). Status events of service conversations extend
+    /// it to the conversation's expiration.
+    pub fn handle_envelope(&self, envelope: &WebhookEnvelope) {
+        if let Some(wa_id) = &envelope.sender_wa_id {
+            if envelope.kind() != EventKind::System {
+                let received_at = envelope.time().unwrap_or_else(SystemTime::now);
+                self.record_incoming(wa_id, received_at);
+            }
+            return;
+        }
+
+        let (recipient, conversation) = match &envelope.event {
+            WebhookEvent::MessageSent {
+                recipient,
+                conversation,
+                ..
+            }
+            | WebhookEvent::MessageDelivered {
+                recipient,
+                conversation,
+                ..
+            }
+            | WebhookEvent::MessageRead {
+                recipient,
+                conversation,
+                ..
+            }
+            | WebhookEvent::MessageFailed {
+                recipient,
+                conversation,
+                ..
+            } => (recipient, conversation),
+            _ => return,
+        };
+        let Some(conversation) = conversation else {
+            return;
+        };
+        if !matches!(
+            conversation.origin_type(),
+            Some("service" | "user_initiated")
+        ) {
+            return;
+        }
+        let expires_at = conversation
+            .expiration_timestamp
+            .as_deref()
+            .and_then(|timestamp| timestamp.parse().ok())
+            .and_then(|seconds| UNIX_EPOCH.checked_add(Duration::from_secs(seconds)));
+        if let Some(expires_at) = expires_at {
+            self.record_conversation(recipient, expires_at);
+        }
+    }
+
+    /// Apply every event of a webhook payload
+    pub fn handle_payload(&self, payload: &WebhookPayload) {
+        for envelope in payload.envelopes() {
+            self.handle_envelope(&envelope);
+        }
+    }
+
+    /// Whether a free-form message to `wa_id` is allowed now
+    pub fn is_open(&self, wa_id: &str) -> bool {
+        self.is_open_at(wa_id, SystemTime::now())
+    }
+
+    /// Whether a free-form message to `wa_id` is allowed at `time`
+    pub fn is_open_at(&self, wa_id: &str, time: SystemTime) -> bool {
+        self.closes_at(wa_id)
+            .is_some_and(|closes_at| time < closes_at)
+    }
+
+    /// When the window with `wa_id` closes or closed, if it was ever opened
+    pub fn closes_at(&self, wa_id: &str) -> Option<SystemTime> {
+        let state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.closes_at.get(&normalize(wa_id)).copied()
+    }
+
+    /// Time left in the window with `wa_id`, `None` if it is closed
+    pub fn remaining(&self, wa_id: &str) -> Option<Duration> {
+        self.closes_at(wa_id)?
+            .duration_since(SystemTime::now())
+            .ok()
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Fail with [`Error::WindowClosed`] unless the window with `to` is open
+    pub fn check(&self, to: &str) -> Result<()> {
+        match self.is_open(to) {
+            true => Ok(()),
+            false => Err(Error::WindowClosed {
+                recipient: to.to_string(),
+                closed_at: self.closes_at(to),
+            }),
+        }
+    }
+
+    /// Forget windows that are closed
+    pub fn prune(&self) {
+        let now = SystemTime::now();
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.closes_at.retain(|_, closes_at| *closes_at > now);
+    }
+
+    fn extend(&self, wa_id: &str, closes_at: SystemTime) {
+        let now = SystemTime::now();
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = state.closes_at.entry(normalize(wa_id)).or_insert(closes_at);
+        *entry = (*entry).max(closes_at);
+
+        if state.closes_at.len() >= state.prune_at.max(PRUNE_THRESHOLD) {
+            state.closes_at.retain(|_, closes_at| *closes_at > now);
+            state.prune_at = (state.closes_at.len() * 2).max(PRUNE_THRESHOLD);
+        }
+    }
+}
+
+impl Middleware for ConversationWindow {
+    /// Apply incoming messages and status events and let every event through
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+        self.handle_envelope(event);
+        Box::pin(std::future::ready(Ok(Next::Continue)))
+    }
+}
+
+/// Digits of a phone number or WhatsApp ID
+fn normalize(wa_id: &str) -> String {
+    wa_id.chars().filter(char::is_ascii_digit).collect()
+}
@@ -0,0 +1,531 @@
+//! Delivery state of sent messages, built from status webhooks
+//!
+//! A [`DeliveryTracker`] ties the [`MessageResponse`] of a send to the status
+//! events that arrive later through webhooks, and follows each message
+//! through its lifecycle:
+//!
+//! ```text
+//! accepted -> sent -> delivered -> read
+//!       \        \          \
+//!        +--------+----------+--> failed
+//! ```
+//!
+//! Webhooks can arrive out of order, or before the send call returns. A state
+//! never moves backwards: a `sent` status arriving after `delivered` only
+//! records its timestamp.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::time::Duration;
+//! use wacloudapi::delivery::{DeliveryState, DeliveryTracker};
+//! use wacloudapi::webhooks::WebhookRouter;
+//! use wacloudapi::Client;
+//!
+//! # async fn example() -> wacloudapi::Result<()> {
+//! let client = Client::new("token", "phone_id");
+//! let tracker = DeliveryTracker::new();
+//!
+//! // Feed status webhooks to the tracker
+//! let router = WebhookRouter::new().middleware(tracker.clone());
+//!
+//! let response = client.messages().send_text("628123456789", "Hello").await?;
+//! tracker.record(&response);
+//!
+//! let delivery = tracker
+//!     .wait_for(&response.messages[0].id, DeliveryState::Delivered, Duration::from_secs(30))
+//!     .await?;
+//! println!("{:?}", delivery.state);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Error, Result};
+use crate::types::MessageResponse;
+use crate::webhooks::{
+    ConversationInfo, Middleware, MiddlewareFuture, Next, PricingInfo, WebhookEnvelope,
+    WebhookError, WebhookEvent, WebhookPayload,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Messages tracked by [`DeliveryTracker::new`] before the oldest are dropped
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Updates buffered per subscriber before it starts lagging
+const UPDATE_BUFFER: usize = 1024;
+
+/// Lifecycle state of a sent message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeliveryState {
+    /// Accepted by the API, no status received yet
+    Accepted,
+    /// Sent to WhatsApp
+    Sent,
+    /// Delivered to the recipient's device
+    Delivered,
+    /// Read by the recipient
+    Read,
+    /// Delivery failed
+    Failed,
+}
+
+impl DeliveryState {
+    /// Whether no further state change is expected
+    pub fn is_final(self) -> bool {
+        matches!(self, DeliveryState::Read | DeliveryState::Failed)
+    }
+
+    /// Whether a message in this state has got at least as far as `target`
+    ///
+    /// A failed message only reaches [`DeliveryState::Failed`].
+    pub fn has_reached(self, target: DeliveryState) -> bool {
+        match (self, target) {
+            (DeliveryState::Failed, target) => target == DeliveryState::Failed,
+            (_, DeliveryState::Failed) => false,
+            (state, target) => state.step() >= target.step(),
+        }
+    }
+
+    /// Whether a message in this state can move to `next`
+    fn can_become(self, next: DeliveryState) -> bool {
+        match (self, next) {
+            (DeliveryState::Read | DeliveryState::Failed, _) => false,
+            (_, DeliveryState::Failed) => true,
+            (state, next) => next.step() > state.step(),
+        }
+    }
+
+    /// Position in the accepted-to-read progression
+    fn step(self) -> u8 {
+        match self {
+            DeliveryState::Accepted => 0,
+            DeliveryState::Sent => 1,
+            DeliveryState::Delivered => 2,
+            DeliveryState::Read => 3,
+            DeliveryState::Failed => 4,
+        }
+    }
+}
+
+/// What is known about the delivery of one message
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDelivery {
+    /// Message ID (`wamid.*`)
+    pub message_id: String,
+    /// WhatsApp ID of the recipient, if known
+    pub recipient: Option<String>,
+    /// Current state
+    pub state: DeliveryState,
+    /// When the message was sent (Unix seconds)
+    pub sent_at: Option<u64>,
+    /// When the message was delivered (Unix seconds)
+    pub delivered_at: Option<u64>,
+    /// When the message was read (Unix seconds)
+    pub read_at: Option<u64>,
+    /// When the message failed (Unix seconds)
+    pub failed_at: Option<u64>,
+    /// Errors reported with a failed status
+    pub errors: Vec<WebhookError>,
+    /// Conversation the message was billed in, once reported
+    pub conversation: Option<ConversationInfo>,
+    /// Pricing of the message, once reported
+    pub pricing: Option<PricingInfo>,
+}
+
+impl MessageDelivery {
+    fn new(message_id: &str) -> Self {
+        Self {
+            message_id: message_id.to_string(),
+            recipient: None,
+            state: DeliveryState::Accepted,
+            sent_at: None,
+            delivered_at: None,
+            read_at: None,
+            failed_at: None,
+            errors: Vec::new(),
+            conversation: None,
+            pricing: None,
+        }
+    }
+}
+
+/// State change of a tracked message, see [`DeliveryTracker::subscribe`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeliveryUpdate {
+    /// Message ID
+    pub message_id: String,
+    /// State before the change, `None` for newly tracked messages
+    pub previous: Option<DeliveryState>,
+    /// State after the change
+    pub state: DeliveryState,
+}
+
+/// Tracks the delivery state of sent messages
+///
+/// Cloning is cheap and clones share their state. Messages are recorded with
+/// [`record`](Self::record) and advanced by status events passed to
+/// [`handle_event`](Self::handle_event) or [`handle_payload`](Self::handle_payload).
+/// The tracker also implements [`Middleware`], so it can observe the events
+/// of a [`WebhookRouter`](crate::webhooks::WebhookRouter).
+///
+/// Status events for message IDs that were not recorded are tracked too,
+/// since a webhook can arrive before the send call returns. Beyond the
+/// capacity, the oldest tracked messages are dropped.
+#[derive(Debug, Clone)]
+pub struct DeliveryTracker {
+    inner: Arc<TrackerInner>,
+}
+
+#[derive(Debug)]
+struct TrackerInner {
+    capacity: usize,
+    state: Mutex<TrackerState>,
+    updates: broadcast::Sender<DeliveryUpdate>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerState {
+    /// Deliveries with the sequence number they were first tracked under
+    messages: HashMap<String, (u64, MessageDelivery)>,
+    /// Message IDs in tracking order, for eviction
+    order: VecDeque<(u64, String)>,
+    next_seq: u64,
+}
+
+impl Default for DeliveryTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeliveryTracker {
+    /// Create a tracker holding up to [`DEFAULT_CAPACITY`] messages
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Create a tracker holding up to `capacity` messages
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (updates, _) = broadcast::channel(UPDATE_BUFFER);
+        Self {
+            inner: Arc::new(TrackerInner {
+                capacity: capacity.max(1),
+                state: Mutex::new(TrackerState::default()),
+                updates,
+            }),
+        }
+    }
+
+    /// Track the messages of a send response as accepted
+    pub fn record(&self, response: &MessageResponse) {
+        for (index, message) in response.messages.iter().enumerate() {
+            let recipient = response.contacts.get(index).map(|c| c.wa_id.as_str());
+            self.track(&message.id, recipient);
+        }
+    }
+
+    /// Track a message ID as accepted
+    ///
+    /// Does nothing to the state of a message that is already tracked, but
+    /// fills in its recipient if unknown.
+    pub fn track(&self, message_id: &str, recipient: Option<&str>) {
+        self.update(message_id, |delivery| {
+            if delivery.recipient.is_none() {
+                delivery.recipient = recipient.map(String::from);
+            }
+            None
+        });
+    }
+
+    /// Apply a status event; other events are ignored
+    ///
+    /// Returns the state change, if the event caused one.
+    pub fn handle_event(&self, event: &WebhookEvent) -> Option<DeliveryUpdate> {
+        let (message_id, recipient, timestamp, conversation, pricing, errors, state) = match event {
+            WebhookEvent::MessageSent {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                ..
+            } => (
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                None,
+                DeliveryState::Sent,
+            ),
+            WebhookEvent::MessageDelivered {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                ..
+            } => (
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                None,
+                DeliveryState::Delivered,
+            ),
+            WebhookEvent::MessageRead {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                ..
+            } => (
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                None,
+                DeliveryState::Read,
+            ),
+            WebhookEvent::MessageFailed {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                errors,
+                ..
+            } => (
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                Some(errors),
+                DeliveryState::Failed,
+            ),
+            _ => return None,
+        };
+
+        self.update(message_id, |delivery| {
+            delivery.recipient.get_or_insert_with(|| recipient.clone());
+            let at = timestamp.parse().ok();
+            let slot = match state {
+                DeliveryState::Sent => &mut delivery.sent_at,
+                DeliveryState::Delivered => &mut delivery.delivered_at,
+                DeliveryState::Read => &mut delivery.read_at,
+                _ => &mut delivery.failed_at,
+            };
+            if slot.is_none() {
+                *slot = at;
+            }
+            if conversation.is_some() {
+                delivery.conversation = conversation.clone();
+            }
+            if pricing.is_some() {
+                delivery.pricing = pricing.clone();
+            }
+            if let Some(errors) = errors {
+                delivery.errors = errors.clone();
+            }
+            delivery.state.can_become(state).then_some(state)
+        })
+    }
+
+    /// Apply every status event of a webhook payload
+    pub fn handle_payload(&self, payload: &WebhookPayload) -> Vec<DeliveryUpdate> {
+        payload
+            .events()
+            .iter()
+            .filter_map(|event| self.handle_event(event))
+            .collect()
+    }
+
+    /// Delivery of a message, if tracked
+    pub fn get(&self, message_id: &str) -> Option<MessageDelivery> {
+        let state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.messages.get(message_id).map(|(_, d)| d.clone())
+    }
+
+    /// Current state of a message, if tracked
+    pub fn state(&self, message_id: &str) -> Option<DeliveryState> {
+        let state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.messages.get(message_id).map(|(_, d)| d.state)
+    }
+
+    /// Tracked messages in the given state, oldest first
+    pub fn in_state(&self, target: DeliveryState) -> Vec<MessageDelivery> {
+        self.filter(|delivery| delivery.state == target)
+    }
+
+    /// Tracked messages that are neither read nor failed, oldest first
+    pub fn pending(&self) -> Vec<MessageDelivery> {
+        self.filter(|delivery| !delivery.state.is_final())
+    }
+
+    /// Stop tracking a message
+    pub fn remove(&self, message_id: &str) -> Option<MessageDelivery> {
+        let mut state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.messages.remove(message_id).map(|(_, d)| d)
+    }
+
+    /// Number of tracked messages
+    pub fn len(&self) -> usize {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .messages
+            .len()
+    }
+
+    /// Whether no messages are tracked
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Receive every state change from now on
+    ///
+    /// A subscriber that falls more than 1024 updates behind skips the
+    /// oldest ones and gets [`RecvError::Lagged`](broadcast::error::RecvError::Lagged).
+    pub fn subscribe(&self) -> broadcast::Receiver<DeliveryUpdate> {
+        self.inner.updates.subscribe()
+    }
+
+    /// Wait until a message reaches `target` or a final state
+    ///
+    /// Returns the delivery as soon as its state
+    /// [has reached](DeliveryState::has_reached) `target`, or is final, so a
+    /// failed message is returned instead of waiting for the timeout; check
+    /// [`MessageDelivery::state`]. Fails with [`Error::DeliveryTimeout`] if
+    /// neither happens within `timeout`. The message does not need to be
+    /// tracked yet when called.
+    pub async fn wait_for(
+        &self,
+        message_id: &str,
+        target: DeliveryState,
+        timeout: Duration,
+    ) -> Result<MessageDelivery> {
+        let done = |delivery: &MessageDelivery| {
+            delivery.state.has_reached(target) || delivery.state.is_final()
+        };
+        // Subscribe before checking, so no update is missed in between
+        let mut updates = self.subscribe();
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            if let Some(delivery) = self.get(message_id).filter(done) {
+                return Ok(delivery);
+            }
+            loop {
+                match tokio::time::timeout_at(deadline, updates.recv()).await {
+                    Ok(Ok(update)) if update.message_id != message_id => continue,
+                    Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => break,
+                    Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => {
+                        return Err(Error::DeliveryTimeout {
+                            message_id: message_id.to_string(),
+                            state: self.state(message_id),
+                        })
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply `change` to the delivery of `message_id`, tracking it if new
+    ///
+    /// `change` returns the state to move to, if any; updates are published
+    /// for new messages and state changes.
+    fn update(
+        &self,
+        message_id: &str,
+        change: impl FnOnce(&mut MessageDelivery) -> Option<DeliveryState>,
+    ) -> Option<DeliveryUpdate> {
+        let mut state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        let TrackerState {
+            messages,
+            order,
+            next_seq,
+        } = &mut *state;
+
+        let mut previous = None;
+        let delivery = match messages.get_mut(message_id) {
+            Some((_, delivery)) => {
+                previous = Some(delivery.state);
+                delivery
+            }
+            None => {
+                let seq = *next_seq;
+                *next_seq += 1;
+                order.push_back((seq, message_id.to_string()));
+                &mut messages
+                    .entry(message_id.to_string())
+                    .or_insert((seq, MessageDelivery::new(message_id)))
+                    .1
+            }
+        };
+
+        if let Some(next) = change(delivery) {
+            delivery.state = next;
+        }
+        let current = delivery.state;
+
+        // Drop the oldest messages beyond capacity
+        while messages.len() > self.inner.capacity {
+            let Some((seq, id)) = order.pop_front() else {
+                break;
+            };
+            if messages
+                .get(&id)
+                .is_some_and(|(tracked, _)| *tracked == seq)
+            {
+                messages.remove(&id);
+            }
+        }
+        // Forget order entries of removed messages
+        if order.len() > messages.len() * 2 + 16 {
+            order.retain(|(seq, id)| messages.get(id).is_some_and(|(s, _)| s == seq));
+        }
+        drop(state);
+
+        if previous == Some(current) {
+            return None;
+        }
+        let update = DeliveryUpdate {
+            message_id: message_id.to_string(),
+            previous,
+            state: current,
+        };
+        // No subscribers is not an error
+        let _ = self.inner.updates.send(update.clone());
+        Some(update)
+    }
+
+    fn filter(&self, keep: impl Fn(&MessageDelivery) -> bool) -> Vec<MessageDelivery> {
+        let state = self.inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .order
+            .iter()
+            .filter_map(|(seq, id)| match state.messages.get(id) {
+                Some((tracked, delivery)) if tracked == seq && keep(delivery) => {
+                    Some(delivery.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Middleware for DeliveryTracker {
+    /// Apply status events and let every event through
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+        self.handle_event(&event.event);
+        Box::pin(std::future::ready(Ok(Next::Continue)))
+    }
+}
@@ -1,6 +1,11 @@
 //! Error types for the WhatsApp Cloud API SDK
 
+use crate::delivery::DeliveryState;
+use crate::media::MediaType;
+use crate::templates::{ButtonType, ComponentKind, HeaderFormat, ParameterFormat};
+use crate::usage::UsageInfo;
 use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
 use thiserror::Error;
 
 /// Result type alias for WhatsApp Cloud API operations
@@ -22,25 +27,32 @@ pub enum Error {
     UrlParse(#[from] url::ParseError),
 
     /// API error returned by WhatsApp Cloud API
-    #[error("API error: {message} (code: {code})")]
-    Api {
-        code: i32,
-        message: String,
-        error_subcode: Option<i32>,
-        error_data: Option<ApiErrorData>,
-    },
+    ///
+    /// Use [`ApiError::error_code`] to classify it.
+    #[error("API error: {} (code: {})", .0.message, .0.code)]
+    Api(Box<ApiError>),
 
     /// Rate limit exceeded
+    ///
+    /// `retry_after` comes from the `Retry-After` header or, failing that,
+    /// from `estimated_time_to_regain_access` in the usage headers.
     #[error("Rate limit exceeded. Retry after {retry_after:?} seconds")]
-    RateLimited { retry_after: Option<u64> },
+    RateLimited {
+        /// Seconds to wait before retrying, if known
+        retry_after: Option<u64>,
+        /// Error returned by the API, if the limit was reported in the body
+        error: Option<Box<ApiError>>,
+        /// Usage reported in the response headers
+        usage: Option<Box<UsageInfo>>,
+    },
 
     /// Invalid access token
-    #[error("Invalid or expired access token")]
-    InvalidToken,
+    #[error("Invalid or expired access token: {}", .0.message)]
+    InvalidToken(Box<ApiError>),
 
-    /// Media upload failed
+    /// Media upload was refused before sending
     #[error("Media upload failed: {0}")]
-    MediaUpload(String),
+    MediaUpload(#[from] MediaUploadError),
 
     /// Invalid phone number format
     #[error("Invalid phone number format: {0}")]
@@ -50,9 +62,274 @@ pub enum Error {
     #[error("Message not sent: {0}")]
     MessageNotSent(String),
 
+    /// Error raised by a custom HTTP transport
+    #[error("Transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+
+    /// Invalid client configuration
+    #[error("Invalid client configuration: {0}")]
+    Config(String),
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// Webhook signature verification failed
+    #[error("Webhook signature verification failed: {0}")]
+    Signature(#[from] SignatureError),
+
+    /// Template definition was refused before sending
+    #[error("Invalid template: {}", join_violations(.0))]
+    InvalidTemplate(Vec<TemplateViolation>),
+
+    /// Downloaded media does not match the size or hash reported by the API
+    #[error("Media {media_id} failed verification: {mismatch}")]
+    MediaVerification {
+        /// ID of the downloaded media
+        media_id: String,
+        /// What did not match
+        mismatch: MediaMismatch,
+    },
+
+    /// A free-form message was not sent because the customer service window
+    /// with the recipient is closed
+    #[error("Customer service window with {recipient} is closed")]
+    WindowClosed {
+        /// Recipient of the message
+        recipient: String,
+        /// When the window closed, if it was ever open
+        closed_at: Option<SystemTime>,
+    },
+
+    /// A resumable upload session stopped accepting content
+    #[error("Upload session {session_id} made no progress past offset {file_offset}")]
+    UploadStalled {
+        /// ID of the upload session
+        session_id: String,
+        /// Offset the session reported
+        file_offset: u64,
+    },
+
+    /// A message did not reach the awaited delivery state in time
+    #[error("Timed out waiting for delivery of {message_id} (state: {state:?})")]
+    DeliveryTimeout {
+        /// ID of the awaited message
+        message_id: String,
+        /// State of the message when the wait ended, if tracked
+        state: Option<DeliveryState>,
+    },
+}
+
+impl Error {
+    /// Get the error returned by the API, if any
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api(error) | Error::InvalidToken(error) => Some(error),
+            Error::RateLimited { error, .. } => error.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Get the classified WhatsApp error code, if the API returned one
+    pub fn error_code(&self) -> Option<WhatsAppErrorCode> {
+        self.api_error().map(ApiError::error_code)
+    }
+}
+
+/// Reasons a webhook signature can fail verification
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureError {
+    /// The `X-Hub-Signature-256` header was not present
+    #[error("missing X-Hub-Signature-256 header")]
+    MissingHeader,
+
+    /// The header did not start with `sha256=`
+    #[error("signature header is missing the sha256= prefix")]
+    MalformedPrefix,
+
+    /// The digest after the prefix is not 64 hex characters
+    #[error("signature digest is not valid hex-encoded SHA-256")]
+    MalformedDigest,
+
+    /// The digest does not match the payload
+    #[error("signature digest does not match payload")]
+    Mismatch,
+}
+
+/// Rules a media upload can break
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MediaUploadError {
+    /// The MIME type is not a valid header value
+    #[error("invalid MIME type: {0}")]
+    InvalidMimeType(String),
+
+    /// No media type supports the MIME type, or the requested one does not
+    #[error(
+        "MIME type {mime_type} is not supported{}",
+        .media_type.map(|t| format!(" for {}", t)).unwrap_or_default()
+    )]
+    UnsupportedMimeType {
+        /// MIME type of the upload
+        mime_type: String,
+        /// Media type the upload was checked against, if one matched
+        media_type: Option<MediaType>,
+    },
+
+    /// The content is larger than the media type allows
+    #[error("{media_type} of {size} bytes exceeds the {max_size} byte limit")]
+    TooLarge {
+        /// Media type the upload was checked against
+        media_type: MediaType,
+        /// Size of the upload
+        size: u64,
+        /// Largest size allowed for the media type
+        max_size: u64,
+    },
+
+    /// The content is not valid base64
+    #[error("invalid base64: {0}")]
+    InvalidBase64(String),
+}
+
+/// Rules a template definition can break
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateViolation {
+    /// The template has no body component
+    #[error("template has no body")]
+    MissingBody,
+
+    /// A component appears more than once
+    #[error("template has more than one {0}")]
+    DuplicateComponent(ComponentKind),
+
+    /// A text is longer than its component allows
+    #[error("{component} text is {length} characters long, over the {max} character limit")]
+    TextTooLong {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Length of the text in characters
+        length: usize,
+        /// Longest text allowed
+        max: usize,
+    },
+
+    /// A text has more placeholders than its component allows
+    #[error("{component} has {count} placeholders, more than the {max} allowed")]
+    TooManyPlaceholders {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Distinct placeholders in the text
+        count: usize,
+        /// Most placeholders allowed
+        max: usize,
+    },
+
+    /// A placeholder is neither a number nor a valid parameter name
+    #[error("{component} placeholder {{{{{placeholder}}}}} is not valid")]
+    InvalidPlaceholder {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Content of the placeholder
+        placeholder: String,
+    },
+
+    /// A placeholder does not follow the template's parameter format
+    #[error("{component} placeholder {{{{{placeholder}}}}} is not {format}")]
+    ParameterFormatMismatch {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Content of the placeholder
+        placeholder: String,
+        /// Parameter format of the template
+        format: ParameterFormat,
+    },
+
+    /// Positional placeholders are not numbered from `{{1}}` without gaps
+    #[error("{component} placeholders are not numbered from {{{{1}}}} without gaps")]
+    PlaceholderSequence {
+        /// Component holding the text
+        component: ComponentKind,
+    },
+
+    /// The number of examples differs from the number of positional placeholders
+    #[error("{component} has {placeholders} placeholders but {examples} examples")]
+    ExampleCountMismatch {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Distinct placeholders in the text
+        placeholders: usize,
+        /// Examples given
+        examples: usize,
+    },
+
+    /// A named placeholder has no example
+    #[error("{component} has no example for {{{{{name}}}}}")]
+    MissingExample {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Name of the parameter
+        name: String,
+    },
+
+    /// An example names a parameter the text does not use
+    #[error("{component} has an example for {name}, which it does not use")]
+    UnusedExample {
+        /// Component holding the text
+        component: ComponentKind,
+        /// Name of the parameter
+        name: String,
+    },
+
+    /// A media header has no sample handle
+    #[error("{} header has no sample handle", .format.as_str())]
+    MissingHeaderSample {
+        /// Header format
+        format: HeaderFormat,
+    },
+
+    /// There are more buttons, or buttons of a type, than allowed
+    #[error(
+        "template has {count} {}buttons, more than the {max} allowed",
+        .button_type.as_ref().map(|t| format!("{} ", t)).unwrap_or_default()
+    )]
+    TooManyButtons {
+        /// Type of the buttons counted, `None` for all buttons
+        button_type: Option<ButtonType>,
+        /// Buttons of the type
+        count: usize,
+        /// Most buttons allowed
+        max: usize,
+    },
+}
+
+fn join_violations(violations: &[TemplateViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Ways downloaded media can differ from its metadata
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MediaMismatch {
+    /// The content is not `file_size` bytes long
+    #[error("expected {expected} bytes, got {actual}")]
+    Size {
+        /// Size reported by the API
+        expected: u64,
+        /// Bytes received, counted up to the first byte past `expected`
+        actual: u64,
+    },
+
+    /// The SHA-256 of the content differs from `sha256`
+    #[error("expected SHA-256 {expected}, got {actual}")]
+    Sha256 {
+        /// Hex-encoded hash reported by the API
+        expected: String,
+        /// Hex-encoded hash of the content received
+        actual: String,
+    },
 }
 
 /// Additional error data from the API
@@ -93,20 +370,231 @@ pub struct ApiError {
     pub error_data: Option<ApiErrorData>,
 }
 
+impl ApiError {
+    /// Classify the error code
+    pub fn error_code(&self) -> WhatsAppErrorCode {
+        WhatsAppErrorCode::from_code(self.code)
+    }
+}
+
 impl From<ApiErrorResponse> for Error {
     fn from(response: ApiErrorResponse) -> Self {
-        let err = response.error;
-
-        // Check for specific error codes
-        match err.code {
-            190 => Error::InvalidToken,
-            4 | 17 | 32 | 613 => Error::RateLimited { retry_after: None },
-            _ => Error::Api {
-                code: err.code,
-                message: err.message,
-                error_subcode: err.error_subcode,
-                error_data: err.error_data,
+        let err = Box::new(response.error);
+
+        match err.error_code() {
+            WhatsAppErrorCode::AccessTokenExpired => Error::InvalidToken(err),
+            code if code.category() == ErrorCategory::Throttling => Error::RateLimited {
+                retry_after: None,
+                error: Some(err),
+                usage: None,
             },
+            _ => Error::Api(err),
         }
     }
 }
+
+/// Broad category of a WhatsApp Cloud API error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Access token or permission problems
+    Authorization,
+    /// Rate limits and throttling
+    Throttling,
+    /// Account restrictions due to policy violations
+    Integrity,
+    /// Invalid or missing request parameters
+    Parameter,
+    /// The message could not be delivered or processed
+    Message,
+    /// Message template problems
+    Template,
+    /// Phone number registration problems
+    Registration,
+    /// Temporary server-side problems
+    Server,
+    /// Codes not known to this crate
+    Unknown,
+}
+
+macro_rules! whatsapp_error_codes {
+    ($($(#[$doc:meta])* $variant:ident = $code:literal, $category:ident, $retryable:literal;)*) => {
+        /// Documented WhatsApp Cloud API error codes
+        ///
+        /// Each code carries its [`ErrorCategory`] and whether retrying the
+        /// request later may succeed. Codes this crate does not know about are
+        /// kept as [`WhatsAppErrorCode::Unknown`].
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum WhatsAppErrorCode {
+            $($(#[$doc])* $variant,)*
+            /// API permission denied (codes 200-299)
+            ApiPermission(i32),
+            /// Code not known to this crate
+            Unknown(i32),
+        }
+
+        impl WhatsAppErrorCode {
+            /// Classify a numeric error code
+            pub fn from_code(code: i32) -> Self {
+                match code {
+                    $($code => WhatsAppErrorCode::$variant,)*
+                    200..=299 => WhatsAppErrorCode::ApiPermission(code),
+                    _ => WhatsAppErrorCode::Unknown(code),
+                }
+            }
+
+            /// Numeric error code
+            pub fn code(&self) -> i32 {
+                match self {
+                    $(WhatsAppErrorCode::$variant => $code,)*
+                    WhatsAppErrorCode::ApiPermission(code) | WhatsAppErrorCode::Unknown(code) => {
+                        *code
+                    }
+                }
+            }
+
+            /// Category of the error
+            pub fn category(&self) -> ErrorCategory {
+                match self {
+                    $(WhatsAppErrorCode::$variant => ErrorCategory::$category,)*
+                    WhatsAppErrorCode::ApiPermission(_) => ErrorCategory::Authorization,
+                    WhatsAppErrorCode::Unknown(_) => ErrorCategory::Unknown,
+                }
+            }
+
+            /// Whether the same request may succeed if retried later
+            ///
+            /// `false` means the error is permanent: the request, template or
+            /// account has to change first.
+            pub fn is_retryable(&self) -> bool {
+                match self {
+                    $(WhatsAppErrorCode::$variant => $retryable,)*
+                    WhatsAppErrorCode::ApiPermission(_) | WhatsAppErrorCode::Unknown(_) => false,
+                }
+            }
+        }
+    };
+}
+
+whatsapp_error_codes! {
+    /// Unable to authenticate the app user
+    AuthException = 0, Authorization, false;
+    /// Capability or permissions issue
+    ApiMethod = 3, Authorization, false;
+    /// Permission not granted or removed
+    PermissionDenied = 10, Authorization, false;
+    /// Access token expired or invalidated
+    AccessTokenExpired = 190, Authorization, false;
+
+    /// App reached its API call rate limit
+    ApiTooManyCalls = 4, Throttling, true;
+    /// User reached its API call rate limit
+    UserRequestLimitReached = 17, Throttling, true;
+    /// Page reached its API call rate limit
+    PageRequestLimitReached = 32, Throttling, true;
+    /// Calls to this API exceeded the rate limit
+    RateLimitExceeded = 613, Throttling, true;
+    /// WhatsApp Business Account reached its rate limit
+    RateLimitIssues = 80007, Throttling, true;
+    /// Cloud API message throughput reached
+    RateLimitHit = 130429, Throttling, true;
+    /// Too many messages sent from this number
+    SpamRateLimitHit = 131048, Throttling, true;
+    /// Too many messages sent to the same user
+    PairRateLimitHit = 131056, Throttling, true;
+
+    /// Temporarily blocked for policy violations
+    TemporarilyBlocked = 368, Integrity, false;
+    /// Account restricted from messaging users in this country
+    RestrictedCountry = 130497, Integrity, false;
+    /// Account locked for policy violations
+    AccountLocked = 131031, Integrity, false;
+
+    /// Parameter value is not valid
+    InvalidParameterValue = 33, Parameter, false;
+    /// Invalid parameter
+    InvalidParameter = 100, Parameter, false;
+    /// Required parameter is missing
+    RequiredParameterMissing = 131008, Parameter, false;
+    /// Parameter value is not valid
+    ParameterValueNotValid = 131009, Parameter, false;
+    /// Recipient cannot be the sender
+    RecipientCannotBeSender = 131021, Parameter, false;
+    /// Recipient is not in the allowed list of the test number
+    RecipientNotAllowed = 131030, Parameter, false;
+    /// Message type is not supported
+    UnsupportedMessageType = 131051, Parameter, false;
+
+    /// Access denied
+    AccessDenied = 131005, Authorization, false;
+    /// Display name must be approved before sending
+    DisplayNameApprovalRequired = 131037, Authorization, false;
+    /// Business eligibility or payment issue
+    BusinessPaymentIssue = 131042, Authorization, false;
+    /// Incorrect registration certificate
+    IncorrectCertificate = 131045, Registration, false;
+
+    /// User's number is part of an experiment
+    Experiment = 130472, Message, false;
+    /// Message undeliverable
+    MessageUndeliverable = 131026, Message, false;
+    /// More than 24 hours since the user last replied
+    ReEngagementRequired = 131047, Message, false;
+    /// Message not delivered to maintain a healthy ecosystem
+    EcosystemEngagement = 131049, Message, false;
+    /// User stopped receiving marketing messages
+    MarketingOptOut = 131050, Message, false;
+    /// Media sent by the user could not be downloaded
+    MediaDownloadError = 131052, Message, false;
+    /// Media could not be uploaded
+    MediaUploadError = 131053, Message, false;
+
+    /// Number of template parameters does not match
+    TemplateParamCountMismatch = 132000, Template, false;
+    /// Template does not exist in this language or is not approved
+    TemplateDoesNotExist = 132001, Template, false;
+    /// Translated template text is too long
+    TemplateHydratedTextTooLong = 132005, Template, false;
+    /// Template content violates a WhatsApp policy
+    TemplateFormatPolicyViolated = 132007, Template, false;
+    /// Template parameter has the wrong format
+    TemplateParamFormatMismatch = 132012, Template, false;
+    /// Template is paused due to low quality
+    TemplatePaused = 132015, Template, false;
+    /// Template is permanently disabled
+    TemplateDisabled = 132016, Template, false;
+    /// Flow is blocked
+    FlowBlocked = 132068, Template, false;
+    /// Flow is throttled
+    FlowThrottled = 132069, Template, true;
+
+    /// Previous deregistration did not complete
+    IncompleteDeregistration = 133000, Registration, false;
+    /// Registration server temporarily unavailable
+    RegistrationServerUnavailable = 133004, Registration, true;
+    /// Two-step verification PIN is incorrect
+    TwoStepPinMismatch = 133005, Registration, false;
+    /// Phone number must be verified before registering
+    ReverificationNeeded = 133006, Registration, false;
+    /// Too many two-step verification PIN guesses
+    TooManyPinGuesses = 133008, Registration, true;
+    /// Two-step verification PIN entered too fast
+    PinGuessedTooFast = 133009, Registration, true;
+    /// Phone number is not registered
+    PhoneNumberNotRegistered = 133010, Registration, false;
+    /// Number was recently deleted; wait before registering
+    RegistrationTooSoon = 133015, Registration, true;
+    /// Too many register or deregister attempts
+    RegistrationRateLimitExceeded = 133016, Registration, true;
+
+    /// Unknown API error
+    ApiUnknown = 1, Server, true;
+    /// Temporary service error
+    ApiService = 2, Server, true;
+    /// Something went wrong
+    SomethingWentWrong = 131000, Server, true;
+    /// Service temporarily unavailable
+    ServiceUnavailable = 131016, Server, true;
+    /// Business account is in maintenance mode
+    AccountInMaintenanceMode = 131057, Server, true;
+}
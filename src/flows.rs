@@ -2,7 +2,9 @@
 
 use crate::client::Client;
 use crate::error::Result;
-use crate::types::MessageResponse;
+use crate::pagination::{PageParams, Paginator};
+use crate::transport::Multipart;
+use crate::types::{MessageResponse, Paging};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -78,8 +80,7 @@ impl FlowsApi {
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.client.send_message(to, true, &body).await
     }
 
     /// List flows for the WABA
@@ -92,6 +93,21 @@ impl FlowsApi {
         self.client.get(&url).await
     }
 
+    /// Stream all flows, following paging cursors
+    ///
+    /// # Arguments
+    ///
+    /// * `waba_id` - WhatsApp Business Account ID
+    /// * `params` - Page size and starting cursor
+    pub fn list_flows_paginated(
+        &self,
+        waba_id: &str,
+        params: PageParams,
+    ) -> Paginator<FlowsListResponse> {
+        let url = self.client.endpoint_url(&format!("{}/flows", waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get flow details
     ///
     /// # Arguments
@@ -135,9 +151,11 @@ impl FlowsApi {
         flow_id: &str,
         flow_json: &str,
     ) -> Result<UpdateFlowResponse> {
-        let form = reqwest::multipart::Form::new()
-            .text("name", "flow.json")
-            .text("file", flow_json.to_string());
+        let form = || {
+            Ok(Multipart::new()
+                .text("name", "flow.json")
+                .text("file", flow_json))
+        };
 
         let url = self.client.endpoint_url(&format!("{}/assets", flow_id));
         self.client.post_form(&url, form).await
@@ -416,22 +434,3 @@ pub struct FlowPreviewResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<String>,
 }
-
-/// Paging info
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Paging {
-    /// Cursors
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cursors: Option<PagingCursors>,
-}
-
-/// Paging cursors
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PagingCursors {
-    /// Before cursor
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub before: Option<String>,
-    /// After cursor
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub after: Option<String>,
-}
@@ -9,6 +9,7 @@
 //! - **Messages**: Send text, media, templates, interactive messages, and more
 //! - **Media**: Upload, download, and manage media files
 //! - **Templates**: Work with message templates
+//! - **Resumable Uploads**: Template header samples and large files
 //! - **Phone Numbers**: Manage business phone numbers
 //! - **Products**: Catalog and product messages
 //! - **Flows**: WhatsApp Flows support
@@ -41,19 +42,28 @@
 pub mod analytics;
 pub mod block;
 pub mod client;
+pub mod conversation;
+pub mod delivery;
 pub mod error;
 pub mod flows;
 pub mod media;
+pub mod media_cache;
 pub mod messages;
+pub mod pagination;
 pub mod phone_numbers;
 pub mod products;
 pub mod qr_codes;
+pub mod rate_limit;
+pub mod retry;
 pub mod templates;
+pub mod transport;
 pub mod types;
 pub mod typing;
+pub mod uploads;
+pub mod usage;
 pub mod waba;
 pub mod webhooks;
 pub mod webhooks_management;
 
-pub use client::Client;
+pub use client::{Client, ClientBuilder};
 pub use error::{Error, Result};
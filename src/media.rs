@@ -1,10 +1,21 @@
 //! Media API for uploading and managing media files
 
 use crate::client::Client;
-use crate::error::{Error, Result};
-use reqwest::multipart::{Form, Part};
+use crate::error::{Error, MediaMismatch, MediaUploadError, Result};
+use crate::transport::{Body, Multipart};
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+use futures_util::stream::{BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, ReadBuf};
+
+/// Most memory [`MediaDownload::bytes`] reserves up front, in bytes
+const MAX_PREALLOCATION: usize = 16 * 1024 * 1024;
 
 /// Media API client
 pub struct MediaApi {
@@ -18,6 +29,12 @@ impl MediaApi {
 
     /// Upload media from a file path
     ///
+    /// The file is streamed, not read into memory. Its MIME type is guessed
+    /// from the extension and checked, with its size, against the
+    /// [`MediaType`] limits before anything is sent.
+    /// With a [media cache](crate::media_cache), the media ID of the same
+    /// content uploaded before is reused.
+    ///
     /// # Arguments
     ///
     /// * `file_path` - Path to the file to upload
@@ -45,17 +62,87 @@ impl MediaApi {
             .unwrap_or("file")
             .to_string();
 
-        let mime_type = mime_guess::from_path(path)
-            .first_or_octet_stream()
-            .to_string();
+        let mime_type = guess_mime_type(path);
 
-        let file_bytes = tokio::fs::read(path).await?;
+        let size = tokio::fs::metadata(path).await?.len();
+        MediaType::check(&mime_type, size)?;
 
-        self.upload_bytes(&file_bytes, &file_name, &mime_type).await
+        let sha256 = match self.client.media_cache() {
+            Some(cache) => {
+                let sha256 = file_sha256(path).await?;
+                if let Some(id) = cache.media_id(&sha256).await? {
+                    return Ok(MediaUploadResponse { id });
+                }
+                Some(sha256)
+            }
+            None => None,
+        };
+
+        // Reopened for every attempt, since a streamed body is consumed
+        let form = || {
+            let file = tokio::fs::File::from_std(std::fs::File::open(path)?);
+            Multipart::new()
+                .text("messaging_product", "whatsapp")
+                .text("type", &mime_type)
+                .file(
+                    "file",
+                    &file_name,
+                    &mime_type,
+                    Body::from_reader(file.take(size)),
+                )
+        };
+
+        let url = format!("{}/media", self.client.base_url());
+        let response: MediaUploadResponse = self.client.post_form(&url, form).await?;
+        self.record_upload(sha256.as_deref(), &response).await;
+        Ok(response)
+    }
+
+    /// Upload media from an [`AsyncRead`]
+    ///
+    /// `size` is checked against the [`MediaType`] limits before anything
+    /// is sent, and no more than `size` bytes are read. The reader can only
+    /// be consumed once, so the upload is not retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - Source of the file content
+    /// * `size` - Length of the file content in bytes
+    /// * `filename` - Name of the file
+    /// * `mime_type` - MIME type of the file
+    pub async fn upload_reader<R>(
+        &self,
+        reader: R,
+        size: u64,
+        filename: &str,
+        mime_type: &str,
+    ) -> Result<MediaUploadResponse>
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        MediaType::check(mime_type, size)?;
+
+        let form = Multipart::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", mime_type)
+            .file(
+                "file",
+                filename,
+                mime_type,
+                Body::from_reader(reader.take(size)),
+            )?;
+
+        let url = format!("{}/media", self.client.base_url());
+        self.client.post_form_once(&url, form).await
     }
 
     /// Upload media from bytes
     ///
+    /// The MIME type and size are checked against the [`MediaType`] limits
+    /// before anything is sent.
+    /// With a [media cache](crate::media_cache), the media ID of the same
+    /// content uploaded before is reused.
+    ///
     /// # Arguments
     ///
     /// * `data` - File content as bytes
@@ -67,18 +154,41 @@ impl MediaApi {
         filename: &str,
         mime_type: &str,
     ) -> Result<MediaUploadResponse> {
-        let file_part = Part::bytes(data.to_vec())
-            .file_name(filename.to_string())
-            .mime_str(mime_type)
-            .map_err(|e| Error::MediaUpload(e.to_string()))?;
+        MediaType::check(mime_type, data.len() as u64)?;
 
-        let form = Form::new()
-            .text("messaging_product", "whatsapp")
-            .text("type", mime_type.to_string())
-            .part("file", file_part);
+        let sha256 = match self.client.media_cache() {
+            Some(cache) => {
+                let sha256 = encode_hex(&Sha256::digest(data));
+                if let Some(id) = cache.media_id(&sha256).await? {
+                    return Ok(MediaUploadResponse { id });
+                }
+                Some(sha256)
+            }
+            None => None,
+        };
+
+        let data = Bytes::copy_from_slice(data);
+        let form = || {
+            Multipart::new()
+                .text("messaging_product", "whatsapp")
+                .text("type", mime_type)
+                .file("file", filename, mime_type, Body::Bytes(data.clone()))
+        };
 
         let url = format!("{}/media", self.client.base_url());
-        self.client.post_form(&url, form).await
+        let response: MediaUploadResponse = self.client.post_form(&url, form).await?;
+        self.record_upload(sha256.as_deref(), &response).await;
+        Ok(response)
+    }
+
+    /// Remember an uploaded media ID in the media cache, if one is configured
+    ///
+    /// The upload already succeeded, so a failing cache is ignored rather
+    /// than losing the media ID.
+    async fn record_upload(&self, sha256: Option<&str>, response: &MediaUploadResponse) {
+        if let (Some(cache), Some(sha256)) = (self.client.media_cache(), sha256) {
+            let _ = cache.record_upload(sha256, &response.id).await;
+        }
     }
 
     /// Upload media from base64
@@ -94,10 +204,9 @@ impl MediaApi {
         filename: &str,
         mime_type: &str,
     ) -> Result<MediaUploadResponse> {
-        use base64::Engine;
         let data = base64::engine::general_purpose::STANDARD
             .decode(base64_data)
-            .map_err(|e| Error::MediaUpload(format!("Invalid base64: {}", e)))?;
+            .map_err(|e| MediaUploadError::InvalidBase64(e.to_string()))?;
 
         self.upload_bytes(&data, filename, mime_type).await
     }
@@ -118,6 +227,11 @@ impl MediaApi {
 
     /// Download media by media ID
     ///
+    /// The content is checked against the size and SHA-256 reported by
+    /// [`get_url`](Self::get_url); a mismatch fails with
+    /// [`Error::MediaVerification`]. With a [media cache](crate::media_cache),
+    /// media downloaded before is served from the cache.
+    ///
     /// # Arguments
     ///
     /// * `media_id` - The media ID
@@ -126,39 +240,305 @@ impl MediaApi {
     ///
     /// Returns the media content as bytes
     pub async fn download(&self, media_id: &str) -> Result<Vec<u8>> {
-        // First get the media URL
-        let media_info = self.get_url(media_id).await?;
+        let cache = self.client.media_cache();
+        if let Some(cache) = cache {
+            if let Some(content) = cache.content(media_id).await? {
+                return Ok(content.to_vec());
+            }
+        }
 
-        // Then download the actual content
-        let response = self
-            .client
-            .http_client()
-            .get(&media_info.url)
-            .header("Authorization", format!("Bearer {}", self.get_token()))
-            .send()
-            .await?;
+        let content = self.download_stream(media_id).await?.bytes().await?;
+        if let Some(cache) = cache {
+            // The content is already here; a failing cache must not lose it
+            let _ = cache.record_download(media_id, &content).await;
+        }
+        Ok(content.to_vec())
+    }
 
-        if !response.status().is_success() {
-            return Err(Error::MediaUpload(format!(
-                "Failed to download media: {}",
-                response.status()
-            )));
+    /// Download media by media ID without buffering it
+    ///
+    /// The returned [`MediaDownload`] is a [`Stream`] of chunks and an
+    /// [`AsyncRead`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use wacloudapi::Client;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use futures_util::StreamExt;
+    ///
+    /// let client = Client::new("token", "phone_id");
+    /// let mut download = client.media().download_stream("media_id").await?;
+    /// println!("{} bytes of {}", download.info().file_size, download.info().mime_type);
+    ///
+    /// while let Some(chunk) = download.next().await {
+    ///     let chunk = chunk?;
+    ///     // ...
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn download_stream(&self, media_id: &str) -> Result<MediaDownload> {
+        let info = self.get_url(media_id).await?;
+        self.download_stream_from(info).await
+    }
+
+    /// Download media whose URL was already looked up with
+    /// [`get_url`](Self::get_url)
+    ///
+    /// The URL is valid for 5 minutes after the lookup.
+    pub async fn download_stream_from(&self, info: MediaUrlResponse) -> Result<MediaDownload> {
+        let body = self.client.get_body(&info.url).await?;
+        Ok(MediaDownload::new(info, body))
+    }
+
+    /// Download media by media ID into a file
+    ///
+    /// The content is written to a `.part` file next to `path`, which is
+    /// renamed to `path` once the content is verified and removed otherwise.
+    ///
+    /// # Returns
+    ///
+    /// Returns the media info, including its MIME type
+    pub async fn download_to_file(
+        &self,
+        media_id: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<MediaUrlResponse> {
+        let path = path.as_ref();
+        let mut download = self.download_stream(media_id).await?;
+
+        let mut part = path.as_os_str().to_owned();
+        part.push(".part");
+        let part = PathBuf::from(part);
+
+        let result = async {
+            let mut file = tokio::fs::File::create(&part).await?;
+            while let Some(chunk) = download.next().await {
+                file.write_all(&chunk?).await?;
+            }
+            file.flush().await?;
+            tokio::fs::rename(&part, path).await?;
+            Ok(())
         }
+        .await;
 
-        Ok(response.bytes().await?.to_vec())
+        match result {
+            Ok(()) => Ok(download.info),
+            Err(error) => {
+                let _ = tokio::fs::remove_file(&part).await;
+                Err(error)
+            }
+        }
     }
 
     /// Delete media by media ID
+    ///
+    /// The media is also removed from the media cache, if one is configured.
     pub async fn delete(&self, media_id: &str) -> Result<crate::types::SuccessResponse> {
         let url = self.client.endpoint_url(media_id);
-        self.client.delete(&url).await
+        let response = self.client.delete(&url).await?;
+        if let Some(cache) = self.client.media_cache() {
+            // The media is deleted either way; a stale entry is only a cache miss
+            let _ = cache.remove(media_id).await;
+        }
+        Ok(response)
     }
+}
 
-    // Helper to get token from client (we need to expose this somehow)
-    fn get_token(&self) -> String {
-        // This is a workaround - ideally we'd have a better way to access the token
-        // For now, we'll use a placeholder that should be set via the client
-        String::new()
+/// Media content being downloaded
+///
+/// Yields the content in chunks as a [`Stream`], or through [`AsyncRead`].
+/// The size and SHA-256 are checked as the content arrives: once all of it
+/// was yielded, a stream whose content does not match [`info`](Self::info)
+/// ends with [`Error::MediaVerification`]. Content longer than `file_size`
+/// fails as soon as the extra bytes arrive. When reading, the error is
+/// returned as an [`std::io::Error`] of kind `InvalidData` wrapping it.
+pub struct MediaDownload {
+    info: MediaUrlResponse,
+    body: BoxStream<'static, std::io::Result<Bytes>>,
+    /// Hash and size of the content so far, `None` once the stream ended
+    verifier: Option<(Sha256, u64)>,
+    /// Chunk partly consumed by `poll_read`
+    pending: Bytes,
+}
+
+impl MediaDownload {
+    fn new(info: MediaUrlResponse, body: Body) -> Self {
+        Self {
+            info,
+            body: body.into_stream(),
+            verifier: Some((Sha256::new(), 0)),
+            pending: Bytes::new(),
+        }
+    }
+
+    /// Media info the content is checked against
+    pub fn info(&self) -> &MediaUrlResponse {
+        &self.info
+    }
+
+    /// Read the whole verified content into memory
+    pub async fn bytes(mut self) -> Result<Bytes> {
+        // The reported size is only a hint; don't let it reserve unbounded memory
+        let capacity = usize::try_from(self.info.file_size)
+            .unwrap_or(0)
+            .min(MAX_PREALLOCATION);
+        let mut buf = BytesMut::with_capacity(capacity);
+        while let Some(chunk) = self.next().await {
+            buf.extend_from_slice(&chunk?);
+        }
+        Ok(buf.freeze())
+    }
+
+    fn mismatch(&self, mismatch: MediaMismatch) -> Error {
+        Error::MediaVerification {
+            media_id: self.info.id.clone(),
+            mismatch,
+        }
+    }
+
+    fn verify(&self, hasher: Sha256, size: u64) -> Result<()> {
+        if let Ok(expected) = u64::try_from(self.info.file_size) {
+            if size != expected {
+                return Err(self.mismatch(MediaMismatch::Size {
+                    expected,
+                    actual: size,
+                }));
+            }
+        }
+
+        let expected = self.info.sha256.trim();
+        if expected.is_empty() {
+            return Ok(());
+        }
+        let digest = hasher.finalize();
+        let actual = encode_hex(&digest);
+        // Hex in media URL responses, base64 in webhooks
+        if expected.eq_ignore_ascii_case(&actual)
+            || expected == base64::engine::general_purpose::STANDARD.encode(digest)
+        {
+            return Ok(());
+        }
+        Err(self.mismatch(MediaMismatch::Sha256 {
+            expected: expected.to_string(),
+            actual,
+        }))
+    }
+}
+
+impl Stream for MediaDownload {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.verifier.is_none() {
+            return Poll::Ready(None);
+        }
+
+        match ready!(this.body.poll_next_unpin(cx)) {
+            Some(Ok(chunk)) => {
+                let (hasher, size) = this.verifier.as_mut().expect("checked above");
+                *size += chunk.len() as u64;
+                hasher.update(&chunk);
+
+                let size = *size;
+                match u64::try_from(this.info.file_size) {
+                    Ok(expected) if size > expected => {
+                        this.verifier = None;
+                        Poll::Ready(Some(Err(this.mismatch(MediaMismatch::Size {
+                            expected,
+                            actual: size,
+                        }))))
+                    }
+                    _ => Poll::Ready(Some(Ok(chunk))),
+                }
+            }
+            Some(Err(e)) => {
+                this.verifier = None;
+                Poll::Ready(Some(Err(Error::Io(e))))
+            }
+            None => {
+                let (hasher, size) = this.verifier.take().expect("checked above");
+                Poll::Ready(this.verify(hasher, size).err().map(Err))
+            }
+        }
+    }
+}
+
+impl AsyncRead for MediaDownload {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        while self.pending.is_empty() {
+            match ready!(self.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => self.pending = chunk,
+                Some(Err(Error::Io(e))) => return Poll::Ready(Err(e)),
+                Some(Err(e)) => {
+                    return Poll::Ready(Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        e,
+                    )))
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let len = buf.remaining().min(self.pending.len());
+        buf.put_slice(&self.pending.split_to(len));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl std::fmt::Debug for MediaDownload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaDownload")
+            .field("info", &self.info)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(&mut hex, "{:02x}", byte).unwrap();
+    }
+    hex
+}
+
+/// MIME type of a file from its extension
+///
+/// `mime_guess` does not know `.amr` and maps `.m4a` to `audio/m4a`, neither
+/// of which WhatsApp accepts, so its audio extensions are mapped explicitly.
+fn guess_mime_type(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("amr") => "audio/amr".to_string(),
+        Some("m4a") => "audio/mp4".to_string(),
+        Some("opus") => "audio/ogg".to_string(),
+        _ => mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string(),
+    }
+}
+
+/// Hex-encoded SHA-256 of a file, read in chunks
+async fn file_sha256(path: &Path) -> Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buf).await? {
+            0 => return Ok(encode_hex(&hasher.finalize())),
+            n => hasher.update(&buf[..n]),
+        }
     }
 }
 
@@ -187,7 +567,7 @@ pub struct MediaUrlResponse {
 }
 
 /// Supported media types for upload
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MediaType {
     /// Audio files (mp3, ogg, amr, etc.)
     Audio,
@@ -229,8 +609,61 @@ impl MediaType {
     }
 
     /// Check if a MIME type is supported for this media type
+    ///
+    /// Parameters such as `; codecs=opus` are ignored.
     pub fn is_mime_supported(&self, mime_type: &str) -> bool {
-        self.supported_mime_types().contains(&mime_type)
+        let essence = mime_type.split(';').next().unwrap_or_default().trim();
+        self.supported_mime_types()
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(essence))
+    }
+
+    /// Get the media type a MIME type is uploaded as
+    ///
+    /// `image/webp` is reported as [`MediaType::Image`]; stickers share its
+    /// MIME type but have a lower size limit.
+    pub fn from_mime(mime_type: &str) -> Option<Self> {
+        [
+            MediaType::Image,
+            MediaType::Video,
+            MediaType::Audio,
+            MediaType::Document,
+        ]
+        .into_iter()
+        .find(|media_type| media_type.is_mime_supported(mime_type))
+    }
+
+    /// Check that content of this type has a supported MIME type and size
+    pub fn validate(
+        &self,
+        mime_type: &str,
+        size: u64,
+    ) -> std::result::Result<(), MediaUploadError> {
+        if !self.is_mime_supported(mime_type) {
+            return Err(MediaUploadError::UnsupportedMimeType {
+                mime_type: mime_type.to_string(),
+                media_type: Some(*self),
+            });
+        }
+        if size > self.max_size() {
+            return Err(MediaUploadError::TooLarge {
+                media_type: *self,
+                size,
+                max_size: self.max_size(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate an upload against the media type of its MIME type
+    fn check(mime_type: &str, size: u64) -> std::result::Result<(), MediaUploadError> {
+        match Self::from_mime(mime_type) {
+            Some(media_type) => media_type.validate(mime_type, size),
+            None => Err(MediaUploadError::UnsupportedMimeType {
+                mime_type: mime_type.to_string(),
+                media_type: None,
+            }),
+        }
     }
 
     /// Get max file size in bytes
@@ -244,3 +677,15 @@ impl MediaType {
         }
     }
 }
+
+impl std::fmt::Display for MediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MediaType::Audio => "audio",
+            MediaType::Document => "document",
+            MediaType::Image => "image",
+            MediaType::Sticker => "sticker",
+            MediaType::Video => "video",
+        })
+    }
+}
@@ -0,0 +1,515 @@
+//! Local cache of uploaded media IDs and downloaded media
+//!
+//! Uploaded media IDs stay valid for 30 days, so uploading the same file
+//! again is wasted. A [`MediaCache`] remembers the media ID uploaded for each
+//! content SHA-256 and the content downloaded for each media ID, in a
+//! pluggable [`MediaStore`]. Attached to a client with
+//! [`Client::with_media_cache`], it lets `upload_file`, `upload_bytes` and
+//! `upload_base64` reuse an unexpired media ID for the same content, and
+//! `download` serve media it already holds without calling the API.
+//!
+//! Media IDs belong to the phone number that uploaded them, so a store
+//! should not be shared between clients of different phone numbers.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wacloudapi::media_cache::{FileMediaStore, MediaCache};
+//! use wacloudapi::Client;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let cache = MediaCache::new(FileMediaStore::open("./media-cache")?);
+//! let client = Client::new("token", "phone_id").with_media_cache(cache);
+//!
+//! // Uploaded once, then reused until the media ID expires
+//! let first = client.media().upload_file("./logo.png").await?;
+//! let second = client.media().upload_file("./logo.png").await?;
+//! assert_eq!(first.id, second.id);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Client::with_media_cache`]: crate::Client::with_media_cache
+
+use crate::error::Result;
+use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long an uploaded media ID is reused by default
+///
+/// A day short of the 30 days media IDs stay valid, so a reused ID does not
+/// expire before the message using it is sent.
+pub const DEFAULT_UPLOAD_TTL: Duration = Duration::from_secs(29 * 24 * 60 * 60);
+
+/// Longest [`MediaCache::upload_ttl`], far beyond any media ID's lifetime
+const MAX_UPLOAD_TTL: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+
+/// Suffix of files being written by [`FileMediaStore`]
+const TMP_SUFFIX: &str = ".tmp";
+
+/// Downloaded content held by [`MemoryMediaStore::new`], in bytes
+pub const DEFAULT_CONTENT_CAPACITY: usize = 64 * 1024 * 1024;
+
+/// Future returned by [`MediaStore`] methods
+pub type MediaStoreFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Holds uploaded media IDs and downloaded content
+///
+/// Implementations must be safe to share between concurrent requests, and
+/// must not block the async runtime: slow I/O belongs in the returned future
+/// or in [`tokio::task::spawn_blocking`].
+pub trait MediaStore: Send + Sync + 'static {
+    /// Get the media ID uploaded for content with this SHA-256, unless it expired
+    fn media_id<'a>(&'a self, sha256: &'a str) -> MediaStoreFuture<'a, Option<String>>;
+
+    /// Record the media ID uploaded for content with this SHA-256
+    fn insert_media_id<'a>(
+        &'a self,
+        sha256: &'a str,
+        media_id: &'a str,
+        expires_at: SystemTime,
+    ) -> MediaStoreFuture<'a, ()>;
+
+    /// Get the content downloaded for a media ID
+    fn content<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, Option<Bytes>>;
+
+    /// Record the content downloaded for a media ID
+    fn insert_content<'a>(&'a self, media_id: &'a str, content: Bytes) -> MediaStoreFuture<'a, ()>;
+
+    /// Forget a media ID and its content, after it was deleted
+    fn remove<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, ()>;
+}
+
+/// Cache consulted by [`MediaApi`](crate::media::MediaApi)
+///
+/// Cloning is cheap and clones share their store.
+#[derive(Clone)]
+pub struct MediaCache {
+    store: Arc<dyn MediaStore>,
+    upload_ttl: Duration,
+}
+
+impl MediaCache {
+    /// Create a cache backed by `store`
+    pub fn new(store: impl MediaStore) -> Self {
+        Self {
+            store: Arc::new(store),
+            upload_ttl: DEFAULT_UPLOAD_TTL,
+        }
+    }
+
+    /// Set how long an uploaded media ID is reused
+    ///
+    /// Defaults to [`DEFAULT_UPLOAD_TTL`]; values over a century are capped.
+    pub fn upload_ttl(mut self, ttl: Duration) -> Self {
+        self.upload_ttl = ttl.min(MAX_UPLOAD_TTL);
+        self
+    }
+
+    /// Get the store backing this cache
+    pub fn store(&self) -> &dyn MediaStore {
+        self.store.as_ref()
+    }
+
+    /// Get the reusable media ID of content with this SHA-256
+    pub async fn media_id(&self, sha256: &str) -> Result<Option<String>> {
+        self.store.media_id(sha256).await
+    }
+
+    /// Record a media ID just uploaded for content with this SHA-256
+    pub async fn record_upload(&self, sha256: &str, media_id: &str) -> Result<()> {
+        let expires_at = SystemTime::now() + self.upload_ttl;
+        self.store
+            .insert_media_id(sha256, media_id, expires_at)
+            .await
+    }
+
+    /// Get the downloaded content of a media ID
+    pub async fn content(&self, media_id: &str) -> Result<Option<Bytes>> {
+        self.store.content(media_id).await
+    }
+
+    /// Record the downloaded content of a media ID
+    pub async fn record_download(&self, media_id: &str, content: &Bytes) -> Result<()> {
+        self.store.insert_content(media_id, content.clone()).await
+    }
+
+    /// Forget a media ID and its content
+    pub async fn remove(&self, media_id: &str) -> Result<()> {
+        self.store.remove(media_id).await
+    }
+}
+
+impl std::fmt::Debug for MediaCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaCache")
+            .field("upload_ttl", &self.upload_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// In-memory media store
+///
+/// Downloaded content is bounded by size; the least recently used content is
+/// dropped first. Expired media IDs are dropped when looked up or when
+/// another media ID is added.
+#[derive(Debug)]
+pub struct MemoryMediaStore {
+    inner: Mutex<MemoryState>,
+}
+
+#[derive(Debug)]
+struct MemoryState {
+    /// Media ID and expiry by content SHA-256
+    media_ids: HashMap<String, (String, SystemTime)>,
+    /// Content and last-used sequence number by media ID
+    contents: HashMap<String, (Bytes, u64)>,
+    /// Media IDs by sequence number, least recently used first
+    order: BTreeMap<u64, String>,
+    next_seq: u64,
+    size: usize,
+    capacity: usize,
+}
+
+impl MemoryMediaStore {
+    /// Create a store holding up to [`DEFAULT_CONTENT_CAPACITY`] bytes of content
+    pub fn new() -> Self {
+        Self::with_content_capacity(DEFAULT_CONTENT_CAPACITY)
+    }
+
+    /// Create a store holding up to `capacity` bytes of content
+    ///
+    /// Content larger than `capacity` is not held.
+    pub fn with_content_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(MemoryState {
+                media_ids: HashMap::new(),
+                contents: HashMap::new(),
+                order: BTreeMap::new(),
+                next_seq: 0,
+                size: 0,
+                capacity,
+            }),
+        }
+    }
+
+    /// Bytes of content currently held
+    pub fn content_size(&self) -> usize {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).size
+    }
+}
+
+impl Default for MemoryMediaStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryState {
+    fn remove_content(&mut self, media_id: &str) {
+        if let Some((content, seq)) = self.contents.remove(media_id) {
+            self.order.remove(&seq);
+            self.size -= content.len();
+        }
+    }
+}
+
+impl MemoryMediaStore {
+    fn get_media_id(&self, sha256: &str) -> Result<Option<String>> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        match state.media_ids.get(sha256) {
+            Some((media_id, expires_at)) if *expires_at > SystemTime::now() => {
+                Ok(Some(media_id.clone()))
+            }
+            Some(_) => {
+                state.media_ids.remove(sha256);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn put_media_id(&self, sha256: &str, media_id: &str, expires_at: SystemTime) -> Result<()> {
+        let now = SystemTime::now();
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .media_ids
+            .retain(|_, (_, expires_at)| *expires_at > now);
+        state
+            .media_ids
+            .insert(sha256.to_string(), (media_id.to_string(), expires_at));
+        Ok(())
+    }
+
+    fn get_content(&self, media_id: &str) -> Result<Option<Bytes>> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let seq = state.next_seq;
+        let Some((content, last_used)) = state.contents.get_mut(media_id) else {
+            return Ok(None);
+        };
+        let content = content.clone();
+        let previous = std::mem::replace(last_used, seq);
+
+        state.order.remove(&previous);
+        state.order.insert(seq, media_id.to_string());
+        state.next_seq += 1;
+        Ok(Some(content))
+    }
+
+    fn put_content(&self, media_id: &str, content: Bytes) -> Result<()> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.remove_content(media_id);
+        if content.len() > state.capacity {
+            return Ok(());
+        }
+
+        while state.size + content.len() > state.capacity {
+            let Some((_, oldest)) = state.order.pop_first() else {
+                break;
+            };
+            state.remove_content(&oldest);
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.size += content.len();
+        state.contents.insert(media_id.to_string(), (content, seq));
+        state.order.insert(seq, media_id.to_string());
+        Ok(())
+    }
+
+    fn remove_media(&self, media_id: &str) -> Result<()> {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        state.media_ids.retain(|_, (id, _)| id != media_id);
+        state.remove_content(media_id);
+        Ok(())
+    }
+}
+
+impl MediaStore for MemoryMediaStore {
+    fn media_id<'a>(&'a self, sha256: &'a str) -> MediaStoreFuture<'a, Option<String>> {
+        Box::pin(std::future::ready(self.get_media_id(sha256)))
+    }
+
+    fn insert_media_id<'a>(
+        &'a self,
+        sha256: &'a str,
+        media_id: &'a str,
+        expires_at: SystemTime,
+    ) -> MediaStoreFuture<'a, ()> {
+        Box::pin(std::future::ready(
+            self.put_media_id(sha256, media_id, expires_at),
+        ))
+    }
+
+    fn content<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, Option<Bytes>> {
+        Box::pin(std::future::ready(self.get_content(media_id)))
+    }
+
+    fn insert_content<'a>(&'a self, media_id: &'a str, content: Bytes) -> MediaStoreFuture<'a, ()> {
+        Box::pin(std::future::ready(self.put_content(media_id, content)))
+    }
+
+    fn remove<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, ()> {
+        Box::pin(std::future::ready(self.remove_media(media_id)))
+    }
+}
+
+/// Media store in a directory, surviving restarts
+///
+/// Media IDs are kept in `ids/` and content in `content/`, one file per
+/// entry, written through a temporary file so a crash never leaves a partial
+/// entry. Content is not bounded; remove the directory to clear it.
+#[derive(Debug, Clone)]
+pub struct FileMediaStore {
+    dir: PathBuf,
+}
+
+impl FileMediaStore {
+    /// Open the store in `dir`, creating it if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join("ids"))?;
+        fs::create_dir_all(dir.join("content"))?;
+        Ok(Self { dir })
+    }
+
+    /// Directory holding the store
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn id_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join("ids").join(file_name(sha256))
+    }
+
+    fn content_path(&self, media_id: &str) -> PathBuf {
+        self.dir.join("content").join(file_name(media_id))
+    }
+
+    /// Read an ID entry as its expiry and media ID
+    fn read_id(path: &Path) -> Result<Option<(u64, String)>> {
+        let entry = match fs::read_to_string(path) {
+            Ok(entry) => entry,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(entry.split_once(' ').and_then(|(expires_at, media_id)| {
+            Some((expires_at.parse().ok()?, media_id.to_string()))
+        }))
+    }
+}
+
+impl FileMediaStore {
+    fn get_media_id(&self, sha256: &str) -> Result<Option<String>> {
+        let path = self.id_path(sha256);
+        match Self::read_id(&path)? {
+            Some((expires_at, media_id)) if expires_at > unix_secs(SystemTime::now()) => {
+                Ok(Some(media_id))
+            }
+            Some(_) => {
+                remove_file(&path)?;
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn put_media_id(&self, sha256: &str, media_id: &str, expires_at: SystemTime) -> Result<()> {
+        let entry = format!("{} {}", unix_secs(expires_at), media_id);
+        write_atomic(&self.id_path(sha256), entry.as_bytes())
+    }
+
+    fn get_content(&self, media_id: &str) -> Result<Option<Bytes>> {
+        match fs::read(self.content_path(media_id)) {
+            Ok(content) => Ok(Some(content.into())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn put_content(&self, media_id: &str, content: &[u8]) -> Result<()> {
+        write_atomic(&self.content_path(media_id), content)
+    }
+
+    fn remove_media(&self, media_id: &str) -> Result<()> {
+        remove_file(&self.content_path(media_id))?;
+        for entry in fs::read_dir(self.dir.join("ids"))? {
+            let path = entry?.path();
+            // Entries being written by another writer
+            if path.to_string_lossy().ends_with(TMP_SUFFIX) {
+                continue;
+            }
+            if matches!(Self::read_id(&path)?, Some((_, id)) if id == media_id) {
+                remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Files are read and written on the blocking thread pool, so large
+/// content does not hold up the async runtime.
+impl MediaStore for FileMediaStore {
+    fn media_id<'a>(&'a self, sha256: &'a str) -> MediaStoreFuture<'a, Option<String>> {
+        let (store, sha256) = (self.clone(), sha256.to_string());
+        Box::pin(blocking(move || store.get_media_id(&sha256)))
+    }
+
+    fn insert_media_id<'a>(
+        &'a self,
+        sha256: &'a str,
+        media_id: &'a str,
+        expires_at: SystemTime,
+    ) -> MediaStoreFuture<'a, ()> {
+        let (store, sha256, media_id) = (self.clone(), sha256.to_string(), media_id.to_string());
+        Box::pin(blocking(move || {
+            store.put_media_id(&sha256, &media_id, expires_at)
+        }))
+    }
+
+    fn content<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, Option<Bytes>> {
+        let (store, media_id) = (self.clone(), media_id.to_string());
+        Box::pin(blocking(move || store.get_content(&media_id)))
+    }
+
+    fn insert_content<'a>(&'a self, media_id: &'a str, content: Bytes) -> MediaStoreFuture<'a, ()> {
+        let (store, media_id) = (self.clone(), media_id.to_string());
+        Box::pin(blocking(move || store.put_content(&media_id, &content)))
+    }
+
+    fn remove<'a>(&'a self, media_id: &'a str) -> MediaStoreFuture<'a, ()> {
+        let (store, media_id) = (self.clone(), media_id.to_string());
+        Box::pin(blocking(move || store.remove_media(&media_id)))
+    }
+}
+
+/// Run file system work on the blocking thread pool
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// File name for a key, hex-encoded unless it is already safe
+fn file_name(key: &str) -> String {
+    let safe = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    // Distinct prefixes keep a safe key from colliding with an escaped one
+    match safe {
+        true => format!("k_{}", key),
+        false => format!("x_{}", crate::media::encode_hex(key.as_bytes())),
+    }
+}
+
+/// Write a file through a temporary file unique to this writer, so
+/// concurrent writers of the same key never share one
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp = temp_path(path);
+    let result = fs::write(&tmp, data).and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    Ok(result?)
+}
+
+/// A temporary path next to `path` that no other writer uses
+pub(crate) fn temp_path(path: &Path) -> PathBuf {
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}.{}{}",
+        std::process::id(),
+        NEXT_TMP.fetch_add(1, Ordering::Relaxed),
+        TMP_SUFFIX
+    ));
+    tmp.into()
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
@@ -15,6 +15,60 @@ impl MessagesApi {
         Self { client }
     }
 
+    /// Send any outgoing message
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Recipient's phone number (with country code, no + or spaces)
+    /// * `message` - The message to send
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use wacloudapi::Client;
+    /// use wacloudapi::messages::{MediaContent, OutgoingMessage};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new("token", "phone_id");
+    /// let message = OutgoingMessage::image(
+    ///     MediaContent::from_link("https://example.com/cat.jpg").with_caption("A cat"),
+    /// );
+    /// client.messages().send("628123456789", &message).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(&self, to: &str, message: &OutgoingMessage) -> Result<MessageResponse> {
+        self.send_with_options(to, message, SendOptions::default())
+            .await
+    }
+
+    /// Send any outgoing message with a reply context or callback data
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - Recipient's phone number (with country code, no + or spaces)
+    /// * `message` - The message to send
+    /// * `options` - Reply context and `biz_opaque_callback_data`
+    pub async fn send_with_options(
+        &self,
+        to: &str,
+        message: &OutgoingMessage,
+        options: SendOptions,
+    ) -> Result<MessageResponse> {
+        let body = SendMessageRequest {
+            messaging_product: "whatsapp",
+            recipient_type: "individual",
+            to,
+            context: options.context,
+            biz_opaque_callback_data: options.biz_opaque_callback_data,
+            message,
+        };
+
+        self.client
+            .send_message(to, message.is_free_form(), &body)
+            .await
+    }
+
     /// Send a text message
     ///
     /// # Arguments
@@ -33,36 +87,13 @@ impl MessagesApi {
     /// # }
     /// ```
     pub async fn send_text(&self, to: &str, text: &str) -> Result<MessageResponse> {
-        let body = SendTextRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "text".to_string(),
-            text: TextContent {
-                preview_url: false,
-                body: text.to_string(),
-            },
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::text(text)).await
     }
 
     /// Send a text message with URL preview
     pub async fn send_text_with_preview(&self, to: &str, text: &str) -> Result<MessageResponse> {
-        let body = SendTextRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "text".to_string(),
-            text: TextContent {
-                preview_url: true,
-                body: text.to_string(),
-            },
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::text_with_preview(text))
+            .await
     }
 
     /// Send a reply to a message
@@ -72,22 +103,12 @@ impl MessagesApi {
         text: &str,
         message_id: &str,
     ) -> Result<MessageResponse> {
-        let body = SendReplyRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            context: Context {
-                message_id: message_id.to_string(),
-            },
-            message_type: "text".to_string(),
-            text: TextContent {
-                preview_url: false,
-                body: text.to_string(),
-            },
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send_with_options(
+            to,
+            &OutgoingMessage::text(text),
+            SendOptions::new().reply_to(message_id),
+        )
+        .await
     }
 
     /// Send a reaction to a message
@@ -97,19 +118,8 @@ impl MessagesApi {
         message_id: &str,
         emoji: &str,
     ) -> Result<MessageResponse> {
-        let body = SendReactionRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "reaction".to_string(),
-            reaction: Reaction {
-                message_id: message_id.to_string(),
-                emoji: emoji.to_string(),
-            },
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::reaction(message_id, emoji))
+            .await
     }
 
     /// Remove a reaction from a message (send empty emoji)
@@ -124,25 +134,8 @@ impl MessagesApi {
         url: &str,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "image".to_string(),
-            image: Some(MediaContent {
-                id: None,
-                link: Some(url.to_string()),
-                caption: caption.map(|s| s.to_string()),
-                filename: None,
-            }),
-            video: None,
-            audio: None,
-            document: None,
-            sticker: None,
-        };
-
-        let api_url = format!("{}/messages", self.client.base_url());
-        self.client.post(&api_url, &body).await
+        let image = MediaContent::from_link(url).with_optional_caption(caption);
+        self.send(to, &OutgoingMessage::image(image)).await
     }
 
     /// Send an image by media ID
@@ -152,25 +145,8 @@ impl MessagesApi {
         media_id: &str,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "image".to_string(),
-            image: Some(MediaContent {
-                id: Some(media_id.to_string()),
-                link: None,
-                caption: caption.map(|s| s.to_string()),
-                filename: None,
-            }),
-            video: None,
-            audio: None,
-            document: None,
-            sticker: None,
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        let image = MediaContent::from_id(media_id).with_optional_caption(caption);
+        self.send(to, &OutgoingMessage::image(image)).await
     }
 
     /// Send a video by URL
@@ -180,25 +156,8 @@ impl MessagesApi {
         url: &str,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "video".to_string(),
-            image: None,
-            video: Some(MediaContent {
-                id: None,
-                link: Some(url.to_string()),
-                caption: caption.map(|s| s.to_string()),
-                filename: None,
-            }),
-            audio: None,
-            document: None,
-            sticker: None,
-        };
-
-        let api_url = format!("{}/messages", self.client.base_url());
-        self.client.post(&api_url, &body).await
+        let video = MediaContent::from_link(url).with_optional_caption(caption);
+        self.send(to, &OutgoingMessage::video(video)).await
     }
 
     /// Send a video by media ID
@@ -208,71 +167,20 @@ impl MessagesApi {
         media_id: &str,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "video".to_string(),
-            image: None,
-            video: Some(MediaContent {
-                id: Some(media_id.to_string()),
-                link: None,
-                caption: caption.map(|s| s.to_string()),
-                filename: None,
-            }),
-            audio: None,
-            document: None,
-            sticker: None,
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        let video = MediaContent::from_id(media_id).with_optional_caption(caption);
+        self.send(to, &OutgoingMessage::video(video)).await
     }
 
     /// Send an audio file by URL
     pub async fn send_audio_url(&self, to: &str, url: &str) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "audio".to_string(),
-            image: None,
-            video: None,
-            audio: Some(MediaContent {
-                id: None,
-                link: Some(url.to_string()),
-                caption: None,
-                filename: None,
-            }),
-            document: None,
-            sticker: None,
-        };
-
-        let api_url = format!("{}/messages", self.client.base_url());
-        self.client.post(&api_url, &body).await
+        self.send(to, &OutgoingMessage::audio(MediaContent::from_link(url)))
+            .await
     }
 
     /// Send an audio file by media ID
     pub async fn send_audio_id(&self, to: &str, media_id: &str) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "audio".to_string(),
-            image: None,
-            video: None,
-            audio: Some(MediaContent {
-                id: Some(media_id.to_string()),
-                link: None,
-                caption: None,
-                filename: None,
-            }),
-            document: None,
-            sticker: None,
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::audio(MediaContent::from_id(media_id)))
+            .await
     }
 
     /// Send a document by URL
@@ -283,25 +191,9 @@ impl MessagesApi {
         filename: Option<&str>,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "document".to_string(),
-            image: None,
-            video: None,
-            audio: None,
-            document: Some(MediaContent {
-                id: None,
-                link: Some(url.to_string()),
-                caption: caption.map(|s| s.to_string()),
-                filename: filename.map(|s| s.to_string()),
-            }),
-            sticker: None,
-        };
-
-        let api_url = format!("{}/messages", self.client.base_url());
-        self.client.post(&api_url, &body).await
+        let mut document = MediaContent::from_link(url).with_optional_caption(caption);
+        document.filename = filename.map(|s| s.to_string());
+        self.send(to, &OutgoingMessage::document(document)).await
     }
 
     /// Send a document by media ID
@@ -312,71 +204,24 @@ impl MessagesApi {
         filename: Option<&str>,
         caption: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "document".to_string(),
-            image: None,
-            video: None,
-            audio: None,
-            document: Some(MediaContent {
-                id: Some(media_id.to_string()),
-                link: None,
-                caption: caption.map(|s| s.to_string()),
-                filename: filename.map(|s| s.to_string()),
-            }),
-            sticker: None,
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        let mut document = MediaContent::from_id(media_id).with_optional_caption(caption);
+        document.filename = filename.map(|s| s.to_string());
+        self.send(to, &OutgoingMessage::document(document)).await
     }
 
     /// Send a sticker by URL
     pub async fn send_sticker_url(&self, to: &str, url: &str) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "sticker".to_string(),
-            image: None,
-            video: None,
-            audio: None,
-            document: None,
-            sticker: Some(MediaContent {
-                id: None,
-                link: Some(url.to_string()),
-                caption: None,
-                filename: None,
-            }),
-        };
-
-        let api_url = format!("{}/messages", self.client.base_url());
-        self.client.post(&api_url, &body).await
+        self.send(to, &OutgoingMessage::sticker(MediaContent::from_link(url)))
+            .await
     }
 
     /// Send a sticker by media ID
     pub async fn send_sticker_id(&self, to: &str, media_id: &str) -> Result<MessageResponse> {
-        let body = SendMediaRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "sticker".to_string(),
-            image: None,
-            video: None,
-            audio: None,
-            document: None,
-            sticker: Some(MediaContent {
-                id: Some(media_id.to_string()),
-                link: None,
-                caption: None,
-                filename: None,
-            }),
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(
+            to,
+            &OutgoingMessage::sticker(MediaContent::from_id(media_id)),
+        )
+        .await
     }
 
     /// Send a location message
@@ -388,35 +233,19 @@ impl MessagesApi {
         name: Option<&str>,
         address: Option<&str>,
     ) -> Result<MessageResponse> {
-        let body = SendLocationRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "location".to_string(),
-            location: Location {
-                latitude,
-                longitude,
-                name: name.map(|s| s.to_string()),
-                address: address.map(|s| s.to_string()),
-            },
+        let location = Location {
+            latitude,
+            longitude,
+            name: name.map(|s| s.to_string()),
+            address: address.map(|s| s.to_string()),
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::Location { location }).await
     }
 
     /// Send a contact message
     pub async fn send_contacts(&self, to: &str, contacts: Vec<Contact>) -> Result<MessageResponse> {
-        let body = SendContactsRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "contacts".to_string(),
-            contacts,
-        };
-
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::Contacts { contacts }).await
     }
 
     /// Send a template message
@@ -427,22 +256,15 @@ impl MessagesApi {
         language_code: &str,
         components: Option<Vec<TemplateComponent>>,
     ) -> Result<MessageResponse> {
-        let body = SendTemplateRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "template".to_string(),
-            template: Template {
-                name: template_name.to_string(),
-                language: Language {
-                    code: language_code.to_string(),
-                },
-                components,
+        let template = Template {
+            name: template_name.to_string(),
+            language: Language {
+                code: language_code.to_string(),
             },
+            components,
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::Template { template }).await
     }
 
     /// Send an interactive list message
@@ -455,38 +277,26 @@ impl MessagesApi {
         button_text: &str,
         sections: Vec<ListSection>,
     ) -> Result<MessageResponse> {
-        let body = SendInteractiveRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "interactive".to_string(),
-            interactive: Interactive {
-                interactive_type: "list".to_string(),
-                header: header.map(|h| InteractiveHeader {
-                    header_type: "text".to_string(),
-                    text: Some(h.to_string()),
-                    image: None,
-                    video: None,
-                    document: None,
-                }),
-                body: InteractiveBody {
-                    text: body_text.to_string(),
-                },
-                footer: footer.map(|f| InteractiveFooter {
-                    text: f.to_string(),
-                }),
-                action: InteractiveAction {
-                    button: Some(button_text.to_string()),
-                    buttons: None,
-                    sections: Some(sections),
-                    catalog_id: None,
-                    product_retailer_id: None,
-                },
+        let interactive = Interactive {
+            interactive_type: "list".to_string(),
+            header: header.map(InteractiveHeader::text),
+            body: InteractiveBody {
+                text: body_text.to_string(),
+            },
+            footer: footer.map(|f| InteractiveFooter {
+                text: f.to_string(),
+            }),
+            action: InteractiveAction {
+                button: Some(button_text.to_string()),
+                buttons: None,
+                sections: Some(sections),
+                catalog_id: None,
+                product_retailer_id: None,
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::interactive(interactive))
+            .await
     }
 
     /// Send an interactive button message
@@ -498,38 +308,26 @@ impl MessagesApi {
         footer: Option<&str>,
         buttons: Vec<Button>,
     ) -> Result<MessageResponse> {
-        let body = SendInteractiveRequest {
-            messaging_product: "whatsapp".to_string(),
-            recipient_type: "individual".to_string(),
-            to: to.to_string(),
-            message_type: "interactive".to_string(),
-            interactive: Interactive {
-                interactive_type: "button".to_string(),
-                header: header.map(|h| InteractiveHeader {
-                    header_type: "text".to_string(),
-                    text: Some(h.to_string()),
-                    image: None,
-                    video: None,
-                    document: None,
-                }),
-                body: InteractiveBody {
-                    text: body_text.to_string(),
-                },
-                footer: footer.map(|f| InteractiveFooter {
-                    text: f.to_string(),
-                }),
-                action: InteractiveAction {
-                    button: None,
-                    buttons: Some(buttons),
-                    sections: None,
-                    catalog_id: None,
-                    product_retailer_id: None,
-                },
+        let interactive = Interactive {
+            interactive_type: "button".to_string(),
+            header: header.map(InteractiveHeader::text),
+            body: InteractiveBody {
+                text: body_text.to_string(),
+            },
+            footer: footer.map(|f| InteractiveFooter {
+                text: f.to_string(),
+            }),
+            action: InteractiveAction {
+                button: None,
+                buttons: Some(buttons),
+                sections: None,
+                catalog_id: None,
+                product_retailer_id: None,
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.send(to, &OutgoingMessage::interactive(interactive))
+            .await
     }
 
     /// Mark a message as read
@@ -545,76 +343,254 @@ impl MessagesApi {
     }
 }
 
+/// A message that can be sent with [`MessagesApi::send`]
+///
+/// Serializes to the message-specific part of a send request (`type` and
+/// the matching content object), so messages can be built, inspected,
+/// stored or queued before they are sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutgoingMessage {
+    /// Text message
+    Text {
+        /// Text content
+        text: TextContent,
+    },
+    /// Image message
+    Image {
+        /// Image by ID or link
+        image: MediaContent,
+    },
+    /// Video message
+    Video {
+        /// Video by ID or link
+        video: MediaContent,
+    },
+    /// Audio message
+    Audio {
+        /// Audio by ID or link
+        audio: MediaContent,
+    },
+    /// Document message
+    Document {
+        /// Document by ID or link
+        document: MediaContent,
+    },
+    /// Sticker message
+    Sticker {
+        /// Sticker by ID or link
+        sticker: MediaContent,
+    },
+    /// Location message
+    Location {
+        /// Location
+        location: Location,
+    },
+    /// Contacts message
+    Contacts {
+        /// Contact cards
+        contacts: Vec<Contact>,
+    },
+    /// Template message
+    Template {
+        /// Template name, language and parameters
+        template: Template,
+    },
+    /// Interactive message
+    Interactive {
+        /// Interactive content
+        interactive: Box<Interactive>,
+    },
+    /// Reaction to a message
+    Reaction {
+        /// Reaction
+        reaction: Reaction,
+    },
+}
+
+impl OutgoingMessage {
+    /// Create a text message
+    pub fn text(body: impl Into<String>) -> Self {
+        OutgoingMessage::Text {
+            text: TextContent {
+                preview_url: false,
+                body: body.into(),
+            },
+        }
+    }
+
+    /// Create a text message with URL preview
+    pub fn text_with_preview(body: impl Into<String>) -> Self {
+        OutgoingMessage::Text {
+            text: TextContent {
+                preview_url: true,
+                body: body.into(),
+            },
+        }
+    }
+
+    /// Create an image message
+    pub fn image(image: MediaContent) -> Self {
+        OutgoingMessage::Image { image }
+    }
+
+    /// Create a video message
+    pub fn video(video: MediaContent) -> Self {
+        OutgoingMessage::Video { video }
+    }
+
+    /// Create an audio message
+    pub fn audio(audio: MediaContent) -> Self {
+        OutgoingMessage::Audio { audio }
+    }
+
+    /// Create a document message
+    pub fn document(document: MediaContent) -> Self {
+        OutgoingMessage::Document { document }
+    }
+
+    /// Create a sticker message
+    pub fn sticker(sticker: MediaContent) -> Self {
+        OutgoingMessage::Sticker { sticker }
+    }
+
+    /// Create a location message
+    pub fn location(latitude: f64, longitude: f64) -> Self {
+        OutgoingMessage::Location {
+            location: Location {
+                latitude,
+                longitude,
+                name: None,
+                address: None,
+            },
+        }
+    }
+
+    /// Create a template message
+    pub fn template(
+        name: impl Into<String>,
+        language_code: impl Into<String>,
+        components: Option<Vec<TemplateComponent>>,
+    ) -> Self {
+        OutgoingMessage::Template {
+            template: Template {
+                name: name.into(),
+                language: Language {
+                    code: language_code.into(),
+                },
+                components,
+            },
+        }
+    }
+
+    /// Create an interactive message
+    pub fn interactive(interactive: Interactive) -> Self {
+        OutgoingMessage::Interactive {
+            interactive: Box::new(interactive),
+        }
+    }
+
+    /// Create a reaction to a message (an empty emoji removes the reaction)
+    pub fn reaction(message_id: impl Into<String>, emoji: impl Into<String>) -> Self {
+        OutgoingMessage::Reaction {
+            reaction: Reaction {
+                message_id: message_id.into(),
+                emoji: emoji.into(),
+            },
+        }
+    }
+
+    /// Value of the `type` field for this message
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            OutgoingMessage::Text { .. } => "text",
+            OutgoingMessage::Image { .. } => "image",
+            OutgoingMessage::Video { .. } => "video",
+            OutgoingMessage::Audio { .. } => "audio",
+            OutgoingMessage::Document { .. } => "document",
+            OutgoingMessage::Sticker { .. } => "sticker",
+            OutgoingMessage::Location { .. } => "location",
+            OutgoingMessage::Contacts { .. } => "contacts",
+            OutgoingMessage::Template { .. } => "template",
+            OutgoingMessage::Interactive { .. } => "interactive",
+            OutgoingMessage::Reaction { .. } => "reaction",
+        }
+    }
+
+    /// Whether this is a free-form message, which is only allowed within the
+    /// 24-hour customer service window
+    ///
+    /// Every message but a template is free-form.
+    pub fn is_free_form(&self) -> bool {
+        !matches!(self, OutgoingMessage::Template { .. })
+    }
+}
+
+/// Optional fields of a send request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendOptions {
+    /// Message being replied to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<Context>,
+    /// Arbitrary data echoed back in status webhooks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub biz_opaque_callback_data: Option<String>,
+}
+
+impl SendOptions {
+    /// Create empty options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the message as a reply to the given message ID
+    pub fn reply_to(mut self, message_id: impl Into<String>) -> Self {
+        self.context = Some(Context {
+            message_id: message_id.into(),
+        });
+        self
+    }
+
+    /// Attach data that is returned in the status webhooks of the message
+    pub fn biz_opaque_callback_data(mut self, data: impl Into<String>) -> Self {
+        self.biz_opaque_callback_data = Some(data.into());
+        self
+    }
+}
+
 // Request/Response types
 
 #[derive(Debug, Serialize)]
-struct SendTextRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    text: TextContent,
+struct SendMessageRequest<'a> {
+    messaging_product: &'static str,
+    recipient_type: &'static str,
+    to: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<Context>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    biz_opaque_callback_data: Option<String>,
+    #[serde(flatten)]
+    message: &'a OutgoingMessage,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextContent {
     pub preview_url: bool,
     pub body: String,
 }
 
-#[derive(Debug, Serialize)]
-struct SendReplyRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    context: Context,
-    #[serde(rename = "type")]
-    message_type: String,
-    text: TextContent,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Context {
     pub message_id: String,
 }
 
-#[derive(Debug, Serialize)]
-struct SendReactionRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    reaction: Reaction,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reaction {
     pub message_id: String,
     pub emoji: String,
 }
 
-#[derive(Debug, Serialize)]
-struct SendMediaRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    image: Option<MediaContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    video: Option<MediaContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    audio: Option<MediaContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    document: Option<MediaContent>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    sticker: Option<MediaContent>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MediaContent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
@@ -626,17 +602,46 @@ pub struct MediaContent {
     pub filename: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct SendLocationRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    location: Location,
+impl MediaContent {
+    /// Refer to media uploaded with the Media API
+    pub fn from_id(media_id: impl Into<String>) -> Self {
+        Self {
+            id: Some(media_id.into()),
+            link: None,
+            caption: None,
+            filename: None,
+        }
+    }
+
+    /// Refer to media hosted at a public URL
+    pub fn from_link(url: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            link: Some(url.into()),
+            caption: None,
+            filename: None,
+        }
+    }
+
+    /// Add a caption (images, videos and documents)
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Add a filename (documents)
+    pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    fn with_optional_caption(mut self, caption: Option<&str>) -> Self {
+        self.caption = caption.map(|s| s.to_string());
+        self
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Location {
     pub latitude: f64,
     pub longitude: f64,
@@ -646,18 +651,8 @@ pub struct Location {
     pub address: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct SendContactsRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    contacts: Vec<Contact>,
-}
-
 /// Contact information for sending contact messages
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Contact {
     /// Contact name
     pub name: ContactName,
@@ -681,7 +676,7 @@ pub struct Contact {
     pub birthday: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactName {
     pub formatted_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -696,7 +691,7 @@ pub struct ContactName {
     pub prefix: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactPhone {
     pub phone: String,
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
@@ -705,21 +700,21 @@ pub struct ContactPhone {
     pub wa_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactEmail {
     pub email: String,
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub email_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactUrl {
     pub url: String,
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub url_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactAddress {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub street: Option<String>,
@@ -737,7 +732,7 @@ pub struct ContactAddress {
     pub address_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContactOrg {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub company: Option<String>,
@@ -747,17 +742,7 @@ pub struct ContactOrg {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct SendTemplateRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    template: Template,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Template {
     pub name: String,
     pub language: Language,
@@ -765,12 +750,12 @@ pub struct Template {
     pub components: Option<Vec<TemplateComponent>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Language {
     pub code: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateComponent {
     #[serde(rename = "type")]
     pub component_type: String,
@@ -782,7 +767,7 @@ pub struct TemplateComponent {
     pub parameters: Option<Vec<TemplateParameter>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateParameter {
     #[serde(rename = "type")]
     pub param_type: String,
@@ -800,29 +785,19 @@ pub struct TemplateParameter {
     pub video: Option<MediaContent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Currency {
     pub fallback_value: String,
     pub code: String,
     pub amount_1000: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DateTime {
     pub fallback_value: String,
 }
 
-#[derive(Debug, Serialize)]
-struct SendInteractiveRequest {
-    messaging_product: String,
-    recipient_type: String,
-    to: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    interactive: Interactive,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Interactive {
     #[serde(rename = "type")]
     pub interactive_type: String,
@@ -834,7 +809,7 @@ pub struct Interactive {
     pub action: InteractiveAction,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InteractiveHeader {
     #[serde(rename = "type")]
     pub header_type: String,
@@ -848,17 +823,30 @@ pub struct InteractiveHeader {
     pub document: Option<MediaContent>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl InteractiveHeader {
+    /// Create a text header
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            header_type: "text".to_string(),
+            text: Some(text.into()),
+            image: None,
+            video: None,
+            document: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InteractiveBody {
     pub text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InteractiveFooter {
     pub text: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InteractiveAction {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub button: Option<String>,
@@ -872,14 +860,14 @@ pub struct InteractiveAction {
     pub product_retailer_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Button {
     #[serde(rename = "type")]
     pub button_type: String,
     pub reply: ButtonReply,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ButtonReply {
     pub id: String,
     pub title: String,
@@ -898,13 +886,13 @@ impl Button {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ListSection {
     pub title: String,
     pub rows: Vec<ListRow>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ListRow {
     pub id: String,
     pub title: String,
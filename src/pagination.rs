@@ -0,0 +1,264 @@
+//! Cursor-based pagination for Graph API list endpoints
+//!
+//! List endpoints return one page at a time together with [`Paging`]
+//! cursors. A [`Paginator`] follows those cursors and exposes the results as
+//! a stream of pages or of individual items.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use futures_util::TryStreamExt;
+//! use wacloudapi::pagination::PageParams;
+//! use wacloudapi::Client;
+//!
+//! # async fn example() -> wacloudapi::Result<()> {
+//! let client = Client::new("token", "phone_id");
+//!
+//! let templates: Vec<_> = client
+//!     .templates()
+//!     .list_paginated("waba_id", PageParams::new().limit(100))
+//!     .items()
+//!     .try_collect()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::types::Paging;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use url::Url;
+
+/// A page returned by a Graph API list endpoint
+pub trait Paginated: DeserializeOwned + Send + 'static {
+    /// Item contained in the page
+    type Item: Send + 'static;
+
+    /// Paging info of the page, if any
+    fn paging(&self) -> Option<&Paging>;
+
+    /// Items of the page
+    fn items(&self) -> &[Self::Item];
+
+    /// Consume the page and return its items
+    fn into_items(self) -> Vec<Self::Item>;
+}
+
+/// Page size and starting cursor for a paginated request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageParams {
+    /// Maximum number of items per page
+    pub limit: Option<u32>,
+    /// Start after this cursor
+    pub after: Option<String>,
+    /// Start before this cursor and page backwards
+    pub before: Option<String>,
+}
+
+impl PageParams {
+    /// Start from the first page with the default page size
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of items per page
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Start after the given cursor
+    pub fn after(mut self, cursor: impl Into<String>) -> Self {
+        self.after = Some(cursor.into());
+        self
+    }
+
+    /// Start before the given cursor and page backwards
+    pub fn before(mut self, cursor: impl Into<String>) -> Self {
+        self.before = Some(cursor.into());
+        self
+    }
+
+    fn backwards(&self) -> bool {
+        self.before.is_some()
+    }
+}
+
+/// Follows the cursors of a Graph API list endpoint
+///
+/// Pages are fetched lazily as the stream is polled. Paging continues in the
+/// direction given by [`PageParams`]: forwards through `after` cursors, or
+/// backwards through `before` cursors if a `before` cursor was given.
+pub struct Paginator<R> {
+    client: Client,
+    url: String,
+    params: PageParams,
+    _page: PhantomData<fn() -> R>,
+}
+
+impl<R: Paginated> Paginator<R> {
+    pub(crate) fn new(client: Client, url: String, params: PageParams) -> Self {
+        Self {
+            client,
+            url,
+            params,
+            _page: PhantomData,
+        }
+    }
+
+    /// Fetch only the first page
+    pub async fn first_page(&self) -> Result<R> {
+        let url = page_url(&self.url, &self.params)?;
+        self.client.get(&url).await
+    }
+
+    /// Stream every page, starting with the first one
+    pub fn pages(self) -> BoxStream<'static, Result<R>> {
+        let backwards = self.params.backwards();
+        let first = page_url(&self.url, &self.params);
+        let limit = self.params.limit;
+        let base = self.url;
+        let client = self.client;
+
+        stream::try_unfold(Some(first), move |next| {
+            let client = client.clone();
+            let base = base.clone();
+            async move {
+                let url = match next {
+                    Some(url) => url?,
+                    None => return Ok(None),
+                };
+
+                let page: R = client.get(&url).await?;
+                let next = match page.paging() {
+                    Some(paging) if !page.items().is_empty() => {
+                        next_url(&base, paging, limit, backwards)
+                            // Stop instead of requesting the same page forever
+                            .filter(|next| !matches!(next, Ok(next) if *next == url))
+                    }
+                    _ => None,
+                };
+
+                Ok(Some((page, next)))
+            }
+        })
+        .boxed()
+    }
+
+    /// Stream every item across all pages
+    pub fn items(self) -> BoxStream<'static, Result<R::Item>> {
+        self.pages()
+            .map_ok(|page| stream::iter(page.into_items().into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+}
+
+impl<R> std::fmt::Debug for Paginator<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Paginator")
+            .field("url", &self.url)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+/// URL of the page after (or before) the current one
+fn next_url(
+    base: &str,
+    paging: &Paging,
+    limit: Option<u32>,
+    backwards: bool,
+) -> Option<Result<String>> {
+    let (link, cursor) = if backwards {
+        (
+            paging.previous.as_ref()?,
+            paging.cursors.as_ref().and_then(|c| c.before.clone()),
+        )
+    } else {
+        (
+            paging.next.as_ref()?,
+            paging.cursors.as_ref().and_then(|c| c.after.clone()),
+        )
+    };
+
+    // Prefer cursors over the returned link so the configured base URL and
+    // API version are kept
+    let params = match cursor {
+        Some(cursor) if backwards => PageParams::new().before(cursor),
+        Some(cursor) => PageParams::new().after(cursor),
+        None => return Some(Ok(link.clone())),
+    };
+
+    Some(page_url(base, &PageParams { limit, ..params }))
+}
+
+/// Add paging parameters to a URL, replacing any already present
+fn page_url(base: &str, params: &PageParams) -> Result<String> {
+    let mut url = Url::parse(base)?;
+
+    let existing: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !matches!(key.as_ref(), "limit" | "after" | "before"))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+
+    {
+        let mut query = url.query_pairs_mut();
+        query.clear().extend_pairs(existing);
+        if let Some(limit) = params.limit {
+            query.append_pair("limit", &limit.to_string());
+        }
+        if let Some(after) = &params.after {
+            query.append_pair("after", after);
+        }
+        if let Some(before) = &params.before {
+            query.append_pair("before", before);
+        }
+    }
+
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+
+    Ok(url.into())
+}
+
+macro_rules! impl_paginated {
+    ($($page:ty => $item:ty),* $(,)?) => {
+        $(
+            impl Paginated for $page {
+                type Item = $item;
+
+                fn paging(&self) -> Option<&Paging> {
+                    self.paging.as_ref()
+                }
+
+                fn items(&self) -> &[Self::Item] {
+                    &self.data
+                }
+
+                fn into_items(self) -> Vec<Self::Item> {
+                    self.data
+                }
+            }
+        )*
+    };
+}
+
+impl_paginated! {
+    crate::types::PhoneNumbersResponse => crate::types::PhoneNumber,
+    crate::types::WabaListResponse => crate::types::WhatsAppBusinessAccount,
+    crate::templates::TemplatesResponse => crate::templates::MessageTemplate,
+    crate::block::BlockedUsersResponse => crate::block::BlockedUser,
+    crate::qr_codes::QrCodesListResponse => crate::qr_codes::QrCode,
+    crate::flows::FlowsListResponse => crate::flows::Flow,
+    crate::waba::SubscribedAppsResponse => crate::waba::SubscribedApp,
+    crate::waba::PhoneNumbersResponse => crate::waba::WabaPhoneNumber,
+    crate::waba::AssignedUsersResponse => crate::waba::AssignedUser,
+    crate::waba::SystemUsersResponse => crate::waba::SystemUser,
+    crate::waba::WabaTemplatesResponse => crate::waba::WabaTemplate,
+}
@@ -2,6 +2,7 @@
 
 use crate::client::Client;
 use crate::error::Result;
+use crate::pagination::{PageParams, Paginator};
 use crate::types::{PhoneNumber, PhoneNumbersResponse, SuccessResponse};
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +28,23 @@ impl PhoneNumbersApi {
         self.client.get(&url).await
     }
 
+    /// Stream all phone numbers of a WABA, following paging cursors
+    ///
+    /// # Arguments
+    ///
+    /// * `waba_id` - WhatsApp Business Account ID
+    /// * `params` - Page size and starting cursor
+    pub fn list_paginated(
+        &self,
+        waba_id: &str,
+        params: PageParams,
+    ) -> Paginator<PhoneNumbersResponse> {
+        let url = self
+            .client
+            .endpoint_url(&format!("{}/phone_numbers", waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get a specific phone number by ID
     pub async fn get(&self, phone_number_id: &str) -> Result<PhoneNumber> {
         let url = self.client.endpoint_url(phone_number_id);
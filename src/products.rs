@@ -53,8 +53,7 @@ impl ProductsApi {
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.client.send_message(to, true, &body).await
     }
 
     /// Send a multi-product message (product list)
@@ -100,8 +99,7 @@ impl ProductsApi {
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.client.send_message(to, true, &body).await
     }
 
     /// Send a catalog message
@@ -141,8 +139,7 @@ impl ProductsApi {
             },
         };
 
-        let url = format!("{}/messages", self.client.base_url());
-        self.client.post(&url, &body).await
+        self.client.send_message(to, true, &body).await
     }
 
     /// Get commerce settings
@@ -2,6 +2,8 @@
 
 use crate::client::Client;
 use crate::error::Result;
+use crate::pagination::{PageParams, Paginator};
+use crate::types::Paging;
 use serde::{Deserialize, Serialize};
 
 /// QR Codes API client
@@ -40,6 +42,16 @@ impl QrCodesApi {
         self.client.get(&url).await
     }
 
+    /// Stream all QR codes, following paging cursors
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Page size and starting cursor
+    pub fn list_paginated(&self, params: PageParams) -> Paginator<QrCodesListResponse> {
+        let url = format!("{}/message_qrdls", self.client.base_url());
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get a specific QR code
     ///
     /// # Arguments
@@ -132,6 +144,9 @@ pub struct QrCodeResponse {
 pub struct QrCodesListResponse {
     /// List of QR codes
     pub data: Vec<QrCode>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// QR code details
@@ -0,0 +1,205 @@
+//! Client-side rate limiting for outgoing messages
+//!
+//! WhatsApp limits how many messages a phone number can send per second
+//! (its throughput level) and how often the same user can be messaged (the
+//! pair rate limit). Exceeding them fails sends with error 130429 or 131056.
+//! A [`RateLimiter`] attached to the [`Client`](crate::Client) holds sends
+//! back until they fit both budgets, instead of failing them.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wacloudapi::rate_limit::RateLimiter;
+//! use wacloudapi::Client;
+//!
+//! let client = Client::new("token", "phone_id")
+//!     .with_rate_limiter(RateLimiter::for_throughput_level("STANDARD"));
+//! ```
+
+use crate::types::Throughput;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Messages per second for the standard throughput level
+pub const STANDARD_MESSAGES_PER_SECOND: f64 = 80.0;
+
+/// Messages per second for the high throughput level
+pub const HIGH_MESSAGES_PER_SECOND: f64 = 1000.0;
+
+/// Messages that can be sent to one user in a burst
+pub const DEFAULT_PAIR_BURST: u32 = 45;
+
+/// Time for one message of the pair budget to become available again
+pub const DEFAULT_PAIR_INTERVAL: Duration = Duration::from_secs(6);
+
+/// Recipient buckets held before full (idle) ones are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Lowest messages-per-second budget; lower rates are raised to it
+pub const MIN_MESSAGES_PER_SECOND: f64 = 0.001;
+
+/// Longest wait returned by [`RateLimiter::try_acquire`]; callers try again after it
+const MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Token bucket limiter for outgoing messages
+///
+/// Enforces a global messages-per-second budget for the business number and a
+/// per-recipient pair limit. Sends that exceed either budget wait until a
+/// token is available.
+#[derive(Debug)]
+pub struct RateLimiter {
+    messages_per_second: f64,
+    pair_burst: u32,
+    pair_interval: Duration,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    global: Bucket,
+    recipients: HashMap<String, Bucket>,
+    /// Number of recipient buckets at which idle ones are pruned next
+    prune_at: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, capacity: f64, per_second: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(capacity);
+        self.updated = now;
+    }
+
+    fn wait_time(&self, per_second: f64) -> Duration {
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64((1.0 - self.tokens) / per_second)
+                .map_or(MAX_WAIT, |wait| wait.min(MAX_WAIT))
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter with the given global messages-per-second budget
+    ///
+    /// The pair limit defaults to bursts of 45 messages per user, with one
+    /// message becoming available again every 6 seconds. Rates below
+    /// [`MIN_MESSAGES_PER_SECOND`], including zero, negative and NaN rates,
+    /// are raised to it.
+    pub fn new(messages_per_second: f64) -> Self {
+        let messages_per_second = messages_per_second.max(MIN_MESSAGES_PER_SECOND);
+
+        Self {
+            messages_per_second,
+            pair_burst: DEFAULT_PAIR_BURST,
+            pair_interval: DEFAULT_PAIR_INTERVAL,
+            state: Mutex::new(LimiterState {
+                global: Bucket::full(messages_per_second.max(1.0), Instant::now()),
+                recipients: HashMap::new(),
+                prune_at: PRUNE_THRESHOLD,
+            }),
+        }
+    }
+
+    /// Create a limiter for a phone number throughput level
+    ///
+    /// `HIGH` allows 1000 messages per second; `STANDARD` and any other
+    /// level allow 80.
+    pub fn for_throughput_level(level: &str) -> Self {
+        match level.to_ascii_uppercase().as_str() {
+            "HIGH" => Self::new(HIGH_MESSAGES_PER_SECOND),
+            _ => Self::new(STANDARD_MESSAGES_PER_SECOND),
+        }
+    }
+
+    /// Create a limiter from the throughput reported for a phone number
+    pub fn for_throughput(throughput: &Throughput) -> Self {
+        Self::for_throughput_level(&throughput.level)
+    }
+
+    /// Set the per-recipient pair limit
+    ///
+    /// # Arguments
+    ///
+    /// * `burst` - Messages that can be sent to one user back to back
+    /// * `interval` - Time for one message of the budget to become available again
+    pub fn pair_limit(mut self, burst: u32, interval: Duration) -> Self {
+        self.pair_burst = burst.max(1);
+        self.pair_interval = interval;
+        self
+    }
+
+    /// Global messages-per-second budget
+    pub fn messages_per_second(&self) -> f64 {
+        self.messages_per_second
+    }
+
+    /// Wait until a message to `recipient` fits both budgets, then take a token
+    pub async fn acquire(&self, recipient: &str) {
+        loop {
+            match self.try_acquire(recipient) {
+                Ok(()) => return,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Take a token without waiting
+    ///
+    /// Returns how long to wait before trying again if either budget is
+    /// exhausted, at most a minute.
+    pub fn try_acquire(&self, recipient: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let pair_capacity = self.pair_burst as f64;
+        let pair_rate = 1.0 / self.pair_interval.as_secs_f64().max(1e-9);
+        let global_capacity = self.messages_per_second.max(1.0);
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Pruning is amortised: the threshold doubles with the buckets still
+        // in use, so a busy limiter does not scan them on every call
+        if state.recipients.len() >= state.prune_at {
+            state.recipients.retain(|_, bucket| {
+                let mut bucket = *bucket;
+                bucket.refill(pair_capacity, pair_rate, now);
+                bucket.tokens < pair_capacity
+            });
+            state.prune_at = (state.recipients.len() * 2).max(PRUNE_THRESHOLD);
+        }
+
+        state
+            .global
+            .refill(global_capacity, self.messages_per_second, now);
+        let global_wait = state.global.wait_time(self.messages_per_second);
+
+        let pair = state
+            .recipients
+            .entry(recipient.to_string())
+            .or_insert_with(|| Bucket::full(pair_capacity, now));
+        pair.refill(pair_capacity, pair_rate, now);
+        let pair_wait = pair.wait_time(pair_rate);
+
+        let wait = global_wait.max(pair_wait);
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        pair.tokens -= 1.0;
+        state.global.tokens -= 1.0;
+        Ok(())
+    }
+}
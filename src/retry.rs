@@ -0,0 +1,161 @@
+//! Retry policy for failed API requests
+//!
+//! By default the client sends every request once. Attach a [`RetryPolicy`] to
+//! retry transient failures with exponential backoff.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use wacloudapi::retry::RetryPolicy;
+//! use wacloudapi::Client;
+//!
+//! let client = Client::new("token", "phone_id").with_retry_policy(
+//!     RetryPolicy::new()
+//!         .max_attempts(5)
+//!         .base_delay(Duration::from_millis(200)),
+//! );
+//! ```
+
+use crate::error::{Error, WhatsAppErrorCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Configuration for retrying failed requests
+///
+/// Requests are retried when the connection fails, when the API reports a
+/// rate limit, when the server answers with a 5xx status, or when the error
+/// code is retryable. By default that is every code for which
+/// [`WhatsAppErrorCode::is_retryable`] holds; [`RetryPolicy::retryable_codes`]
+/// replaces it with a fixed list.
+///
+/// Rate limits and connection failures mean the request never reached the
+/// API, so they are retried for every request. Other failures are ambiguous:
+/// the API may already have processed the request. They are only retried for
+/// idempotent requests (GET, DELETE) unless
+/// [`RetryPolicy::retry_non_idempotent`] is enabled, so a message is never
+/// sent twice by accident.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound for the delay between attempts
+    pub max_delay: Duration,
+    /// Randomize delays to avoid retrying in lockstep
+    pub jitter: bool,
+    /// API error codes that are worth retrying, `None` for the codes
+    /// classified as retryable by [`WhatsAppErrorCode::is_retryable`]
+    pub retryable_codes: Option<Vec<i32>>,
+    /// Also retry ambiguous failures of non-idempotent requests (POST)
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_codes: None,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a retry policy with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of attempts (including the first one)
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the maximum delay between attempts
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Enable or disable jitter
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the API error codes that are retried, instead of the codes
+    /// classified as retryable by [`WhatsAppErrorCode::is_retryable`]
+    pub fn retryable_codes(mut self, codes: Vec<i32>) -> Self {
+        self.retryable_codes = Some(codes);
+        self
+    }
+
+    /// Allow retrying ambiguous failures of non-idempotent requests
+    pub fn retry_non_idempotent(mut self, retry: bool) -> Self {
+        self.retry_non_idempotent = retry;
+        self
+    }
+
+    /// Delay to wait after the given failed attempt (1-based)
+    ///
+    /// The delay doubles with every attempt and is capped at `max_delay`.
+    /// With jitter enabled, a random delay between half and the full value
+    /// is returned.
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+
+        if self.jitter {
+            let half = delay / 2;
+            let random = RandomState::new().build_hasher().finish();
+            half + half.mul_f64((random % 1_000) as f64 / 1_000.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Decide whether a failed attempt should be retried
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - HTTP status of the response, if one was received
+    /// * `error` - The error produced by the attempt
+    /// * `idempotent` - Whether the request can safely be sent twice
+    pub fn should_retry(&self, status: Option<u16>, error: &Error, idempotent: bool) -> bool {
+        let ambiguous_allowed = idempotent || self.retry_non_idempotent;
+
+        match error {
+            Error::RateLimited { .. } => true,
+            Error::Request(e) if e.is_connect() => true,
+            Error::Request(e) => ambiguous_allowed && (e.is_timeout() || e.is_request()),
+            Error::Api(err) => {
+                ambiguous_allowed
+                    && (status.is_some_and(|s| s >= 500) || self.is_retryable_code(err.code))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_retryable_code(&self, code: i32) -> bool {
+        match &self.retryable_codes {
+            Some(codes) => codes.contains(&code),
+            None => WhatsAppErrorCode::from_code(code).is_retryable(),
+        }
+    }
+}
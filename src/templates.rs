@@ -1,9 +1,90 @@
 //! Templates API for managing message templates
 
 use crate::client::Client;
-use crate::error::Result;
+use crate::error::{Error, Result, TemplateViolation};
+use crate::pagination::{PageParams, Paginator};
 use crate::types::{Paging, SuccessResponse};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Longest header text, in characters
+pub const MAX_HEADER_LENGTH: usize = 60;
+
+/// Longest body text, in characters
+pub const MAX_BODY_LENGTH: usize = 1024;
+
+/// Longest footer text, in characters
+pub const MAX_FOOTER_LENGTH: usize = 60;
+
+/// Most buttons a template can have
+pub const MAX_BUTTONS: usize = 10;
+
+/// Most URL buttons a template can have
+pub const MAX_URL_BUTTONS: usize = 2;
+
+/// Most phone number buttons a template can have
+pub const MAX_PHONE_NUMBER_BUTTONS: usize = 1;
+
+/// Define an enum of known string values with an `Other` fallback, so
+/// values this crate does not know are passed through unchanged
+macro_rules! template_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$doc:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum $name {
+            $($(#[$doc])* $variant,)*
+            /// A value not known to this crate, passed through as is
+            Other(String),
+        }
+
+        impl $name {
+            /// Get the string representation
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+
+            /// Parse the string representation of a known value, ignoring case
+            pub fn parse(value: &str) -> Option<Self> {
+                $(
+                    if value.eq_ignore_ascii_case($value) {
+                        return Some($name::$variant);
+                    }
+                )*
+                None
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self::parse(&value).unwrap_or($name::Other(value))
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self::from(value.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Other(value) => value,
+                    known => known.as_str().to_string(),
+                }
+            }
+        }
+    };
+}
 
 /// Templates API client
 pub struct TemplatesApi {
@@ -27,6 +108,23 @@ impl TemplatesApi {
         self.client.get(&url).await
     }
 
+    /// Stream all templates, following paging cursors
+    ///
+    /// # Arguments
+    ///
+    /// * `waba_id` - WhatsApp Business Account ID
+    /// * `params` - Page size and starting cursor
+    pub fn list_paginated(
+        &self,
+        waba_id: &str,
+        params: PageParams,
+    ) -> Paginator<TemplatesResponse> {
+        let url = self
+            .client
+            .endpoint_url(&format!("{}/message_templates", waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get templates with specific status
     pub async fn list_by_status(
         &self,
@@ -50,11 +148,17 @@ impl TemplatesApi {
     }
 
     /// Create a new message template
+    ///
+    /// The template is checked with [`CreateTemplate::validate`] first; if
+    /// it breaks any rule, this fails with [`Error::InvalidTemplate`]
+    /// listing every violation, without calling the API.
     pub async fn create(
         &self,
         waba_id: &str,
         template: &CreateTemplate,
     ) -> Result<CreateTemplateResponse> {
+        template.validate().map_err(Error::InvalidTemplate)?;
+
         let url = self
             .client
             .endpoint_url(&format!("{}/message_templates", waba_id));
@@ -107,12 +211,12 @@ pub struct MessageTemplate {
 /// Template component definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateComponentDef {
-    /// Component type (HEADER, BODY, FOOTER, BUTTONS)
+    /// Component type
     #[serde(rename = "type")]
-    pub component_type: String,
-    /// Component format (for HEADER: TEXT, IMAGE, VIDEO, DOCUMENT)
+    pub component_type: ComponentKind,
+    /// Header format
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<String>,
+    pub format: Option<HeaderFormat>,
     /// Text content
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
@@ -127,9 +231,9 @@ pub struct TemplateComponentDef {
 /// Template button definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TemplateButton {
-    /// Button type (QUICK_REPLY, URL, PHONE_NUMBER)
+    /// Button type
     #[serde(rename = "type")]
-    pub button_type: String,
+    pub button_type: ButtonType,
     /// Button text
     pub text: String,
     /// URL (for URL type)
@@ -138,10 +242,66 @@ pub struct TemplateButton {
     /// Phone number (for PHONE_NUMBER type)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub phone_number: Option<String>,
+    /// Example values (URL suffix for URL type, code for COPY_CODE type)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<Vec<String>>,
+}
+
+impl TemplateButton {
+    /// Create a quick reply button
+    pub fn quick_reply(text: impl Into<String>) -> Self {
+        Self::new(ButtonType::QuickReply, text)
+    }
+
+    /// Create a button opening `url`
+    pub fn url(text: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            url: Some(url.into()),
+            ..Self::new(ButtonType::Url, text)
+        }
+    }
+
+    /// Create a button opening `url`, whose `{{1}}` suffix is set when sending
+    pub fn dynamic_url(
+        text: impl Into<String>,
+        url: impl Into<String>,
+        example: impl Into<String>,
+    ) -> Self {
+        Self {
+            example: Some(vec![example.into()]),
+            ..Self::url(text, url)
+        }
+    }
+
+    /// Create a button calling `phone_number`
+    pub fn phone_number(text: impl Into<String>, phone_number: impl Into<String>) -> Self {
+        Self {
+            phone_number: Some(phone_number.into()),
+            ..Self::new(ButtonType::PhoneNumber, text)
+        }
+    }
+
+    /// Create a button copying a code set when sending
+    pub fn copy_code(example: impl Into<String>) -> Self {
+        Self {
+            example: Some(vec![example.into()]),
+            ..Self::new(ButtonType::CopyCode, "Copy offer code")
+        }
+    }
+
+    fn new(button_type: ButtonType, text: impl Into<String>) -> Self {
+        Self {
+            button_type,
+            text: text.into(),
+            url: None,
+            phone_number: None,
+            example: None,
+        }
+    }
 }
 
 /// Template example values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TemplateExample {
     /// Header handle (for media headers)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -149,9 +309,70 @@ pub struct TemplateExample {
     /// Header text example
     #[serde(skip_serializing_if = "Option::is_none")]
     pub header_text: Option<Vec<String>>,
+    /// Header text examples of named parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_text_named_params: Option<Vec<NamedParameterExample>>,
     /// Body text examples
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body_text: Option<Vec<Vec<String>>>,
+    /// Body text examples of named parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_text_named_params: Option<Vec<NamedParameterExample>>,
+}
+
+/// Example value of a named parameter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedParameterExample {
+    /// Parameter name, as in `{{param_name}}`
+    pub param_name: String,
+    /// Example value
+    pub example: String,
+}
+
+/// Example values for the parameters of a header or body text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextExamples {
+    /// Values of `{{1}}`, `{{2}}`, ... in order
+    Positional(Vec<String>),
+    /// Values of named parameters
+    Named(Vec<NamedParameterExample>),
+}
+
+impl TextExamples {
+    /// Create examples of named parameters from `(name, example)` pairs
+    pub fn named<N, E>(examples: impl IntoIterator<Item = (N, E)>) -> Self
+    where
+        N: Into<String>,
+        E: Into<String>,
+    {
+        TextExamples::Named(
+            examples
+                .into_iter()
+                .map(|(name, example)| NamedParameterExample {
+                    param_name: name.into(),
+                    example: example.into(),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<String>> for TextExamples {
+    fn from(examples: Vec<String>) -> Self {
+        TextExamples::Positional(examples)
+    }
+}
+
+impl From<Vec<&str>> for TextExamples {
+    fn from(examples: Vec<&str>) -> Self {
+        TextExamples::Positional(examples.into_iter().map(String::from).collect())
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for TextExamples {
+    fn from(examples: [&str; N]) -> Self {
+        TextExamples::Positional(examples.into_iter().map(String::from).collect())
+    }
 }
 
 /// Create template request
@@ -159,8 +380,8 @@ pub struct TemplateExample {
 pub struct CreateTemplate {
     /// Template name (lowercase, underscores only)
     pub name: String,
-    /// Template category (MARKETING, UTILITY, AUTHENTICATION)
-    pub category: String,
+    /// Template category
+    pub category: TemplateCategory,
     /// Language code (e.g., "en_US")
     pub language: String,
     /// Template components
@@ -168,6 +389,9 @@ pub struct CreateTemplate {
     /// Allow category change (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_category_change: Option<bool>,
+    /// Parameter format (defaults to positional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_format: Option<ParameterFormat>,
 }
 
 impl CreateTemplate {
@@ -179,18 +403,25 @@ impl CreateTemplate {
     ) -> Self {
         Self {
             name: name.into(),
-            category: category.as_str().to_string(),
+            category,
             language: language.into(),
             components: Vec::new(),
             allow_category_change: None,
+            parameter_format: None,
         }
     }
 
+    /// Set whether placeholders are numbered (`{{1}}`) or named (`{{first_name}}`)
+    pub fn with_parameter_format(mut self, format: ParameterFormat) -> Self {
+        self.parameter_format = Some(format);
+        self
+    }
+
     /// Add a header component
     pub fn with_header(mut self, format: HeaderFormat, text: Option<String>) -> Self {
         self.components.push(TemplateComponentDef {
-            component_type: "HEADER".to_string(),
-            format: Some(format.as_str().to_string()),
+            component_type: ComponentKind::Header,
+            format: Some(format),
             text,
             buttons: None,
             example: None,
@@ -198,10 +429,28 @@ impl CreateTemplate {
         self
     }
 
+    /// Add an image, video or document header
+    ///
+    /// `handle` is the `h` of a sample uploaded through the Resumable Upload
+    /// API, see [`UploadSessionApi`](crate::uploads::UploadSessionApi).
+    pub fn with_media_header(mut self, format: HeaderFormat, handle: impl Into<String>) -> Self {
+        self.components.push(TemplateComponentDef {
+            component_type: ComponentKind::Header,
+            format: Some(format),
+            text: None,
+            buttons: None,
+            example: Some(TemplateExample {
+                header_handle: Some(vec![handle.into()]),
+                ..Default::default()
+            }),
+        });
+        self
+    }
+
     /// Add a body component
     pub fn with_body(mut self, text: impl Into<String>) -> Self {
         self.components.push(TemplateComponentDef {
-            component_type: "BODY".to_string(),
+            component_type: ComponentKind::Body,
             format: None,
             text: Some(text.into()),
             buttons: None,
@@ -210,10 +459,62 @@ impl CreateTemplate {
         self
     }
 
+    /// Add a text header with examples for its parameter
+    pub fn with_text_header(
+        mut self,
+        text: impl Into<String>,
+        examples: impl Into<TextExamples>,
+    ) -> Self {
+        let example = match examples.into() {
+            TextExamples::Positional(values) => TemplateExample {
+                header_text: Some(values),
+                ..Default::default()
+            },
+            TextExamples::Named(values) => TemplateExample {
+                header_text_named_params: Some(values),
+                ..Default::default()
+            },
+        };
+        self.components.push(TemplateComponentDef {
+            component_type: ComponentKind::Header,
+            format: Some(HeaderFormat::Text),
+            text: Some(text.into()),
+            buttons: None,
+            example: Some(example),
+        });
+        self
+    }
+
+    /// Add a body component with examples for its parameters
+    pub fn with_body_text(
+        mut self,
+        text: impl Into<String>,
+        examples: impl Into<TextExamples>,
+    ) -> Self {
+        let example = match examples.into() {
+            TextExamples::Positional(values) => TemplateExample {
+                body_text: Some(vec![values]),
+                ..Default::default()
+            },
+            TextExamples::Named(values) => TemplateExample {
+                body_text_named_params: Some(values),
+                ..Default::default()
+            },
+        };
+        self.components.push(TemplateComponentDef {
+            component_type: ComponentKind::Body,
+            format: None,
+            text: Some(text.into()),
+            buttons: None,
+            example: Some(example),
+        });
+        self
+    }
+
     /// Add a footer component
     pub fn with_footer(mut self, text: impl Into<String>) -> Self {
         self.components.push(TemplateComponentDef {
-            component_type: "FOOTER".to_string(),
+            component_type: ComponentKind::Footer,
             format: None,
             text: Some(text.into()),
             buttons: None,
@@ -225,7 +526,7 @@ impl CreateTemplate {
     /// Add buttons component
     pub fn with_buttons(mut self, buttons: Vec<TemplateButton>) -> Self {
         self.components.push(TemplateComponentDef {
-            component_type: "BUTTONS".to_string(),
+            component_type: ComponentKind::Buttons,
             format: None,
             text: None,
             buttons: Some(buttons),
@@ -233,6 +534,289 @@ impl CreateTemplate {
         });
         self
     }
+
+    /// Add a button to the buttons component, creating it if needed
+    pub fn with_button(mut self, button: TemplateButton) -> Self {
+        let buttons = self
+            .components
+            .iter_mut()
+            .find(|component| component.component_type == ComponentKind::Buttons);
+        match buttons {
+            Some(component) => component.buttons.get_or_insert_with(Vec::new).push(button),
+            None => return self.with_buttons(vec![button]),
+        }
+        self
+    }
+
+    /// Check the template against the rules enforced by the API
+    ///
+    /// Checks text lengths, that placeholders follow the parameter format
+    /// and have examples, and the number of buttons of each type. Returns
+    /// every violation found, not just the first. Components and buttons of
+    /// types not covered here are passed through unchecked.
+    pub fn validate(&self) -> std::result::Result<(), Vec<TemplateViolation>> {
+        let mut violations = Vec::new();
+
+        let format = self.parameter_format.unwrap_or_default();
+
+        let mut seen = BTreeSet::new();
+        for component in &self.components {
+            let kind = &component.component_type;
+            // Other components (CAROUSEL, LIMITED_TIME_OFFER, ...) are left to the API
+            if let ComponentKind::Other(_) = kind {
+                continue;
+            }
+            if !seen.insert(kind) {
+                violations.push(TemplateViolation::DuplicateComponent(kind.clone()));
+            }
+
+            let example = component.example.as_ref();
+            match kind {
+                ComponentKind::Header => {
+                    let header_format = component.format.as_ref().unwrap_or(&HeaderFormat::Text);
+                    if matches!(
+                        header_format,
+                        HeaderFormat::Image | HeaderFormat::Video | HeaderFormat::Document
+                    ) && example
+                        .and_then(|example| example.header_handle.as_ref())
+                        .is_none_or(Vec::is_empty)
+                    {
+                        violations.push(TemplateViolation::MissingHeaderSample {
+                            format: header_format.clone(),
+                        });
+                    }
+                    if let Some(text) = &component.text {
+                        let examples = match format {
+                            ParameterFormat::Positional => {
+                                Examples::Positional(example.and_then(|e| e.header_text.as_deref()))
+                            }
+                            ParameterFormat::Named => Examples::Named(
+                                example.and_then(|e| e.header_text_named_params.as_deref()),
+                            ),
+                        };
+                        check_text(
+                            kind,
+                            text,
+                            MAX_HEADER_LENGTH,
+                            Some(1),
+                            format,
+                            examples,
+                            &mut violations,
+                        );
+                    }
+                }
+                ComponentKind::Body => {
+                    if let Some(text) = &component.text {
+                        let examples = match format {
+                            ParameterFormat::Positional => Examples::Positional(
+                                example
+                                    .and_then(|e| e.body_text.as_ref())
+                                    .and_then(|rows| rows.first())
+                                    .map(Vec::as_slice),
+                            ),
+                            ParameterFormat::Named => Examples::Named(
+                                example.and_then(|e| e.body_text_named_params.as_deref()),
+                            ),
+                        };
+                        check_text(
+                            kind,
+                            text,
+                            MAX_BODY_LENGTH,
+                            None,
+                            format,
+                            examples,
+                            &mut violations,
+                        );
+                    }
+                }
+                ComponentKind::Footer => {
+                    if let Some(text) = &component.text {
+                        check_text(
+                            kind,
+                            text,
+                            MAX_FOOTER_LENGTH,
+                            Some(0),
+                            format,
+                            Examples::None,
+                            &mut violations,
+                        );
+                    }
+                }
+                ComponentKind::Buttons => {
+                    check_buttons(
+                        component.buttons.as_deref().unwrap_or_default(),
+                        &mut violations,
+                    );
+                }
+                ComponentKind::Other(_) => {}
+            }
+        }
+
+        if !seen.contains(&ComponentKind::Body) {
+            violations.push(TemplateViolation::MissingBody);
+        }
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+}
+
+/// Examples given for the placeholders of a text
+enum Examples<'a> {
+    /// Placeholders are not allowed, so examples are not checked
+    None,
+    Positional(Option<&'a [String]>),
+    Named(Option<&'a [NamedParameterExample]>),
+}
+
+/// Check the length and placeholders of a header, body or footer text
+fn check_text(
+    component: &ComponentKind,
+    text: &str,
+    max_length: usize,
+    max_placeholders: Option<usize>,
+    format: ParameterFormat,
+    examples: Examples<'_>,
+    violations: &mut Vec<TemplateViolation>,
+) {
+    let length = text.chars().count();
+    if length > max_length {
+        violations.push(TemplateViolation::TextTooLong {
+            component: component.clone(),
+            length,
+            max: max_length,
+        });
+    }
+
+    let mut numbers = BTreeSet::new();
+    let mut names = BTreeSet::new();
+    for placeholder in placeholders(text) {
+        let found = if !placeholder.is_empty() && placeholder.bytes().all(|b| b.is_ascii_digit()) {
+            ParameterFormat::Positional
+        } else if is_parameter_name(placeholder) {
+            ParameterFormat::Named
+        } else {
+            violations.push(TemplateViolation::InvalidPlaceholder {
+                component: component.clone(),
+                placeholder: placeholder.to_string(),
+            });
+            continue;
+        };
+
+        if found != format {
+            violations.push(TemplateViolation::ParameterFormatMismatch {
+                component: component.clone(),
+                placeholder: placeholder.to_string(),
+                format,
+            });
+        } else if format == ParameterFormat::Positional {
+            numbers.insert(placeholder.parse::<u32>().unwrap_or(u32::MAX));
+        } else {
+            names.insert(placeholder);
+        }
+    }
+
+    let count = numbers.len() + names.len();
+    if let Some(max) = max_placeholders {
+        if count > max {
+            violations.push(TemplateViolation::TooManyPlaceholders {
+                component: component.clone(),
+                count,
+                max,
+            });
+        }
+    }
+
+    match examples {
+        Examples::None => {}
+        Examples::Positional(examples) => {
+            if !numbers.iter().copied().eq(1..=numbers.len() as u32) {
+                violations.push(TemplateViolation::PlaceholderSequence {
+                    component: component.clone(),
+                });
+            }
+            let examples = examples.map_or(0, <[String]>::len);
+            if examples != numbers.len() {
+                violations.push(TemplateViolation::ExampleCountMismatch {
+                    component: component.clone(),
+                    placeholders: numbers.len(),
+                    examples,
+                });
+            }
+        }
+        Examples::Named(examples) => {
+            let examples = examples.unwrap_or_default();
+            for name in &names {
+                if !examples.iter().any(|example| example.param_name == *name) {
+                    violations.push(TemplateViolation::MissingExample {
+                        component: component.clone(),
+                        name: name.to_string(),
+                    });
+                }
+            }
+            for example in examples {
+                if !names.contains(example.param_name.as_str()) {
+                    violations.push(TemplateViolation::UnusedExample {
+                        component: component.clone(),
+                        name: example.param_name.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Check the number of buttons, overall and per type
+fn check_buttons(buttons: &[TemplateButton], violations: &mut Vec<TemplateViolation>) {
+    if buttons.len() > MAX_BUTTONS {
+        violations.push(TemplateViolation::TooManyButtons {
+            button_type: None,
+            count: buttons.len(),
+            max: MAX_BUTTONS,
+        });
+    }
+
+    for (button_type, max) in [
+        (ButtonType::Url, MAX_URL_BUTTONS),
+        (ButtonType::PhoneNumber, MAX_PHONE_NUMBER_BUTTONS),
+    ] {
+        let count = buttons
+            .iter()
+            .filter(|button| button.button_type == button_type)
+            .count();
+        if count > max {
+            violations.push(TemplateViolation::TooManyButtons {
+                button_type: Some(button_type),
+                count,
+                max,
+            });
+        }
+    }
+}
+
+/// Contents of the `{{...}}` placeholders of a text, trimmed
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        found.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    found
+}
+
+/// Whether a placeholder is a valid named parameter (lowercase letters,
+/// digits and underscores, starting with a letter)
+fn is_parameter_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
 }
 
 /// Create template response
@@ -274,52 +858,106 @@ impl TemplateStatus {
     }
 }
 
-/// Template category
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TemplateCategory {
-    /// Marketing messages
-    Marketing,
-    /// Utility/transactional messages
-    Utility,
-    /// Authentication messages (OTP)
-    Authentication,
+template_enum! {
+    /// Template category
+    TemplateCategory {
+        /// Marketing messages
+        Marketing = "MARKETING",
+        /// Utility/transactional messages
+        Utility = "UTILITY",
+        /// Authentication messages (OTP)
+        Authentication = "AUTHENTICATION",
+    }
 }
 
-impl TemplateCategory {
-    /// Get the string representation
-    pub fn as_str(&self) -> &str {
-        match self {
-            TemplateCategory::Marketing => "MARKETING",
-            TemplateCategory::Utility => "UTILITY",
-            TemplateCategory::Authentication => "AUTHENTICATION",
-        }
+template_enum! {
+    /// Header format
+    HeaderFormat {
+        /// Text header
+        Text = "TEXT",
+        /// Image header
+        Image = "IMAGE",
+        /// Video header
+        Video = "VIDEO",
+        /// Document header
+        Document = "DOCUMENT",
+        /// Location header
+        Location = "LOCATION",
     }
 }
 
-/// Header format
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum HeaderFormat {
-    /// Text header
-    Text,
-    /// Image header
-    Image,
-    /// Video header
-    Video,
-    /// Document header
-    Document,
-    /// Location header
-    Location,
-}
-
-impl HeaderFormat {
-    /// Get the string representation
-    pub fn as_str(&self) -> &str {
-        match self {
-            HeaderFormat::Text => "TEXT",
-            HeaderFormat::Image => "IMAGE",
-            HeaderFormat::Video => "VIDEO",
-            HeaderFormat::Document => "DOCUMENT",
-            HeaderFormat::Location => "LOCATION",
-        }
+/// Format of template placeholders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ParameterFormat {
+    /// Numbered placeholders (`{{1}}`)
+    #[default]
+    Positional,
+    /// Named placeholders (`{{first_name}}`)
+    Named,
+}
+
+impl std::fmt::Display for ParameterFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ParameterFormat::Positional => "positional",
+            ParameterFormat::Named => "named",
+        })
+    }
+}
+
+template_enum! {
+    /// Template component type
+    ComponentKind {
+        /// Header
+        Header = "HEADER",
+        /// Body
+        Body = "BODY",
+        /// Footer
+        Footer = "FOOTER",
+        /// Buttons
+        Buttons = "BUTTONS",
+    }
+}
+
+impl std::fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ComponentKind::Header => "header",
+            ComponentKind::Body => "body",
+            ComponentKind::Footer => "footer",
+            ComponentKind::Buttons => "buttons",
+            ComponentKind::Other(value) => value,
+        })
+    }
+}
+
+template_enum! {
+    /// Template button type
+    ButtonType {
+        /// Quick reply button
+        QuickReply = "QUICK_REPLY",
+        /// Button opening a URL
+        Url = "URL",
+        /// Button calling a phone number
+        PhoneNumber = "PHONE_NUMBER",
+        /// Button copying a code
+        CopyCode = "COPY_CODE",
+        /// One-time password button of authentication templates
+        Otp = "OTP",
+        /// Button opening a Flow
+        Flow = "FLOW",
+        /// Button opening the product catalog
+        Catalog = "CATALOG",
+        /// Button opening a multi-product message
+        Mpm = "MPM",
+        /// Button starting a WhatsApp call
+        VoiceCall = "VOICE_CALL",
+    }
+}
+
+impl std::fmt::Display for ButtonType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
     }
 }
@@ -0,0 +1,353 @@
+//! Pluggable HTTP transport
+//!
+//! Every request made by the [`Client`](crate::Client) is described as an
+//! [`HttpRequest`] and handed to a [`Transport`], which returns an
+//! [`HttpResponse`]. [`ReqwestTransport`] is used by default; implement the
+//! trait to record traffic, serve canned responses in tests, or use another
+//! HTTP stack.
+//!
+//! # Example
+//!
+//! ```rust
+//! use wacloudapi::transport::{Body, HttpRequest, HttpResponse, Transport, TransportFuture};
+//! use wacloudapi::Client;
+//!
+//! struct AlwaysOk;
+//!
+//! impl Transport for AlwaysOk {
+//!     fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+//!         Box::pin(async {
+//!             Ok(HttpResponse::new(
+//!                 reqwest::StatusCode::OK,
+//!                 Body::from(r#"{"success":true}"#),
+//!             ))
+//!         })
+//!     }
+//! }
+//!
+//! # fn example() -> wacloudapi::Result<()> {
+//! let client = Client::builder("token", "phone_id")
+//!     .transport(AlwaysOk)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{MediaUploadError, Result};
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+use futures_util::stream::{self, BoxStream, StreamExt, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Size of the chunks read by [`Body::from_reader`]
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Future returned by [`Transport::send`]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 'a>>;
+
+/// Sends HTTP requests on behalf of the client
+pub trait Transport: Send + Sync + 'static {
+    /// Send a request and return the response
+    ///
+    /// Non-success status codes must be returned as responses, not errors;
+    /// the client turns them into API errors.
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+/// Request or response body
+pub enum Body {
+    /// Body held in memory
+    Bytes(Bytes),
+    /// Body produced chunk by chunk
+    Stream(BoxStream<'static, std::io::Result<Bytes>>),
+}
+
+impl Body {
+    /// Create an empty body
+    pub fn empty() -> Self {
+        Body::Bytes(Bytes::new())
+    }
+
+    /// Create a body from a stream of chunks
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+    {
+        Body::Stream(stream.boxed())
+    }
+
+    /// Create a body that reads from `reader` in chunks
+    pub fn from_reader<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + 'static,
+    {
+        let reader = Some(Box::pin(reader));
+        Body::from_stream(stream::unfold(reader, |reader| async move {
+            let mut reader = reader?;
+            let mut buf = BytesMut::with_capacity(READ_CHUNK_SIZE);
+            match reader.read_buf(&mut buf).await {
+                Ok(0) => None,
+                Ok(_) => Some((Ok(buf.freeze()), Some(reader))),
+                Err(e) => Some((Err(e), None)),
+            }
+        }))
+    }
+
+    /// Get the body if it is held in memory
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Body::Bytes(bytes) => Some(bytes),
+            Body::Stream(_) => None,
+        }
+    }
+
+    /// Read the whole body into memory
+    pub async fn into_bytes(self) -> Result<Bytes> {
+        match self {
+            Body::Bytes(bytes) => Ok(bytes),
+            Body::Stream(stream) => {
+                let chunks: Vec<Bytes> = stream.try_collect().await?;
+                let mut buf = BytesMut::with_capacity(chunks.iter().map(Bytes::len).sum());
+                for chunk in chunks {
+                    buf.extend_from_slice(&chunk);
+                }
+                Ok(buf.freeze())
+            }
+        }
+    }
+
+    /// Turn the body into a stream of chunks
+    pub fn into_stream(self) -> BoxStream<'static, std::io::Result<Bytes>> {
+        match self {
+            Body::Bytes(bytes) if bytes.is_empty() => stream::empty().boxed(),
+            Body::Bytes(bytes) => stream::once(async move { Ok(bytes) }).boxed(),
+            Body::Stream(stream) => stream,
+        }
+    }
+}
+
+impl std::fmt::Debug for Body {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Body::Bytes(bytes) => f.debug_tuple("Bytes").field(&bytes.len()).finish(),
+            Body::Stream(_) => f.write_str("Stream"),
+        }
+    }
+}
+
+impl From<Bytes> for Body {
+    fn from(bytes: Bytes) -> Self {
+        Body::Bytes(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Body::Bytes(bytes.into())
+    }
+}
+
+impl From<String> for Body {
+    fn from(text: String) -> Self {
+        Body::Bytes(text.into())
+    }
+}
+
+impl From<&'static str> for Body {
+    fn from(text: &'static str) -> Self {
+        Body::Bytes(Bytes::from_static(text.as_bytes()))
+    }
+}
+
+/// Description of an HTTP request
+#[derive(Debug)]
+pub struct HttpRequest {
+    /// HTTP method
+    pub method: Method,
+    /// Absolute URL
+    pub url: String,
+    /// Request headers, including `Authorization`
+    pub headers: HeaderMap,
+    /// Request body
+    pub body: Body,
+}
+
+impl HttpRequest {
+    /// Create a request without headers or body
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: Body::empty(),
+        }
+    }
+}
+
+/// HTTP response returned by a transport
+#[derive(Debug)]
+pub struct HttpResponse {
+    /// HTTP status code
+    pub status: StatusCode,
+    /// Response headers
+    pub headers: HeaderMap,
+    /// Response body
+    pub body: Body,
+}
+
+impl HttpResponse {
+    /// Create a response without headers
+    pub fn new(status: StatusCode, body: Body) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body,
+        }
+    }
+}
+
+/// Default transport backed by `reqwest`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    /// Wrap a `reqwest::Client`
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Get the underlying `reqwest::Client`
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let body = match request.body {
+                Body::Bytes(bytes) => reqwest::Body::from(bytes),
+                Body::Stream(stream) => reqwest::Body::wrap_stream(stream),
+            };
+
+            let response = self
+                .client
+                .request(request.method, request.url)
+                .headers(request.headers)
+                .body(body)
+                .send()
+                .await?;
+
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = Body::from_stream(response.bytes_stream().map_err(std::io::Error::other));
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+/// Multipart form encoded into a streaming body
+pub(crate) struct Multipart {
+    boundary: String,
+    parts: Vec<Body>,
+}
+
+impl Multipart {
+    pub(crate) fn new() -> Self {
+        let random = RandomState::new().build_hasher().finish();
+        Self {
+            boundary: format!("wacloudapi-{:016x}", random),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Add a text field
+    pub(crate) fn text(mut self, name: &str, value: impl Into<String>) -> Self {
+        let header = self.part_header(name, None, None);
+        self.parts
+            .push(Body::from(format!("{}{}\r\n", header, value.into())));
+        self
+    }
+
+    /// Add a file field
+    pub(crate) fn file(
+        mut self,
+        name: &str,
+        filename: &str,
+        content_type: &str,
+        data: Body,
+    ) -> Result<Self> {
+        HeaderValue::from_str(content_type)
+            .map_err(|_| MediaUploadError::InvalidMimeType(content_type.to_string()))?;
+
+        let header = self.part_header(name, Some(filename), Some(content_type));
+        self.parts.push(Body::from(header));
+        self.parts.push(data);
+        self.parts.push(Body::from("\r\n"));
+        Ok(self)
+    }
+
+    /// Value of the `Content-Type` header for this form
+    pub(crate) fn content_type(&self) -> HeaderValue {
+        HeaderValue::from_str(&format!("multipart/form-data; boundary={}", self.boundary))
+            .expect("boundary is a valid header value")
+    }
+
+    /// Encode the form into a request body
+    pub(crate) fn into_body(self) -> Body {
+        let mut parts = self.parts;
+        parts.push(Body::from(format!("--{}--\r\n", self.boundary)));
+
+        if parts.iter().all(|part| part.as_bytes().is_some()) {
+            let mut buf = BytesMut::new();
+            for part in &parts {
+                buf.extend_from_slice(part.as_bytes().unwrap_or_default());
+            }
+            return Body::Bytes(buf.freeze());
+        }
+
+        Body::from_stream(stream::iter(parts).flat_map(Body::into_stream))
+    }
+
+    fn part_header(
+        &self,
+        name: &str,
+        filename: Option<&str>,
+        content_type: Option<&str>,
+    ) -> String {
+        let mut header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            self.boundary,
+            escape_quoted(name)
+        );
+        if let Some(filename) = filename {
+            header.push_str(&format!("; filename=\"{}\"", escape_quoted(filename)));
+        }
+        header.push_str("\r\n");
+        if let Some(content_type) = content_type {
+            header.push_str(&format!("Content-Type: {}\r\n", content_type));
+        }
+        header.push_str("\r\n");
+        header
+    }
+}
+
+fn escape_quoted(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(['\r', '\n'], " ")
+}
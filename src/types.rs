@@ -83,24 +83,31 @@ pub struct PhoneNumbersResponse {
     pub paging: Option<Paging>,
 }
 
-/// Paging info
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Paging info of a Graph API list response
+///
+/// Use [`Paginator`](crate::pagination::Paginator) to follow it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Paging {
     /// Cursors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cursors: Option<Cursors>,
     /// Next page URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub next: Option<String>,
     /// Previous page URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub previous: Option<String>,
 }
 
 /// Cursors for pagination
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Cursors {
     /// Before cursor
-    pub before: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
     /// After cursor
-    pub after: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
 }
 
 /// WhatsApp Business Account (WABA)
@@ -0,0 +1,372 @@
+//! Resumable Upload API for template header samples and large files
+//!
+//! Media headers of message templates need a sample uploaded through the
+//! Resumable Upload API rather than the Media API. An upload session is
+//! created with the file's name, length and MIME type, then the content is
+//! sent in chunks, each from a `file_offset`. After each chunk, and when the
+//! transfer is interrupted, the session reports how much it received and the
+//! upload resumes from there. The
+//! finished upload returns a handle (`h`) to use in
+//! [`CreateTemplate::with_media_header`](crate::templates::CreateTemplate::with_media_header).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wacloudapi::templates::{CreateTemplate, HeaderFormat, TemplateCategory};
+//! use wacloudapi::Client;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("token", "phone_id");
+//! let upload = client.uploads("app_id").upload_file("./banner.jpg").await?;
+//!
+//! let template = CreateTemplate::new("summer_sale", TemplateCategory::Marketing, "en_US")
+//!     .with_media_header(HeaderFormat::Image, upload.h)
+//!     .with_body("Our summer sale starts today!");
+//! client.templates().create("waba_id", &template).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::transport::{Body, HttpRequest};
+use bytes::Bytes;
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::Method;
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use url::Url;
+
+/// Default number of attempts made to finish an upload
+pub const DEFAULT_UPLOAD_ATTEMPTS: u32 = 3;
+
+/// Default number of bytes sent per request
+pub const DEFAULT_UPLOAD_CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Header carrying the offset an upload starts from
+const FILE_OFFSET: HeaderName = HeaderName::from_static("file_offset");
+
+/// Resumable Upload API client
+pub struct UploadSessionApi {
+    client: Client,
+    app_id: String,
+    max_attempts: u32,
+    chunk_size: u64,
+}
+
+impl UploadSessionApi {
+    pub(crate) fn new(client: Client, app_id: String) -> Self {
+        Self {
+            client,
+            app_id,
+            max_attempts: DEFAULT_UPLOAD_ATTEMPTS,
+            chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE,
+        }
+    }
+
+    /// Set how many times an interrupted upload is attempted in total
+    ///
+    /// Defaults to [`DEFAULT_UPLOAD_ATTEMPTS`]. Each new attempt resumes
+    /// from the offset reported by the session.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set how many bytes are sent per request
+    ///
+    /// Defaults to [`DEFAULT_UPLOAD_CHUNK_SIZE`]. Smaller chunks lose less
+    /// progress when a request is interrupted, at the cost of more requests.
+    pub fn chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Create an upload session
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - Name of the file
+    /// * `file_length` - Size of the file in bytes
+    /// * `file_type` - MIME type of the file
+    pub async fn create_session(
+        &self,
+        file_name: &str,
+        file_length: u64,
+        file_type: &str,
+    ) -> Result<UploadSession> {
+        let mut url = Url::parse(
+            &self
+                .client
+                .endpoint_url(&format!("{}/uploads", self.app_id)),
+        )?;
+        url.query_pairs_mut()
+            .append_pair("file_name", file_name)
+            .append_pair("file_length", &file_length.to_string())
+            .append_pair("file_type", file_type);
+
+        self.client
+            .execute(false, || {
+                Ok(self.client.request(Method::POST, url.as_str()))
+            })
+            .await
+    }
+
+    /// Get the status of an upload session, including the offset to resume from
+    ///
+    /// # Arguments
+    ///
+    /// * `session_id` - Session ID returned by [`create_session`](Self::create_session)
+    pub async fn status(&self, session_id: &str) -> Result<UploadSessionStatus> {
+        let url = self.client.endpoint_url(session_id);
+        self.client
+            .execute(true, || {
+                let mut request = self.client.request(Method::GET, &url);
+                request
+                    .headers
+                    .insert(AUTHORIZATION, self.client.oauth_authorization());
+                Ok(request)
+            })
+            .await
+    }
+
+    /// Send the content of a session starting at `file_offset`
+    ///
+    /// `body` holds the content from `file_offset` to the end of the file.
+    /// This is a single request; see [`resume_file`](Self::resume_file) and
+    /// [`resume_bytes`](Self::resume_bytes) to pick up where the session
+    /// left off in chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_id` - Session ID returned by [`create_session`](Self::create_session)
+    /// * `file_offset` - Offset of the first byte of `body` in the file
+    /// * `body` - Remaining content of the file
+    pub async fn upload(
+        &self,
+        session_id: &str,
+        file_offset: u64,
+        body: Body,
+    ) -> Result<UploadHandle> {
+        self.client
+            .execute_once(self.upload_request(session_id, file_offset, body))
+            .await
+    }
+
+    fn upload_request(&self, session_id: &str, file_offset: u64, body: Body) -> HttpRequest {
+        let url = self.client.endpoint_url(session_id);
+        let mut request = self.client.request(Method::POST, &url);
+        request
+            .headers
+            .insert(AUTHORIZATION, self.client.oauth_authorization());
+        request.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        );
+        request
+            .headers
+            .insert(FILE_OFFSET, HeaderValue::from(file_offset));
+        request.body = body;
+        request
+    }
+
+    /// Upload a file, resuming after interruptions
+    ///
+    /// The file is streamed in chunks of [`chunk_size`](Self::chunk_size),
+    /// not read into memory. Its MIME type is guessed from the extension.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use wacloudapi::Client;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = Client::new("token", "phone_id");
+    /// let upload = client.uploads("app_id").upload_file("./brochure.pdf").await?;
+    /// println!("Handle: {}", upload.h);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn upload_file(&self, path: impl AsRef<Path>) -> Result<UploadHandle> {
+        let path = path.as_ref();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("file");
+        let file_type = mime_guess::from_path(path).first_or_octet_stream();
+        let file_length = tokio::fs::metadata(path).await?.len();
+
+        let session = self
+            .create_session(file_name, file_length, file_type.as_ref())
+            .await?;
+        self.send_resumable(&session.id, 0, file_length, |start, end| {
+            file_body(path, start, end)
+        })
+        .await
+    }
+
+    /// Upload bytes in chunks of [`chunk_size`](Self::chunk_size), resuming
+    /// after interruptions
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - File content
+    /// * `file_name` - Name of the file
+    /// * `file_type` - MIME type of the file
+    pub async fn upload_bytes(
+        &self,
+        data: impl Into<Bytes>,
+        file_name: &str,
+        file_type: &str,
+    ) -> Result<UploadHandle> {
+        let data = data.into();
+        let file_length = data.len() as u64;
+        let session = self
+            .create_session(file_name, file_length, file_type)
+            .await?;
+        self.send_resumable(&session.id, 0, file_length, |start, end| {
+            bytes_body(&data, start, end)
+        })
+        .await
+    }
+
+    /// Finish an existing session from a file
+    ///
+    /// Asks the session for the offset it reached, then sends the rest of
+    /// the file. Use it to finish an upload started by a previous process.
+    pub async fn resume_file(
+        &self,
+        session_id: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<UploadHandle> {
+        let path = path.as_ref();
+        let file_length = tokio::fs::metadata(path).await?.len();
+        let offset = self.status(session_id).await?.file_offset;
+        self.send_resumable(session_id, offset, file_length, |start, end| {
+            file_body(path, start, end)
+        })
+        .await
+    }
+
+    /// Finish an existing session from bytes
+    ///
+    /// Asks the session for the offset it reached, then sends the rest of
+    /// `data`.
+    pub async fn resume_bytes(
+        &self,
+        session_id: &str,
+        data: impl Into<Bytes>,
+    ) -> Result<UploadHandle> {
+        let data = data.into();
+        let file_length = data.len() as u64;
+        let offset = self.status(session_id).await?.file_offset;
+        self.send_resumable(session_id, offset, file_length, |start, end| {
+            bytes_body(&data, start, end)
+        })
+        .await
+    }
+
+    /// Send the content from `offset` in chunks, resuming from the session's
+    /// offset after each chunk and after interruptions
+    ///
+    /// `body` returns the content from its first to its second offset.
+    async fn send_resumable<F, Fut>(
+        &self,
+        session_id: &str,
+        mut offset: u64,
+        file_length: u64,
+        body: F,
+    ) -> Result<UploadHandle>
+    where
+        F: Fn(u64, u64) -> Fut,
+        Fut: Future<Output = Result<Body>>,
+    {
+        let mut attempt = 1;
+        loop {
+            let end = offset.saturating_add(self.chunk_size).min(file_length);
+            let request = self.upload_request(session_id, offset, body(offset, end).await?);
+            let result = if end < file_length {
+                // Only the last chunk is answered with the handle
+                self.client
+                    .execute_once::<IgnoredAny>(request)
+                    .await
+                    .map(|_| None)
+            } else {
+                self.client.execute_once(request).await.map(Some)
+            };
+
+            let started_at = offset;
+            match result {
+                Ok(Some(handle)) => return Ok(handle),
+                Ok(None) => {
+                    offset = self.status(session_id).await?.file_offset;
+                    if offset > started_at {
+                        continue;
+                    }
+                    if attempt >= self.max_attempts {
+                        return Err(Error::UploadStalled {
+                            session_id: session_id.to_string(),
+                            file_offset: offset,
+                        });
+                    }
+                }
+                Err(error) => {
+                    if attempt >= self.max_attempts || !is_interruption(&error) {
+                        return Err(error);
+                    }
+                    offset = self.status(session_id).await?.file_offset;
+                }
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Whether an upload failed in a way that resuming can fix
+fn is_interruption(error: &Error) -> bool {
+    match error {
+        Error::Request(_) | Error::Io(_) | Error::Transport(_) => true,
+        // Resuming right away would only hit the limit again
+        Error::RateLimited { .. } => false,
+        error => error.error_code().is_some_and(|code| code.is_retryable()),
+    }
+}
+
+async fn file_body(path: &Path, start: u64, end: u64) -> Result<Body> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(start)).await?;
+    Ok(Body::from_reader(file.take(end.saturating_sub(start))))
+}
+
+async fn bytes_body(data: &Bytes, start: u64, end: u64) -> Result<Body> {
+    let index = |offset: u64| {
+        usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(data.len())
+    };
+    let start = index(start);
+    Ok(Body::Bytes(data.slice(start..index(end).max(start))))
+}
+
+/// Upload session created by [`UploadSessionApi::create_session`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    /// Session ID (`upload:<id>`)
+    pub id: String,
+}
+
+/// Status of an upload session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSessionStatus {
+    /// Session ID (`upload:<id>`)
+    pub id: String,
+    /// Number of bytes received so far, where the upload resumes
+    pub file_offset: u64,
+}
+
+/// Handle of a finished upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadHandle {
+    /// Handle to use as a template header sample
+    pub h: String,
+}
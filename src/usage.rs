@@ -0,0 +1,220 @@
+//! API usage reported in response headers
+//!
+//! Graph API responses carry `X-App-Usage` and `X-Business-Use-Case-Usage`
+//! headers with the share of the rate limit already used. The client keeps
+//! the latest snapshot, available through [`Client::usage`](crate::Client::usage),
+//! so callers can slow down before they are throttled.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wacloudapi::Client;
+//!
+//! # async fn example() -> wacloudapi::Result<()> {
+//! let client = Client::new("token", "phone_id");
+//! client.phone_numbers().get("phone_id").await?;
+//!
+//! if let Some(usage) = client.usage() {
+//!     if usage.max_usage() > 80 {
+//!         // back off before hitting the limit
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Header with app-level usage
+pub const APP_USAGE_HEADER: &str = "x-app-usage";
+
+/// Header with usage per business object and use case
+pub const BUSINESS_USE_CASE_USAGE_HEADER: &str = "x-business-use-case-usage";
+
+/// Usage snapshot parsed from response headers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageInfo {
+    /// App-level usage from `X-App-Usage`
+    pub app: Option<AppUsage>,
+    /// Usage per business object ID from `X-Business-Use-Case-Usage`
+    pub business_use_cases: HashMap<String, Vec<BusinessUseCaseUsage>>,
+}
+
+/// App-level usage, as percentages of the limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppUsage {
+    /// Percentage of allowed calls made
+    #[serde(default)]
+    pub call_count: u32,
+    /// Percentage of allowed CPU time used
+    #[serde(default)]
+    pub total_cputime: u32,
+    /// Percentage of allowed total time used
+    #[serde(default)]
+    pub total_time: u32,
+}
+
+/// Usage of one business use case, as percentages of the limit
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusinessUseCaseUsage {
+    /// Use case (e.g. "whatsapp_business_management")
+    #[serde(rename = "type")]
+    pub use_case: String,
+    /// Percentage of allowed calls made
+    #[serde(default)]
+    pub call_count: u32,
+    /// Percentage of allowed CPU time used
+    #[serde(default)]
+    pub total_cputime: u32,
+    /// Percentage of allowed total time used
+    #[serde(default)]
+    pub total_time: u32,
+    /// Minutes until calls are no longer throttled
+    #[serde(default)]
+    pub estimated_time_to_regain_access: u64,
+}
+
+impl AppUsage {
+    /// Highest of the three percentages
+    pub fn max_usage(&self) -> u32 {
+        self.call_count.max(self.total_cputime).max(self.total_time)
+    }
+}
+
+impl BusinessUseCaseUsage {
+    /// Highest of the three percentages
+    pub fn max_usage(&self) -> u32 {
+        self.call_count.max(self.total_cputime).max(self.total_time)
+    }
+}
+
+impl UsageInfo {
+    /// Parse the usage headers of a response
+    ///
+    /// Returns `None` if neither header is present. Malformed headers are
+    /// ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let app = header_json::<AppUsage>(headers, APP_USAGE_HEADER);
+        let business_use_cases = header_json(headers, BUSINESS_USE_CASE_USAGE_HEADER);
+
+        if app.is_none() && business_use_cases.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            app,
+            business_use_cases: business_use_cases.unwrap_or_default(),
+        })
+    }
+
+    /// Highest usage percentage across all reported limits
+    pub fn max_usage(&self) -> u32 {
+        self.business_use_cases
+            .values()
+            .flatten()
+            .map(BusinessUseCaseUsage::max_usage)
+            .chain(self.app.as_ref().map(AppUsage::max_usage))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Longest estimated time until throttled use cases regain access
+    pub fn estimated_time_to_regain_access(&self) -> Option<Duration> {
+        self.business_use_cases
+            .values()
+            .flatten()
+            .map(|usage| usage.estimated_time_to_regain_access)
+            .filter(|minutes| *minutes > 0)
+            .max()
+            .map(|minutes| Duration::from_secs(minutes * 60))
+    }
+}
+
+/// Parse the `Retry-After` header as a number of seconds
+///
+/// Both forms of the header are accepted: a number of seconds, or an HTTP
+/// date, which is turned into the seconds left until then (0 if it has
+/// passed).
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(seconds);
+    }
+
+    let date = UNIX_EPOCH.checked_add(Duration::from_secs(parse_http_date(value)?))?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .map_or(0, |remaining| remaining.as_secs()),
+    )
+}
+
+/// Parse an HTTP date into seconds since the Unix epoch
+///
+/// Accepts the IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`) and the obsolete
+/// RFC 850 (`Sunday, 06-Nov-94 08:49:37 GMT`) and asctime
+/// (`Sun Nov  6 08:49:37 1994`) forms. In all of them the day comes before
+/// the year, so the fields can be told apart by their shape. Years outside
+/// 1970-9999 are rejected.
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut tokens = value
+        .split(|c: char| c.is_ascii_whitespace() || c == ',' || c == '-')
+        .filter(|token| !token.is_empty());
+    // Weekday
+    tokens.next()?;
+
+    let (mut month, mut time, mut numbers) = (None, None, Vec::new());
+    for token in tokens {
+        if token.contains(':') {
+            time = Some(token);
+        } else if token.bytes().all(|b| b.is_ascii_digit()) {
+            numbers.push(token.parse::<u64>().ok()?);
+        } else if let Some(index) = MONTHS.iter().position(|m| *m == token) {
+            month = Some(index as u64 + 1);
+        } else if token != "GMT" {
+            return None;
+        }
+    }
+
+    let [day, year] = numbers[..] else {
+        return None;
+    };
+    let year = match year {
+        0..=69 => year + 2000,
+        70..=99 => year + 1900,
+        _ => year,
+    };
+    let mut clock = time?.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (clock.next()??, clock.next()??, clock.next()??);
+    // Four-digit years keep the arithmetic below far from overflowing
+    if clock.next().is_some() || !(1..=31).contains(&day) || !(1970..=9999).contains(&year) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Days since the epoch of a proleptic Gregorian date
+    let month = month?;
+    let (y, m) = match month {
+        1 | 2 => (year - 1, month + 9),
+        _ => (year, month - 3),
+    };
+    let era = y / 400;
+    let year_of_era = y % 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+fn header_json<T: for<'de> Deserialize<'de>>(headers: &HeaderMap, name: &str) -> Option<T> {
+    serde_json::from_slice(headers.get(name)?.as_bytes()).ok()
+}
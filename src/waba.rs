@@ -2,6 +2,8 @@
 
 use crate::client::Client;
 use crate::error::Result;
+use crate::pagination::{PageParams, Paginator};
+use crate::types::Paging;
 use serde::{Deserialize, Serialize};
 
 /// WABA Management API client
@@ -75,24 +77,60 @@ impl WabaApi {
         self.client.get(&url).await
     }
 
+    /// Stream all subscribed apps, following paging cursors
+    pub fn get_subscribed_apps_paginated(
+        &self,
+        params: PageParams,
+    ) -> Paginator<SubscribedAppsResponse> {
+        let url = format!(
+            "{}/subscribed_apps",
+            self.client.endpoint_url(&self.waba_id)
+        );
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get phone numbers for this WABA
     pub async fn get_phone_numbers(&self) -> Result<PhoneNumbersResponse> {
         let url = format!("{}/phone_numbers", self.client.endpoint_url(&self.waba_id));
         self.client.get(&url).await
     }
 
+    /// Stream all phone numbers for this WABA, following paging cursors
+    pub fn get_phone_numbers_paginated(
+        &self,
+        params: PageParams,
+    ) -> Paginator<PhoneNumbersResponse> {
+        let url = format!("{}/phone_numbers", self.client.endpoint_url(&self.waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get assigned users for this WABA
     pub async fn get_assigned_users(&self) -> Result<AssignedUsersResponse> {
         let url = format!("{}/assigned_users", self.client.endpoint_url(&self.waba_id));
         self.client.get(&url).await
     }
 
+    /// Stream all assigned users for this WABA, following paging cursors
+    pub fn get_assigned_users_paginated(
+        &self,
+        params: PageParams,
+    ) -> Paginator<AssignedUsersResponse> {
+        let url = format!("{}/assigned_users", self.client.endpoint_url(&self.waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get system users for this WABA
     pub async fn get_system_users(&self) -> Result<SystemUsersResponse> {
         let url = format!("{}/system_users", self.client.endpoint_url(&self.waba_id));
         self.client.get(&url).await
     }
 
+    /// Stream all system users for this WABA, following paging cursors
+    pub fn get_system_users_paginated(&self, params: PageParams) -> Paginator<SystemUsersResponse> {
+        let url = format!("{}/system_users", self.client.endpoint_url(&self.waba_id));
+        Paginator::new(self.client.clone(), url, params)
+    }
+
     /// Get message templates for this WABA
     pub async fn get_templates(&self) -> Result<WabaTemplatesResponse> {
         let url = format!(
@@ -101,6 +139,15 @@ impl WabaApi {
         );
         self.client.get(&url).await
     }
+
+    /// Stream all message templates for this WABA, following paging cursors
+    pub fn get_templates_paginated(&self, params: PageParams) -> Paginator<WabaTemplatesResponse> {
+        let url = format!(
+            "{}/message_templates",
+            self.client.endpoint_url(&self.waba_id)
+        );
+        Paginator::new(self.client.clone(), url, params)
+    }
 }
 
 /// Webhook fields that can be subscribed
@@ -187,6 +234,9 @@ pub struct WabaDetails {
 pub struct SubscribedAppsResponse {
     /// List of subscribed apps
     pub data: Vec<SubscribedApp>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// Subscribed app info
@@ -207,6 +257,9 @@ pub struct SubscribedApp {
 pub struct PhoneNumbersResponse {
     /// List of phone numbers
     pub data: Vec<WabaPhoneNumber>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// Phone number info
@@ -229,6 +282,9 @@ pub struct WabaPhoneNumber {
 pub struct AssignedUsersResponse {
     /// List of assigned users
     pub data: Vec<AssignedUser>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// Assigned user info
@@ -249,6 +305,9 @@ pub struct AssignedUser {
 pub struct SystemUsersResponse {
     /// List of system users
     pub data: Vec<SystemUser>,
+    /// Paging info
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paging: Option<Paging>,
 }
 
 /// System user info
@@ -290,25 +349,3 @@ pub struct WabaTemplate {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
 }
-
-/// Paging info
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Paging {
-    /// Cursors
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub cursors: Option<PagingCursors>,
-    /// Next page URL
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub next: Option<String>,
-}
-
-/// Paging cursors
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PagingCursors {
-    /// Before cursor
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub before: Option<String>,
-    /// After cursor
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub after: Option<String>,
-}
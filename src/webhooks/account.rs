@@ -0,0 +1,133 @@
+//! Payloads of the account and phone number webhook fields
+
+use serde::{Deserialize, Serialize};
+
+/// Value of an `account_update` change
+///
+/// Sent when the business account is verified, banned, restricted, deleted,
+/// or violates a policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountUpdate {
+    /// What happened (VERIFIED_ACCOUNT, DISABLED_UPDATE, ACCOUNT_VIOLATION,
+    /// ACCOUNT_RESTRICTION, ACCOUNT_DELETED, PARTNER_ADDED, ...)
+    pub event: String,
+    /// Phone number the update is about, if any
+    #[serde(default)]
+    pub phone_number: Option<String>,
+    /// Ban state, for DISABLED_UPDATE
+    #[serde(default)]
+    pub ban_info: Option<BanInfo>,
+    /// Active restrictions, for ACCOUNT_RESTRICTION
+    #[serde(default)]
+    pub restriction_info: Option<Vec<RestrictionInfo>>,
+    /// Violated policy, for ACCOUNT_VIOLATION
+    #[serde(default)]
+    pub violation_info: Option<ViolationInfo>,
+}
+
+/// Ban state of a business account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BanInfo {
+    /// Ban state (SCHEDULE_FOR_DISABLE, DISABLE, REINSTATE)
+    pub waba_ban_state: String,
+    /// Date the ban takes or took effect
+    #[serde(default)]
+    pub waba_ban_date: Option<String>,
+}
+
+/// Restriction placed on a business account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestrictionInfo {
+    /// Restriction (RESTRICTED_ADD_PHONE_NUMBER_ACTION,
+    /// RESTRICTED_BIZ_INITIATED_MESSAGING,
+    /// RESTRICTED_CUSTOMER_INITIATED_MESSAGING)
+    pub restriction_type: String,
+    /// When the restriction expires
+    #[serde(default)]
+    pub expiration: Option<String>,
+}
+
+/// Policy violation of a business account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViolationInfo {
+    /// Violated policy (e.g. "ADULT", "SCAM")
+    pub violation_type: String,
+}
+
+/// Value of an `account_review_update` change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountReviewUpdate {
+    /// Review decision (APPROVED, REJECTED, PENDING, DEFERRED)
+    pub decision: String,
+}
+
+/// Value of a `phone_number_name_update` change
+///
+/// Sent when a requested display name is approved or rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoneNumberNameUpdate {
+    /// Phone number the name belongs to
+    pub display_phone_number: String,
+    /// Review decision (APPROVED, REJECTED, PENDING, DEFERRED)
+    pub decision: String,
+    /// Display name that was requested
+    pub requested_verified_name: String,
+    /// Why the name was rejected
+    #[serde(default)]
+    pub rejection_reason: Option<String>,
+}
+
+/// Value of a `phone_number_quality_update` change
+///
+/// Sent when a phone number's quality rating or messaging limit changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhoneNumberQualityUpdate {
+    /// Phone number the update is about
+    pub display_phone_number: String,
+    /// What happened (FLAGGED, UNFLAGGED, DOWNGRADE, UPGRADE, ONBOARDING)
+    pub event: String,
+    /// Messaging limit tier after the change (e.g. "TIER_1K", "TIER_UNLIMITED")
+    #[serde(default)]
+    pub current_limit: Option<String>,
+    /// Messaging limit tier before the change
+    #[serde(default)]
+    pub old_limit: Option<String>,
+    /// Business-initiated conversations allowed per day, if reported
+    #[serde(default)]
+    pub max_daily_conversation_per_phone: Option<u64>,
+}
+
+impl PhoneNumberQualityUpdate {
+    /// Whether the number was flagged or its messaging limit lowered
+    pub fn is_degraded(&self) -> bool {
+        matches!(self.event.as_str(), "FLAGGED" | "DOWNGRADE")
+    }
+}
+
+/// Value of a `business_capability_update` change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusinessCapabilityUpdate {
+    /// Business-initiated conversations allowed per phone number per day
+    #[serde(default)]
+    pub max_daily_conversation_per_phone: Option<u64>,
+    /// Phone numbers allowed per business
+    #[serde(default)]
+    pub max_phone_numbers_per_business: Option<u64>,
+    /// Phone numbers allowed per business account
+    #[serde(default)]
+    pub max_phone_numbers_per_waba: Option<u64>,
+}
+
+/// Value of a `security` change
+///
+/// Sent when the two-step verification PIN of a number is changed or reset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityUpdate {
+    /// Phone number the event is about
+    pub display_phone_number: String,
+    /// What happened (PIN_CHANGED, PIN_RESET_REQUEST, PIN_RESET_SUCCESS, ...)
+    pub event: String,
+    /// Who requested the change
+    #[serde(default)]
+    pub requester: Option<String>,
+}
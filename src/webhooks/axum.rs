@@ -0,0 +1,69 @@
+//! Ready-made [axum](https://docs.rs/axum) router for the webhook endpoint
+//!
+//! Requires the `axum` feature.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wacloudapi::webhooks::{self, WebhookEndpoint, WebhookEvent};
+//!
+//! # async fn example() {
+//! let (sender, mut events) = tokio::sync::mpsc::channel::<WebhookEvent>(100);
+//! let endpoint = WebhookEndpoint::new("verify_token", "app_secret", sender);
+//!
+//! tokio::spawn(async move {
+//!     while let Some(event) = events.recv().await {
+//!         println!("{:?}", event);
+//!     }
+//! });
+//!
+//! // Serve with `axum::serve` next to the application's own routes
+//! let app: axum::Router = axum::Router::new().merge(webhooks::axum::router("/webhook", endpoint));
+//! # }
+//! ```
+
+use super::{EndpointResponse, VerificationQuery, WebhookEndpoint, SIGNATURE_HEADER};
+use ::axum::body::Bytes;
+use ::axum::extract::{Query, State};
+use ::axum::http::HeaderMap;
+use ::axum::response::{IntoResponse, Response};
+use ::axum::routing::get;
+use ::axum::Router;
+
+/// Build a router serving `endpoint` at `path`
+///
+/// `GET` answers the verification handshake and `POST` receives deliveries;
+/// see [`WebhookEndpoint`] for the status codes returned. The request body
+/// size is bounded by axum's default body limit.
+pub fn router<S>(path: &str, endpoint: WebhookEndpoint) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(path, get(verify).post(receive))
+        .with_state(endpoint)
+}
+
+async fn verify(
+    State(endpoint): State<WebhookEndpoint>,
+    Query(query): Query<VerificationQuery>,
+) -> EndpointResponse {
+    endpoint.verify(&query)
+}
+
+async fn receive(
+    State(endpoint): State<WebhookEndpoint>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> EndpointResponse {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok());
+    endpoint.receive(signature, &body).await
+}
+
+impl IntoResponse for EndpointResponse {
+    fn into_response(self) -> Response {
+        (self.status, self.body).into_response()
+    }
+}
@@ -0,0 +1,429 @@
+//! Deduplication of redelivered webhook events
+//!
+//! Meta retries deliveries that were not acknowledged in time and sometimes
+//! delivers the same message or status twice. A [`Deduplicator`] remembers the
+//! [`dedup_key`](WebhookEnvelope::dedup_key) of every event in a
+//! [`DedupStore`] and drops events it has already seen.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use wacloudapi::webhooks::{MemoryDedupStore, WebhookRouter};
+//!
+//! let router = WebhookRouter::new()
+//!     .dedup(MemoryDedupStore::new(10_000, Duration::from_secs(24 * 60 * 60)))
+//!     .on_text(|envelope| async move {
+//!         // Runs once per message, even if Meta delivers it twice
+//!         println!("{:?}", envelope.event);
+//!         Ok(())
+//!     });
+//! ```
+
+use super::{
+    HandlerError, Middleware, MiddlewareAfterFuture, MiddlewareFuture, Next, Outcome,
+    WebhookEnvelope, WebhookEvent, WebhookPayload,
+};
+use crate::media_cache::temp_path;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Future returned by [`DedupStore`] methods
+pub type DedupFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, HandlerError>> + Send + 'a>>;
+
+/// Remembers which events were already processed
+///
+/// Implementations must be safe to share between concurrent deliveries, and
+/// must not block the async runtime: slow I/O belongs in the returned future
+/// or in [`tokio::task::spawn_blocking`].
+pub trait DedupStore: Send + Sync + 'static {
+    /// Record `key` as seen; returns whether it was not seen before
+    fn insert<'a>(&'a self, key: &'a str) -> DedupFuture<'a, bool>;
+
+    /// Forget `key`, so a redelivery of its event is processed again
+    fn remove<'a>(&'a self, key: &'a str) -> DedupFuture<'a, ()>;
+}
+
+/// Keys seen recently, bounded by count and age
+///
+/// A key expires `ttl` after it was last seen. When more than `capacity` keys
+/// are held, the least recently seen ones are dropped.
+#[derive(Debug)]
+struct Entries {
+    capacity: usize,
+    ttl: Duration,
+    /// Sequence number and last-seen time of each key
+    seen: HashMap<String, (u64, Duration)>,
+    /// Keys by sequence number, least recently seen first
+    order: BTreeMap<u64, String>,
+    next_seq: u64,
+}
+
+impl Entries {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            seen: HashMap::new(),
+            order: BTreeMap::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Mark `key` as seen at `now`; returns whether it was not held
+    fn touch(&mut self, key: &str, now: Duration) -> bool {
+        self.purge(now);
+        let new = self.remove(key).is_none();
+
+        self.seen.insert(key.to_string(), (self.next_seq, now));
+        self.order.insert(self.next_seq, key.to_string());
+        self.next_seq += 1;
+
+        while self.seen.len() > self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.seen.remove(&oldest);
+            }
+        }
+        new
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Duration> {
+        let (seq, seen_at) = self.seen.remove(key)?;
+        self.order.remove(&seq);
+        Some(seen_at)
+    }
+
+    /// Drop the keys last seen more than `ttl` before `now`
+    fn purge(&mut self, now: Duration) {
+        while let Some((_, key)) = self.order.first_key_value() {
+            let (_, seen_at) = self.seen[key];
+            if now.saturating_sub(seen_at) < self.ttl {
+                break;
+            }
+            if let Some((_, key)) = self.order.pop_first() {
+                self.seen.remove(&key);
+            }
+        }
+    }
+
+    /// Keys with their last-seen time, least recently seen first
+    fn iter(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.order
+            .values()
+            .map(|key| (key.as_str(), self.seen[key].1))
+    }
+
+    fn len(&self) -> usize {
+        self.seen.len()
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// In-memory [`DedupStore`] with LRU eviction and a time to live
+///
+/// Keys expire `ttl` after they were last seen; beyond `capacity` keys the
+/// least recently seen are evicted. Contents are lost on restart; see
+/// [`FileDedupStore`] for a persistent store.
+#[derive(Debug)]
+pub struct MemoryDedupStore {
+    entries: Mutex<Entries>,
+}
+
+impl MemoryDedupStore {
+    /// Create a store holding at most `capacity` keys for `ttl` each
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(Entries::new(capacity, ttl)),
+        }
+    }
+
+    /// Number of keys currently held, including expired keys not yet purged
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no keys are held
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl DedupStore for MemoryDedupStore {
+    fn insert<'a>(&'a self, key: &'a str) -> DedupFuture<'a, bool> {
+        let new = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .touch(key, now());
+        Box::pin(std::future::ready(Ok(new)))
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> DedupFuture<'a, ()> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+/// [`DedupStore`] persisted to an append-only file
+///
+/// Behaves like [`MemoryDedupStore`] and additionally logs every change to a
+/// file, so events seen before a restart are still recognized. The log is
+/// rewritten with only the live keys once it grows past twice the capacity.
+///
+/// Each line holds a last-seen time in milliseconds and a key, or `-` and a
+/// removed key. The file is written on the blocking thread pool, so webhook
+/// handling does not wait on the disk.
+#[derive(Debug)]
+pub struct FileDedupStore {
+    inner: Arc<FileInner>,
+}
+
+#[derive(Debug)]
+struct FileInner {
+    path: PathBuf,
+    state: Mutex<FileState>,
+}
+
+#[derive(Debug)]
+struct FileState {
+    entries: Entries,
+    file: File,
+    lines: usize,
+}
+
+impl FileDedupStore {
+    /// Open or create the store at `path`, holding at most `capacity` keys
+    /// for `ttl` each
+    pub fn open(path: impl AsRef<Path>, capacity: usize, ttl: Duration) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = Entries::new(capacity, ttl);
+        let mut lines = 0;
+
+        if let Ok(file) = File::open(&path) {
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                lines += 1;
+                match line.split_once(' ') {
+                    Some(("-", key)) => {
+                        entries.remove(key);
+                    }
+                    Some((millis, key)) => {
+                        if let Ok(millis) = millis.parse() {
+                            entries.touch(key, Duration::from_millis(millis));
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+        entries.purge(now());
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let inner = FileInner {
+            path,
+            state: Mutex::new(FileState {
+                entries,
+                file,
+                lines,
+            }),
+        };
+        inner.compact(&mut inner.state.lock().unwrap_or_else(|e| e.into_inner()))?;
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Number of keys currently held, including expired keys not yet purged
+    pub fn len(&self) -> usize {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    /// Whether no keys are held
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl FileInner {
+    fn insert(&self, key: &str) -> std::io::Result<bool> {
+        let now = now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let new = state.entries.touch(key, now);
+        self.append(&mut state, format!("{} {}", now.as_millis(), key))?;
+        Ok(new)
+    }
+
+    fn remove(&self, key: &str) -> std::io::Result<()> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.entries.remove(key).is_some() {
+            self.append(&mut state, format!("- {}", key))?;
+        }
+        Ok(())
+    }
+
+    /// Append a line to the log, compacting it if it grew too large
+    fn append(&self, state: &mut FileState, line: String) -> std::io::Result<()> {
+        writeln!(state.file, "{}", line)?;
+        state.lines += 1;
+        if state.lines > state.entries.capacity * 2 {
+            self.compact(state)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the log with only the live keys
+    fn compact(&self, state: &mut FileState) -> std::io::Result<()> {
+        let temp = temp_path(&self.path);
+        let result =
+            Self::write_entries(&temp, state).and_then(|()| std::fs::rename(&temp, &self.path));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+        result?;
+
+        state.file = OpenOptions::new().append(true).open(&self.path)?;
+        state.lines = state.entries.len();
+        Ok(())
+    }
+
+    fn write_entries(path: &Path, state: &FileState) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        for (key, seen_at) in state.entries.iter() {
+            writeln!(file, "{} {}", seen_at.as_millis(), key)?;
+        }
+        file.sync_all()
+    }
+}
+
+impl DedupStore for FileDedupStore {
+    fn insert<'a>(&'a self, key: &'a str) -> DedupFuture<'a, bool> {
+        let (inner, key) = (self.inner.clone(), key.to_string());
+        Box::pin(blocking(move || inner.insert(&key)))
+    }
+
+    fn remove<'a>(&'a self, key: &'a str) -> DedupFuture<'a, ()> {
+        let (inner, key) = (self.inner.clone(), key.to_string());
+        Box::pin(blocking(move || inner.remove(&key)))
+    }
+}
+
+/// Run file work on the blocking thread pool
+async fn blocking<T, F>(work: F) -> Result<T, HandlerError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> std::io::Result<T> + Send + 'static,
+{
+    Ok(tokio::task::spawn_blocking(work).await??)
+}
+
+/// Drops webhook events that were already seen
+///
+/// Events without a [`dedup_key`](WebhookEnvelope::dedup_key), such as
+/// template or account updates, are never dropped. Used as [`Middleware`] it
+/// forgets events whose handlers failed, so Meta's redelivery is processed
+/// again.
+#[derive(Clone)]
+pub struct Deduplicator {
+    store: Arc<dyn DedupStore>,
+}
+
+impl Deduplicator {
+    /// Create a deduplicator backed by `store`
+    pub fn new(store: impl DedupStore) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Record the event as seen; returns whether it was not seen before
+    pub async fn is_new(&self, envelope: &WebhookEnvelope) -> Result<bool, HandlerError> {
+        match envelope.dedup_key() {
+            Some(key) => self.store.insert(&key).await,
+            None => Ok(true),
+        }
+    }
+
+    /// Forget the event, so a redelivery is processed again
+    pub async fn forget(&self, envelope: &WebhookEnvelope) -> Result<(), HandlerError> {
+        match envelope.dedup_key() {
+            Some(key) => self.store.remove(&key).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Envelopes of the payload that were not seen before
+    pub async fn envelopes(
+        &self,
+        payload: &WebhookPayload,
+    ) -> Result<Vec<WebhookEnvelope>, HandlerError> {
+        let mut fresh = Vec::new();
+        for envelope in payload.envelopes() {
+            if self.is_new(&envelope).await? {
+                fresh.push(envelope);
+            }
+        }
+        Ok(fresh)
+    }
+
+    /// Events of the payload that were not seen before
+    pub async fn events(
+        &self,
+        payload: &WebhookPayload,
+    ) -> Result<Vec<WebhookEvent>, HandlerError> {
+        Ok(self
+            .envelopes(payload)
+            .await?
+            .into_iter()
+            .map(|envelope| envelope.event)
+            .collect())
+    }
+}
+
+impl Middleware for Deduplicator {
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+        Box::pin(async move {
+            Ok(match self.is_new(event).await? {
+                true => Next::Continue,
+                false => Next::Skip,
+            })
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        event: &'a WebhookEnvelope,
+        outcome: Outcome,
+    ) -> MiddlewareAfterFuture<'a> {
+        Box::pin(async move {
+            if outcome == Outcome::Failed {
+                // Best effort: if this fails, the redelivery is dropped as a duplicate
+                let _ = self.forget(event).await;
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for Deduplicator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Deduplicator").finish_non_exhaustive()
+    }
+}
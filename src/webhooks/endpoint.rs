@@ -0,0 +1,234 @@
+//! Framework-independent webhook endpoint
+//!
+//! A [`WebhookEndpoint`] answers the `GET` verification handshake and handles
+//! signed `POST` deliveries. HTTP integrations only translate requests into
+//! calls to [`WebhookEndpoint::verify`] and [`WebhookEndpoint::receive`].
+
+use super::{
+    secrets_match, verify_signature, HandlerError, HandlerResult, WebhookEnvelope, WebhookEvent,
+    WebhookPayload, WebhookRouter,
+};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Future returned by [`WebhookHandler::handle`]
+pub type WebhookHandlerFuture<'a> = Pin<Box<dyn Future<Output = HandlerResult> + Send + 'a>>;
+
+/// Receives the payloads accepted by a [`WebhookEndpoint`]
+///
+/// Implemented for closures taking a [`WebhookPayload`], for
+/// [`WebhookRouter`] and for channel senders of [`WebhookEvent`] and
+/// [`WebhookEnvelope`].
+pub trait WebhookHandler: Send + Sync + 'static {
+    /// Handle a verified and parsed payload
+    ///
+    /// An error makes the endpoint answer `500`, so Meta delivers the payload
+    /// again later.
+    fn handle(&self, payload: WebhookPayload) -> WebhookHandlerFuture<'_>;
+}
+
+impl<F, Fut> WebhookHandler for F
+where
+    F: Fn(WebhookPayload) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = HandlerResult> + Send + 'static,
+{
+    fn handle(&self, payload: WebhookPayload) -> WebhookHandlerFuture<'_> {
+        Box::pin(self(payload))
+    }
+}
+
+impl WebhookHandler for WebhookRouter {
+    /// Dispatch the payload; fails if any handler or middleware failed
+    fn handle(&self, payload: WebhookPayload) -> WebhookHandlerFuture<'_> {
+        Box::pin(async move {
+            let report = self.dispatch(&payload).await;
+            match report.failures.first() {
+                None => Ok(()),
+                Some(failure) => Err(format!(
+                    "{} of {} events failed, first: {}",
+                    report.failures.len(),
+                    report.handled + report.skipped + report.unhandled,
+                    failure.error
+                )
+                .into()),
+            }
+        })
+    }
+}
+
+/// Implements [`WebhookHandler`] for the channel senders of an item type,
+/// produced from the payload by the given method
+macro_rules! channel_handlers {
+    ($item:ty, $method:ident) => {
+        impl WebhookHandler for mpsc::Sender<$item> {
+            /// Send every event of the payload, waiting for channel capacity
+            fn handle(&self, payload: WebhookPayload) -> WebhookHandlerFuture<'_> {
+                Box::pin(async move {
+                    for item in payload.$method() {
+                        self.send(item)
+                            .await
+                            .map_err(|_| HandlerError::from("webhook event channel closed"))?;
+                    }
+                    Ok(())
+                })
+            }
+        }
+
+        impl WebhookHandler for mpsc::UnboundedSender<$item> {
+            /// Send every event of the payload
+            fn handle(&self, payload: WebhookPayload) -> WebhookHandlerFuture<'_> {
+                let result = payload.$method().into_iter().try_for_each(|item| {
+                    self.send(item)
+                        .map_err(|_| HandlerError::from("webhook event channel closed"))
+                });
+                Box::pin(std::future::ready(result))
+            }
+        }
+    };
+}
+
+channel_handlers!(WebhookEvent, events);
+channel_handlers!(WebhookEnvelope, envelopes);
+
+/// Query parameters of the `GET` verification request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct VerificationQuery {
+    /// Always "subscribe"
+    #[serde(rename = "hub.mode")]
+    pub mode: Option<String>,
+    /// Verify token configured in the App Dashboard
+    #[serde(rename = "hub.verify_token")]
+    pub verify_token: Option<String>,
+    /// Value to echo back to confirm the subscription
+    #[serde(rename = "hub.challenge")]
+    pub challenge: Option<String>,
+}
+
+impl VerificationQuery {
+    /// Parse the query string of a request URI
+    pub fn from_query(query: &str) -> Self {
+        let mut parsed = Self::default();
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            let slot = match key.as_ref() {
+                "hub.mode" => &mut parsed.mode,
+                "hub.verify_token" => &mut parsed.verify_token,
+                "hub.challenge" => &mut parsed.challenge,
+                _ => continue,
+            };
+            *slot = Some(value.into_owned());
+        }
+        parsed
+    }
+}
+
+/// Status code and body to answer a webhook request with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointResponse {
+    /// HTTP status code
+    pub status: StatusCode,
+    /// Plain-text response body
+    pub body: String,
+}
+
+impl EndpointResponse {
+    fn new(status: StatusCode, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Webhook endpoint with a verify token, app secret and payload handler
+///
+/// | Request | Response |
+/// |---------|----------|
+/// | `GET` with matching `hub.verify_token` | `200` with `hub.challenge` |
+/// | `GET` with missing parameters | `400` |
+/// | `GET` with wrong mode or token | `403` |
+/// | `POST` with missing or invalid `X-Hub-Signature-256` | `401` |
+/// | `POST` with a body that is not a webhook payload | `400` |
+/// | `POST` whose handler fails | `500` |
+/// | `POST` handled | `200` |
+///
+/// # Example
+///
+/// ```rust
+/// use wacloudapi::webhooks::{WebhookEndpoint, WebhookEnvelope};
+///
+/// let (sender, mut events) = tokio::sync::mpsc::channel::<WebhookEnvelope>(100);
+/// let endpoint = WebhookEndpoint::new("verify_token", "app_secret", sender);
+/// ```
+#[derive(Clone)]
+pub struct WebhookEndpoint {
+    verify_token: String,
+    app_secret: String,
+    handler: Arc<dyn WebhookHandler>,
+}
+
+impl WebhookEndpoint {
+    /// Create an endpoint handing payloads to `handler`
+    pub fn new(
+        verify_token: impl Into<String>,
+        app_secret: impl Into<String>,
+        handler: impl WebhookHandler,
+    ) -> Self {
+        Self {
+            verify_token: verify_token.into(),
+            app_secret: app_secret.into(),
+            handler: Arc::new(handler),
+        }
+    }
+
+    /// Answer the `GET` verification handshake
+    pub fn verify(&self, query: &VerificationQuery) -> EndpointResponse {
+        let (Some(mode), Some(token), Some(challenge)) =
+            (&query.mode, &query.verify_token, &query.challenge)
+        else {
+            return EndpointResponse::new(StatusCode::BAD_REQUEST, "missing hub parameters");
+        };
+
+        // Compared in constant time, like the payload signature
+        if mode != "subscribe" || !secrets_match(token.as_bytes(), self.verify_token.as_bytes()) {
+            return EndpointResponse::new(StatusCode::FORBIDDEN, "verification failed");
+        }
+
+        EndpointResponse::new(StatusCode::OK, challenge.as_str())
+    }
+
+    /// Verify, parse and handle a `POST` delivery
+    ///
+    /// `signature` is the `X-Hub-Signature-256` header and `body` the raw
+    /// request body.
+    pub async fn receive(&self, signature: Option<&str>, body: &[u8]) -> EndpointResponse {
+        if let Err(error) = verify_signature(body, signature, &self.app_secret) {
+            return EndpointResponse::new(StatusCode::UNAUTHORIZED, error.to_string());
+        }
+
+        let payload: WebhookPayload = match serde_json::from_slice(body) {
+            Ok(payload) => payload,
+            Err(error) => {
+                return EndpointResponse::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid webhook payload: {}", error),
+                )
+            }
+        };
+
+        match self.handler.handle(payload).await {
+            Ok(()) => EndpointResponse::new(StatusCode::OK, ""),
+            // Don't leak handler internals to the caller
+            Err(_) => EndpointResponse::new(StatusCode::INTERNAL_SERVER_ERROR, "handler failed"),
+        }
+    }
+}
+
+impl std::fmt::Debug for WebhookEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookEndpoint").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,114 @@
+//! Webhook events with the context they were delivered in
+
+use super::{EventKind, StatusKind, WebhookEvent};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A [`WebhookEvent`] together with where, when and from whom it came
+///
+/// Built by [`WebhookPayload::envelopes`](super::WebhookPayload::envelopes).
+/// The metadata tells which of the business's numbers received the event,
+/// so replies can be sent from the same number.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use wacloudapi::webhooks::{WebhookEvent, WebhookPayload};
+///
+/// # fn example(payload: WebhookPayload) {
+/// for envelope in payload.envelopes() {
+///     if let WebhookEvent::TextMessage { text, .. } = &envelope.event {
+///         println!(
+///             "{} ({:?}) wrote to {:?}: {}",
+///             envelope.sender_wa_id.as_deref().unwrap_or("?"),
+///             envelope.sender_name,
+///             envelope.phone_number_id,
+///             text
+///         );
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookEnvelope {
+    /// The event
+    pub event: WebhookEvent,
+    /// WhatsApp Business Account ID, from the entry
+    pub waba_id: String,
+    /// ID of the business phone number that received the event
+    pub phone_number_id: Option<String>,
+    /// Display number of the business phone number that received the event
+    pub display_phone_number: Option<String>,
+    /// When the event happened, in Unix seconds
+    pub timestamp: Option<u64>,
+    /// Profile name of the sender, for incoming messages
+    pub sender_name: Option<String>,
+    /// WhatsApp ID of the sender, for incoming messages
+    pub sender_wa_id: Option<String>,
+    /// Raw JSON of the message, status or change value the event was built
+    /// from, including fields not modeled by [`WebhookEvent`]
+    pub raw: Option<serde_json::Value>,
+}
+
+impl WebhookEnvelope {
+    /// Kind of the wrapped event
+    pub fn kind(&self) -> EventKind {
+        self.event.kind()
+    }
+
+    /// ID of the message the wrapped event is about
+    pub fn message_id(&self) -> Option<&str> {
+        self.event.message_id()
+    }
+
+    /// WhatsApp ID of the user the wrapped event comes from or is about
+    pub fn user(&self) -> Option<&str> {
+        self.event.user()
+    }
+
+    /// Key identifying the event across redeliveries
+    ///
+    /// The ID of the incoming message, or the message ID and status for
+    /// status updates (e.g. `wamid.X:delivered`). `None` for events that are
+    /// not about a message, such as template updates.
+    pub fn dedup_key(&self) -> Option<String> {
+        let message_id = self.message_id()?;
+        let suffix = match self.kind() {
+            EventKind::Status(StatusKind::Sent) => "sent",
+            EventKind::Status(StatusKind::Delivered) => "delivered",
+            EventKind::Status(StatusKind::Read) => "read",
+            EventKind::Status(StatusKind::Failed) => "failed",
+            _ => "message",
+        };
+        // Prefer the raw ID: for reactions `message_id` is the message
+        // reacted to, not the reaction itself
+        let id = self
+            .raw
+            .as_ref()
+            .and_then(|raw| raw.get("id"))
+            .and_then(|id| id.as_str())
+            .unwrap_or(message_id);
+        Some(format!("{}:{}", id, suffix))
+    }
+
+    /// [`timestamp`](Self::timestamp) as a [`SystemTime`], `None` if it is
+    /// missing or out of range
+    pub fn time(&self) -> Option<SystemTime> {
+        UNIX_EPOCH.checked_add(Duration::from_secs(self.timestamp?))
+    }
+}
+
+impl From<WebhookEvent> for WebhookEnvelope {
+    /// Wrap an event without any metadata
+    fn from(event: WebhookEvent) -> Self {
+        Self {
+            event,
+            waba_id: String::new(),
+            phone_number_id: None,
+            display_phone_number: None,
+            timestamp: None,
+            sender_name: None,
+            sender_wa_id: None,
+            raw: None,
+        }
+    }
+}
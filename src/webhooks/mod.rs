@@ -0,0 +1,1702 @@
+//! Webhook types for receiving WhatsApp events
+//!
+//! This module provides types for parsing incoming webhook payloads from WhatsApp Cloud API.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use wacloudapi::webhooks::{WebhookPayload, WebhookEvent};
+//!
+//! fn handle_webhook(payload: &str) -> Result<(), Box<dyn std::error::Error>> {
+//!     let webhook: WebhookPayload = serde_json::from_str(payload)?;
+//!
+//!     for entry in webhook.entry {
+//!         for change in entry.changes {
+//!             if let Some(messages) = change.value.messages {
+//!                 for message in messages {
+//!                     println!("Received message from: {}", message.from);
+//!                 }
+//!             }
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+mod account;
+#[cfg(feature = "axum")]
+pub mod axum;
+mod dedup;
+mod endpoint;
+mod envelope;
+mod router;
+#[cfg(feature = "tower")]
+mod service;
+mod templates;
+
+pub use account::{
+    AccountReviewUpdate, AccountUpdate, BanInfo, BusinessCapabilityUpdate, PhoneNumberNameUpdate,
+    PhoneNumberQualityUpdate, RestrictionInfo, SecurityUpdate, ViolationInfo,
+};
+pub use dedup::{DedupFuture, DedupStore, Deduplicator, FileDedupStore, MemoryDedupStore};
+pub use endpoint::{
+    EndpointResponse, VerificationQuery, WebhookEndpoint, WebhookHandler, WebhookHandlerFuture,
+};
+pub use envelope::WebhookEnvelope;
+pub use router::{
+    DispatchReport, HandlerError, HandlerFailure, HandlerResult, Middleware, MiddlewareAfterFuture,
+    MiddlewareFuture, Next, Outcome, WebhookRouter,
+};
+#[cfg(feature = "tower")]
+pub use service::{WebhookService, WebhookServiceFuture, DEFAULT_MAX_BODY_SIZE};
+pub use templates::{
+    TemplateButtonUpdate, TemplateCategoryUpdate, TemplateComponentsUpdate, TemplateDisableInfo,
+    TemplateOtherInfo, TemplateQualityUpdate, TemplateStatusUpdate,
+};
+
+use crate::error::SignatureError;
+use crate::messages::{ContactAddress, ContactEmail, ContactOrg, ContactUrl};
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use sha2::Sha256;
+
+/// Root webhook payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Object type (always "whatsapp_business_account")
+    pub object: String,
+    /// Entry array containing the webhook data
+    pub entry: Vec<WebhookEntry>,
+}
+
+/// Webhook entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEntry {
+    /// WhatsApp Business Account ID
+    pub id: String,
+    /// When the changes happened, in Unix seconds (sent for fields other
+    /// than `messages`)
+    #[serde(default)]
+    pub time: Option<u64>,
+    /// Array of changes
+    pub changes: Vec<WebhookChange>,
+}
+
+/// Webhook change object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookChange {
+    /// Value containing the actual webhook data
+    pub value: WebhookValue,
+    /// Subscribed field the change belongs to (e.g. "messages",
+    /// "message_template_status_update"); decides how `value` is interpreted
+    pub field: String,
+}
+
+/// Webhook value containing all possible notification types
+///
+/// Changes of the `messages` field fill the typed fields. The values of other
+/// fields, such as template updates, are kept in `other` and parsed into
+/// typed events by [`WebhookPayload::events`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookValue {
+    /// Messaging product (always "whatsapp"; empty for non-message fields)
+    #[serde(default)]
+    pub messaging_product: String,
+    /// Metadata about the business phone number (only sent for `messages`)
+    #[serde(default)]
+    pub metadata: Option<WebhookMetadata>,
+    /// Contact information of message senders
+    #[serde(default)]
+    pub contacts: Option<Vec<WebhookContact>>,
+    /// Received messages
+    #[serde(default)]
+    pub messages: Option<Vec<WebhookMessage>>,
+    /// Message status updates
+    #[serde(default)]
+    pub statuses: Option<Vec<WebhookStatus>>,
+    /// Errors
+    #[serde(default)]
+    pub errors: Option<Vec<WebhookError>>,
+    /// Remaining fields of the value, for changes other than `messages`
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Metadata about the business phone number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookMetadata {
+    /// Display phone number
+    pub display_phone_number: String,
+    /// Phone number ID
+    pub phone_number_id: String,
+}
+
+/// Contact information from webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookContact {
+    /// Contact's profile
+    pub profile: WebhookProfile,
+    /// WhatsApp ID
+    pub wa_id: String,
+}
+
+/// Contact profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookProfile {
+    /// Contact's push name
+    pub name: String,
+}
+
+/// Incoming message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookMessage {
+    /// Sender's phone number
+    pub from: String,
+    /// Message ID
+    pub id: String,
+    /// Timestamp
+    pub timestamp: String,
+    /// Message type
+    #[serde(rename = "type")]
+    pub message_type: String,
+    /// Text message content
+    #[serde(default)]
+    pub text: Option<TextMessage>,
+    /// Image message content
+    #[serde(default)]
+    pub image: Option<MediaMessage>,
+    /// Video message content
+    #[serde(default)]
+    pub video: Option<MediaMessage>,
+    /// Audio message content
+    #[serde(default)]
+    pub audio: Option<MediaMessage>,
+    /// Document message content
+    #[serde(default)]
+    pub document: Option<DocumentMessage>,
+    /// Sticker message content
+    #[serde(default)]
+    pub sticker: Option<MediaMessage>,
+    /// Location message content
+    #[serde(default)]
+    pub location: Option<LocationMessage>,
+    /// Contact message content
+    #[serde(default)]
+    pub contacts: Option<Vec<ContactMessage>>,
+    /// Reaction message content
+    #[serde(default)]
+    pub reaction: Option<ReactionMessage>,
+    /// Interactive message response
+    #[serde(default)]
+    pub interactive: Option<InteractiveResponse>,
+    /// Button response
+    #[serde(default)]
+    pub button: Option<ButtonResponse>,
+    /// Context (for replies)
+    #[serde(default)]
+    pub context: Option<MessageContext>,
+    /// Identity information
+    #[serde(default)]
+    pub identity: Option<IdentityInfo>,
+    /// Referral information (from ads)
+    #[serde(default)]
+    pub referral: Option<ReferralInfo>,
+    /// Order information
+    #[serde(default)]
+    pub order: Option<OrderInfo>,
+    /// System message
+    #[serde(default)]
+    pub system: Option<SystemMessage>,
+    /// Errors in the message
+    #[serde(default)]
+    pub errors: Option<Vec<WebhookError>>,
+    /// Fields not modeled above
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Text message content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextMessage {
+    /// Message body
+    pub body: String,
+}
+
+/// Media message content (image, video, audio, sticker)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaMessage {
+    /// Media ID
+    pub id: String,
+    /// MIME type
+    pub mime_type: String,
+    /// SHA256 hash
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Caption (for images and videos)
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// Whether an audio message is a voice recording
+    #[serde(default)]
+    pub voice: Option<bool>,
+    /// Whether a sticker is animated
+    #[serde(default)]
+    pub animated: Option<bool>,
+}
+
+/// Document message content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentMessage {
+    /// Document ID
+    pub id: String,
+    /// MIME type
+    pub mime_type: String,
+    /// SHA256 hash
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Filename
+    #[serde(default)]
+    pub filename: Option<String>,
+    /// Caption
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+/// Location message content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationMessage {
+    /// Latitude
+    pub latitude: f64,
+    /// Longitude
+    pub longitude: f64,
+    /// Location name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Location address
+    #[serde(default)]
+    pub address: Option<String>,
+    /// URL of the location's website
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Contact card of a contact message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactMessage {
+    /// Contact name
+    pub name: ContactName,
+    /// Phone numbers
+    #[serde(default)]
+    pub phones: Option<Vec<ContactPhone>>,
+    /// Email addresses
+    #[serde(default)]
+    pub emails: Option<Vec<ContactEmail>>,
+    /// URLs
+    #[serde(default)]
+    pub urls: Option<Vec<ContactUrl>>,
+    /// Addresses
+    #[serde(default)]
+    pub addresses: Option<Vec<ContactAddress>>,
+    /// Organization info
+    #[serde(default)]
+    pub org: Option<ContactOrg>,
+    /// Birthday (YYYY-MM-DD)
+    #[serde(default)]
+    pub birthday: Option<String>,
+}
+
+/// Contact name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactName {
+    /// Formatted name
+    pub formatted_name: String,
+    /// First name
+    #[serde(default)]
+    pub first_name: Option<String>,
+    /// Last name
+    #[serde(default)]
+    pub last_name: Option<String>,
+    /// Middle name
+    #[serde(default)]
+    pub middle_name: Option<String>,
+    /// Name suffix
+    #[serde(default)]
+    pub suffix: Option<String>,
+    /// Name prefix
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+/// Contact phone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContactPhone {
+    /// Phone number
+    pub phone: String,
+    /// Phone type
+    #[serde(rename = "type", default)]
+    pub phone_type: Option<String>,
+    /// WhatsApp ID
+    #[serde(default)]
+    pub wa_id: Option<String>,
+}
+
+/// Reaction message content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReactionMessage {
+    /// ID of the message being reacted to
+    pub message_id: String,
+    /// Emoji reaction (empty string to remove)
+    pub emoji: String,
+}
+
+/// Interactive message response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InteractiveResponse {
+    /// Response type
+    #[serde(rename = "type")]
+    pub response_type: String,
+    /// Button reply
+    #[serde(default)]
+    pub button_reply: Option<ButtonReply>,
+    /// List reply
+    #[serde(default)]
+    pub list_reply: Option<ListReply>,
+    /// WhatsApp Flow response
+    #[serde(default)]
+    pub nfm_reply: Option<FlowResponse>,
+}
+
+/// Button reply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ButtonReply {
+    /// Button ID
+    pub id: String,
+    /// Button title
+    pub title: String,
+}
+
+/// List reply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListReply {
+    /// Row ID
+    pub id: String,
+    /// Row title
+    pub title: String,
+    /// Row description
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Response of a completed WhatsApp Flow
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowResponse {
+    /// Flow name (usually "flow")
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Body shown in the chat
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Flow response as a JSON string
+    pub response_json: String,
+}
+
+/// Quick reply button response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ButtonResponse {
+    /// Button text
+    pub text: String,
+    /// Button payload
+    pub payload: String,
+}
+
+/// Message context (for replies)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageContext {
+    /// ID of the message being replied to (absent for forwarded messages)
+    #[serde(rename = "id", alias = "message_id", default)]
+    pub message_id: Option<String>,
+    /// Sender of the original message
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Whether this was forwarded
+    #[serde(default)]
+    pub forwarded: Option<bool>,
+    /// Whether this was forwarded more than 5 times
+    #[serde(default)]
+    pub frequently_forwarded: Option<bool>,
+    /// Product the user asked about from a product message
+    #[serde(default)]
+    pub referred_product: Option<ReferredProduct>,
+}
+
+/// Product referred to by a message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferredProduct {
+    /// Catalog ID
+    pub catalog_id: String,
+    /// Product retailer ID
+    pub product_retailer_id: String,
+}
+
+/// Identity information (for identity changes)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdentityInfo {
+    /// Whether user acknowledged identity change
+    pub acknowledged: bool,
+    /// When the identity was created
+    pub created_timestamp: String,
+    /// Identity hash
+    pub hash: String,
+}
+
+/// Referral information (from Click to WhatsApp ads)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferralInfo {
+    /// Source URL
+    pub source_url: String,
+    /// Source type
+    pub source_type: String,
+    /// Source ID
+    pub source_id: String,
+    /// Headline
+    #[serde(default)]
+    pub headline: Option<String>,
+    /// Body text
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Media type
+    #[serde(default)]
+    pub media_type: Option<String>,
+    /// Image URL
+    #[serde(default)]
+    pub image_url: Option<String>,
+    /// Video URL
+    #[serde(default)]
+    pub video_url: Option<String>,
+    /// Thumbnail URL
+    #[serde(default)]
+    pub thumbnail_url: Option<String>,
+}
+
+/// Order information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderInfo {
+    /// Catalog ID
+    pub catalog_id: String,
+    /// Product items
+    pub product_items: Vec<ProductItem>,
+    /// Order text
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Product item in an order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProductItem {
+    /// Product retailer ID
+    pub product_retailer_id: String,
+    /// Quantity
+    pub quantity: i32,
+    /// Item price
+    #[serde(deserialize_with = "string_or_number")]
+    pub item_price: String,
+    /// Currency
+    pub currency: String,
+}
+
+/// System message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemMessage {
+    /// System message body
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Identity info (for identity changes)
+    #[serde(default)]
+    pub identity: Option<String>,
+    /// New WhatsApp ID
+    #[serde(default)]
+    pub new_wa_id: Option<String>,
+    /// System message type
+    #[serde(rename = "type", default)]
+    pub system_type: Option<String>,
+    /// Customer info
+    #[serde(default)]
+    pub customer: Option<String>,
+}
+
+/// Message status update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookStatus {
+    /// Message ID
+    pub id: String,
+    /// Recipient's phone number
+    pub recipient_id: String,
+    /// Status (sent, delivered, read, failed)
+    pub status: String,
+    /// Timestamp
+    pub timestamp: String,
+    /// Conversation info
+    #[serde(default)]
+    pub conversation: Option<ConversationInfo>,
+    /// Pricing info
+    #[serde(default)]
+    pub pricing: Option<PricingInfo>,
+    /// Errors (for failed status)
+    #[serde(default)]
+    pub errors: Option<Vec<WebhookError>>,
+    /// Arbitrary string the message was sent with
+    #[serde(default)]
+    pub biz_opaque_callback_data: Option<String>,
+    /// Fields not modeled above
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Conversation information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationInfo {
+    /// Conversation ID
+    pub id: String,
+    /// Conversation origin
+    #[serde(default)]
+    pub origin: Option<ConversationOrigin>,
+    /// When the conversation expires (Unix seconds); only sent with the
+    /// first `sent` status of a conversation
+    #[serde(default)]
+    pub expiration_timestamp: Option<String>,
+}
+
+impl ConversationInfo {
+    /// Origin type of the conversation, if reported
+    pub fn origin_type(&self) -> Option<&str> {
+        self.origin
+            .as_ref()
+            .map(|origin| origin.origin_type.as_str())
+    }
+}
+
+/// Conversation origin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConversationOrigin {
+    /// Origin type (authentication, marketing, utility, service,
+    /// referral_conversion, ...)
+    #[serde(rename = "type")]
+    pub origin_type: String,
+}
+
+/// Pricing information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PricingInfo {
+    /// Whether the message is billable
+    pub billable: bool,
+    /// Pricing model (CBP, PMP)
+    pub pricing_model: String,
+    /// Pricing category (authentication, marketing, utility, service,
+    /// referral_conversion, ...)
+    pub category: String,
+    /// Pricing type of per-message pricing (regular, free_customer_service,
+    /// free_entry_point)
+    #[serde(rename = "type", default)]
+    pub pricing_type: Option<String>,
+}
+
+/// Webhook error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookError {
+    /// Error code
+    pub code: i32,
+    /// Error title
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Error message
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Error details
+    #[serde(default)]
+    pub error_data: Option<ErrorData>,
+    /// Link to the error code documentation
+    #[serde(default)]
+    pub href: Option<String>,
+}
+
+impl WebhookError {
+    /// Details about the error, if reported
+    pub fn details(&self) -> Option<&str> {
+        self.error_data.as_ref().map(|data| data.details.as_str())
+    }
+}
+
+/// Error data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorData {
+    /// Details about the error
+    pub details: String,
+}
+
+/// Webhook event type enumeration
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    /// Text message received
+    TextMessage {
+        from: String,
+        text: String,
+        message_id: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+        /// Click-to-WhatsApp ad the message came from
+        referral: Option<ReferralInfo>,
+    },
+    /// Image message received
+    ImageMessage {
+        from: String,
+        media_id: String,
+        message_id: String,
+        caption: Option<String>,
+        /// MIME type
+        mime_type: String,
+        /// SHA256 hash of the media
+        sha256: Option<String>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+        /// Click-to-WhatsApp ad the message came from
+        referral: Option<ReferralInfo>,
+    },
+    /// Video message received
+    VideoMessage {
+        from: String,
+        media_id: String,
+        message_id: String,
+        caption: Option<String>,
+        /// MIME type
+        mime_type: String,
+        /// SHA256 hash of the media
+        sha256: Option<String>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+        /// Click-to-WhatsApp ad the message came from
+        referral: Option<ReferralInfo>,
+    },
+    /// Audio message received
+    AudioMessage {
+        from: String,
+        media_id: String,
+        message_id: String,
+        /// MIME type
+        mime_type: String,
+        /// SHA256 hash of the media
+        sha256: Option<String>,
+        /// Whether this is a voice recording
+        voice: bool,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Document message received
+    DocumentMessage {
+        from: String,
+        media_id: String,
+        message_id: String,
+        filename: Option<String>,
+        caption: Option<String>,
+        /// MIME type
+        mime_type: String,
+        /// SHA256 hash of the media
+        sha256: Option<String>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Sticker message received
+    StickerMessage {
+        from: String,
+        media_id: String,
+        message_id: String,
+        /// MIME type
+        mime_type: String,
+        /// SHA256 hash of the media
+        sha256: Option<String>,
+        /// Whether the sticker is animated
+        animated: bool,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Location message received
+    LocationMessage {
+        from: String,
+        latitude: f64,
+        longitude: f64,
+        message_id: String,
+        name: Option<String>,
+        address: Option<String>,
+        url: Option<String>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Contact message received
+    ContactMessage {
+        from: String,
+        message_id: String,
+        /// Shared contact cards
+        contacts: Vec<ContactMessage>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Reaction received
+    Reaction {
+        from: String,
+        /// ID of the message reacted to
+        message_id: String,
+        /// Emoji, empty if the reaction was removed
+        emoji: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Interactive button reply
+    ButtonReply {
+        from: String,
+        button_id: String,
+        button_title: String,
+        message_id: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Interactive list reply
+    ListReply {
+        from: String,
+        row_id: String,
+        row_title: String,
+        message_id: String,
+        row_description: Option<String>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Template quick reply button pressed (`button` message)
+    QuickReply {
+        from: String,
+        message_id: String,
+        /// Button label
+        text: String,
+        /// Payload set when the template was sent
+        payload: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// WhatsApp Flow completed (`interactive.nfm_reply` message)
+    FlowReply {
+        from: String,
+        message_id: String,
+        /// Flow name
+        name: Option<String>,
+        /// Body shown in the chat (e.g. "Sent")
+        body: Option<String>,
+        /// Flow response as a JSON string
+        response_json: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Order placed from a catalog
+    OrderMessage {
+        from: String,
+        message_id: String,
+        order: OrderInfo,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// System message, e.g. the user changed their number
+    SystemMessage {
+        from: String,
+        message_id: String,
+        system: SystemMessage,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// User opened the chat for the first time (`request_welcome` message)
+    RequestWelcome {
+        from: String,
+        message_id: String,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Message type not supported by the Cloud API
+    UnsupportedMessage {
+        from: String,
+        message_id: String,
+        /// Type reported by the API ("unsupported" or "unknown")
+        message_type: String,
+        /// Why the message is unsupported
+        errors: Vec<WebhookError>,
+        /// Message timestamp (Unix seconds)
+        timestamp: String,
+        /// Sender's profile name
+        profile_name: Option<String>,
+        /// Message replied to or forwarded, if any
+        context: Option<MessageContext>,
+    },
+    /// Message sent
+    MessageSent {
+        message_id: String,
+        recipient: String,
+        /// Status timestamp (Unix seconds)
+        timestamp: String,
+        /// Conversation the message was billed in, if reported
+        conversation: Option<ConversationInfo>,
+        /// Pricing of the message, if reported
+        pricing: Option<PricingInfo>,
+        /// `biz_opaque_callback_data` the message was sent with
+        biz_opaque_callback_data: Option<String>,
+    },
+    /// Message delivered
+    MessageDelivered {
+        message_id: String,
+        recipient: String,
+        /// Status timestamp (Unix seconds)
+        timestamp: String,
+        /// Conversation the message was billed in, if reported
+        conversation: Option<ConversationInfo>,
+        /// Pricing of the message, if reported
+        pricing: Option<PricingInfo>,
+        /// `biz_opaque_callback_data` the message was sent with
+        biz_opaque_callback_data: Option<String>,
+    },
+    /// Message read
+    MessageRead {
+        message_id: String,
+        recipient: String,
+        /// Status timestamp (Unix seconds)
+        timestamp: String,
+        /// Conversation the message was billed in, if reported
+        conversation: Option<ConversationInfo>,
+        /// Pricing of the message, if reported
+        pricing: Option<PricingInfo>,
+        /// `biz_opaque_callback_data` the message was sent with
+        biz_opaque_callback_data: Option<String>,
+    },
+    /// Message failed
+    MessageFailed {
+        message_id: String,
+        recipient: String,
+        /// Code of the first error, 0 if none was reported
+        error_code: i32,
+        /// All reported errors, with title, message and details
+        errors: Vec<WebhookError>,
+        /// Status timestamp (Unix seconds)
+        timestamp: String,
+        /// Conversation the message was billed in, if reported
+        conversation: Option<ConversationInfo>,
+        /// Pricing of the message, if reported
+        pricing: Option<PricingInfo>,
+        /// `biz_opaque_callback_data` the message was sent with
+        biz_opaque_callback_data: Option<String>,
+    },
+    /// Template status changed (`message_template_status_update`)
+    TemplateStatusUpdate(TemplateStatusUpdate),
+    /// Template quality score changed (`message_template_quality_update`)
+    TemplateQualityUpdate(TemplateQualityUpdate),
+    /// Template category changed (`template_category_update`)
+    TemplateCategoryUpdate(TemplateCategoryUpdate),
+    /// Template content changed (`template_components_update`)
+    TemplateComponentsUpdate(TemplateComponentsUpdate),
+    /// Business account verified, banned, restricted, ... (`account_update`)
+    AccountUpdate(AccountUpdate),
+    /// Business account review decided (`account_review_update`)
+    AccountReviewUpdate(AccountReviewUpdate),
+    /// Display name approved or rejected (`phone_number_name_update`)
+    PhoneNumberNameUpdate(PhoneNumberNameUpdate),
+    /// Quality rating or messaging limit changed (`phone_number_quality_update`)
+    PhoneNumberQualityUpdate(PhoneNumberQualityUpdate),
+    /// Business capabilities changed (`business_capability_update`)
+    BusinessCapabilityUpdate(BusinessCapabilityUpdate),
+    /// Two-step verification PIN changed or reset (`security`)
+    Security(SecurityUpdate),
+    /// Unknown event type
+    Unknown,
+}
+
+/// Kind of a [`WebhookEvent`], used to route events to handlers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// Text message received
+    Text,
+    /// Image message received
+    Image,
+    /// Video message received
+    Video,
+    /// Audio message received
+    Audio,
+    /// Document message received
+    Document,
+    /// Sticker message received
+    Sticker,
+    /// Location message received
+    Location,
+    /// Contact message received
+    Contacts,
+    /// Reaction received
+    Reaction,
+    /// Interactive button reply
+    ButtonReply,
+    /// Interactive list reply
+    ListReply,
+    /// Template quick reply button pressed
+    QuickReply,
+    /// WhatsApp Flow completed
+    FlowReply,
+    /// Order placed
+    Order,
+    /// System message
+    System,
+    /// User opened the chat for the first time
+    RequestWelcome,
+    /// Unsupported message type
+    Unsupported,
+    /// Message status update
+    Status(StatusKind),
+    /// Template status update
+    TemplateStatus,
+    /// Template quality update
+    TemplateQuality,
+    /// Template category update
+    TemplateCategory,
+    /// Template components update
+    TemplateComponents,
+    /// Business account update
+    AccountUpdate,
+    /// Business account review update
+    AccountReview,
+    /// Display name update
+    PhoneNumberName,
+    /// Phone number quality update
+    PhoneNumberQuality,
+    /// Business capability update
+    BusinessCapability,
+    /// Security event
+    Security,
+    /// Unknown event type
+    Unknown,
+}
+
+/// Status of a sent message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    /// Message sent
+    Sent,
+    /// Message delivered
+    Delivered,
+    /// Message read
+    Read,
+    /// Message failed
+    Failed,
+}
+
+impl WebhookEvent {
+    /// Kind of the event
+    pub fn kind(&self) -> EventKind {
+        match self {
+            WebhookEvent::TextMessage { .. } => EventKind::Text,
+            WebhookEvent::ImageMessage { .. } => EventKind::Image,
+            WebhookEvent::VideoMessage { .. } => EventKind::Video,
+            WebhookEvent::AudioMessage { .. } => EventKind::Audio,
+            WebhookEvent::DocumentMessage { .. } => EventKind::Document,
+            WebhookEvent::StickerMessage { .. } => EventKind::Sticker,
+            WebhookEvent::LocationMessage { .. } => EventKind::Location,
+            WebhookEvent::ContactMessage { .. } => EventKind::Contacts,
+            WebhookEvent::Reaction { .. } => EventKind::Reaction,
+            WebhookEvent::ButtonReply { .. } => EventKind::ButtonReply,
+            WebhookEvent::ListReply { .. } => EventKind::ListReply,
+            WebhookEvent::QuickReply { .. } => EventKind::QuickReply,
+            WebhookEvent::FlowReply { .. } => EventKind::FlowReply,
+            WebhookEvent::OrderMessage { .. } => EventKind::Order,
+            WebhookEvent::SystemMessage { .. } => EventKind::System,
+            WebhookEvent::RequestWelcome { .. } => EventKind::RequestWelcome,
+            WebhookEvent::UnsupportedMessage { .. } => EventKind::Unsupported,
+            WebhookEvent::MessageSent { .. } => EventKind::Status(StatusKind::Sent),
+            WebhookEvent::MessageDelivered { .. } => EventKind::Status(StatusKind::Delivered),
+            WebhookEvent::MessageRead { .. } => EventKind::Status(StatusKind::Read),
+            WebhookEvent::MessageFailed { .. } => EventKind::Status(StatusKind::Failed),
+            WebhookEvent::TemplateStatusUpdate(_) => EventKind::TemplateStatus,
+            WebhookEvent::TemplateQualityUpdate(_) => EventKind::TemplateQuality,
+            WebhookEvent::TemplateCategoryUpdate(_) => EventKind::TemplateCategory,
+            WebhookEvent::TemplateComponentsUpdate(_) => EventKind::TemplateComponents,
+            WebhookEvent::AccountUpdate(_) => EventKind::AccountUpdate,
+            WebhookEvent::AccountReviewUpdate(_) => EventKind::AccountReview,
+            WebhookEvent::PhoneNumberNameUpdate(_) => EventKind::PhoneNumberName,
+            WebhookEvent::PhoneNumberQualityUpdate(_) => EventKind::PhoneNumberQuality,
+            WebhookEvent::BusinessCapabilityUpdate(_) => EventKind::BusinessCapability,
+            WebhookEvent::Security(_) => EventKind::Security,
+            WebhookEvent::Unknown => EventKind::Unknown,
+        }
+    }
+
+    /// ID of the message the event is about
+    ///
+    /// For reactions this is the ID of the message reacted to.
+    pub fn message_id(&self) -> Option<&str> {
+        match self {
+            WebhookEvent::TextMessage { message_id, .. }
+            | WebhookEvent::ImageMessage { message_id, .. }
+            | WebhookEvent::VideoMessage { message_id, .. }
+            | WebhookEvent::AudioMessage { message_id, .. }
+            | WebhookEvent::DocumentMessage { message_id, .. }
+            | WebhookEvent::StickerMessage { message_id, .. }
+            | WebhookEvent::LocationMessage { message_id, .. }
+            | WebhookEvent::ContactMessage { message_id, .. }
+            | WebhookEvent::Reaction { message_id, .. }
+            | WebhookEvent::ButtonReply { message_id, .. }
+            | WebhookEvent::ListReply { message_id, .. }
+            | WebhookEvent::QuickReply { message_id, .. }
+            | WebhookEvent::FlowReply { message_id, .. }
+            | WebhookEvent::OrderMessage { message_id, .. }
+            | WebhookEvent::SystemMessage { message_id, .. }
+            | WebhookEvent::RequestWelcome { message_id, .. }
+            | WebhookEvent::UnsupportedMessage { message_id, .. }
+            | WebhookEvent::MessageSent { message_id, .. }
+            | WebhookEvent::MessageDelivered { message_id, .. }
+            | WebhookEvent::MessageRead { message_id, .. }
+            | WebhookEvent::MessageFailed { message_id, .. } => Some(message_id),
+            _ => None,
+        }
+    }
+
+    /// WhatsApp ID of the user the event comes from or is about
+    ///
+    /// The sender for incoming messages, the recipient for status updates.
+    pub fn user(&self) -> Option<&str> {
+        match self {
+            WebhookEvent::TextMessage { from, .. }
+            | WebhookEvent::ImageMessage { from, .. }
+            | WebhookEvent::VideoMessage { from, .. }
+            | WebhookEvent::AudioMessage { from, .. }
+            | WebhookEvent::DocumentMessage { from, .. }
+            | WebhookEvent::StickerMessage { from, .. }
+            | WebhookEvent::LocationMessage { from, .. }
+            | WebhookEvent::ContactMessage { from, .. }
+            | WebhookEvent::Reaction { from, .. }
+            | WebhookEvent::ButtonReply { from, .. }
+            | WebhookEvent::ListReply { from, .. }
+            | WebhookEvent::QuickReply { from, .. }
+            | WebhookEvent::FlowReply { from, .. }
+            | WebhookEvent::OrderMessage { from, .. }
+            | WebhookEvent::SystemMessage { from, .. }
+            | WebhookEvent::RequestWelcome { from, .. }
+            | WebhookEvent::UnsupportedMessage { from, .. } => Some(from),
+            WebhookEvent::MessageSent { recipient, .. }
+            | WebhookEvent::MessageDelivered { recipient, .. }
+            | WebhookEvent::MessageRead { recipient, .. }
+            | WebhookEvent::MessageFailed { recipient, .. } => Some(recipient),
+            _ => None,
+        }
+    }
+}
+
+impl WebhookPayload {
+    /// Parse webhook events from the payload
+    ///
+    /// Use [`envelopes`](Self::envelopes) to also get the receiving number,
+    /// the sender and the timestamp of each event.
+    pub fn events(&self) -> Vec<WebhookEvent> {
+        self.envelopes()
+            .into_iter()
+            .map(|envelope| envelope.event)
+            .collect()
+    }
+
+    /// Parse webhook events from the payload, each wrapped with its metadata
+    pub fn envelopes(&self) -> Vec<WebhookEnvelope> {
+        let mut envelopes = Vec::new();
+
+        for entry in &self.entry {
+            for change in &entry.changes {
+                let value = &change.value;
+                let base = WebhookEnvelope {
+                    waba_id: entry.id.clone(),
+                    phone_number_id: value.metadata.as_ref().map(|m| m.phone_number_id.clone()),
+                    display_phone_number: value
+                        .metadata
+                        .as_ref()
+                        .map(|m| m.display_phone_number.clone()),
+                    timestamp: entry.time,
+                    ..WebhookEnvelope::from(WebhookEvent::Unknown)
+                };
+
+                // Handle messages
+                if let Some(messages) = &value.messages {
+                    for msg in messages {
+                        let profile_name = value.profile_name(&msg.from);
+                        envelopes.push(WebhookEnvelope {
+                            event: msg.to_event(profile_name),
+                            timestamp: msg.timestamp.parse().ok().or(entry.time),
+                            sender_name: profile_name.map(String::from),
+                            sender_wa_id: Some(msg.from.clone()),
+                            raw: raw_value(msg),
+                            ..base.clone()
+                        });
+                    }
+                }
+
+                // Handle statuses
+                if let Some(statuses) = &value.statuses {
+                    for status in statuses {
+                        envelopes.push(WebhookEnvelope {
+                            event: status.to_event(),
+                            timestamp: status.timestamp.parse().ok().or(entry.time),
+                            raw: raw_value(status),
+                            ..base.clone()
+                        });
+                    }
+                }
+
+                if let Some(event) = change.field_event() {
+                    envelopes.push(WebhookEnvelope {
+                        event,
+                        raw: Some(serde_json::Value::Object(value.other.clone())),
+                        ..base
+                    });
+                }
+            }
+        }
+
+        envelopes
+    }
+}
+
+impl WebhookValue {
+    /// Profile name of the contact with the given WhatsApp ID
+    ///
+    /// Falls back to the only contact of the value if none matches exactly,
+    /// since `wa_id` and `from` can differ in formatting.
+    fn profile_name(&self, wa_id: &str) -> Option<&str> {
+        let contacts = self.contacts.as_deref()?;
+        contacts
+            .iter()
+            .find(|contact| contact.wa_id == wa_id)
+            .or(match contacts {
+                [only] => Some(only),
+                _ => None,
+            })
+            .map(|contact| contact.profile.name.as_str())
+    }
+}
+
+impl WebhookMessage {
+    /// Event for this message
+    fn to_event(&self, profile_name: Option<&str>) -> WebhookEvent {
+        let from = self.from.clone();
+        let message_id = self.id.clone();
+        let timestamp = self.timestamp.clone();
+        let profile_name = profile_name.map(String::from);
+        let context = self.context.clone();
+
+        match self.message_type.as_str() {
+            "text" => {
+                let Some(text) = &self.text else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::TextMessage {
+                    from,
+                    text: text.body.clone(),
+                    message_id,
+                    timestamp,
+                    profile_name,
+                    context,
+                    referral: self.referral.clone(),
+                }
+            }
+            "image" => {
+                let Some(image) = &self.image else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::ImageMessage {
+                    from,
+                    media_id: image.id.clone(),
+                    message_id,
+                    caption: image.caption.clone(),
+                    mime_type: image.mime_type.clone(),
+                    sha256: image.sha256.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                    referral: self.referral.clone(),
+                }
+            }
+            "video" => {
+                let Some(video) = &self.video else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::VideoMessage {
+                    from,
+                    media_id: video.id.clone(),
+                    message_id,
+                    caption: video.caption.clone(),
+                    mime_type: video.mime_type.clone(),
+                    sha256: video.sha256.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                    referral: self.referral.clone(),
+                }
+            }
+            "audio" => {
+                let Some(audio) = &self.audio else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::AudioMessage {
+                    from,
+                    media_id: audio.id.clone(),
+                    message_id,
+                    mime_type: audio.mime_type.clone(),
+                    sha256: audio.sha256.clone(),
+                    voice: audio.voice.unwrap_or(false),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "document" => {
+                let Some(doc) = &self.document else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::DocumentMessage {
+                    from,
+                    media_id: doc.id.clone(),
+                    message_id,
+                    filename: doc.filename.clone(),
+                    caption: doc.caption.clone(),
+                    mime_type: doc.mime_type.clone(),
+                    sha256: doc.sha256.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "sticker" => {
+                let Some(sticker) = &self.sticker else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::StickerMessage {
+                    from,
+                    media_id: sticker.id.clone(),
+                    message_id,
+                    mime_type: sticker.mime_type.clone(),
+                    sha256: sticker.sha256.clone(),
+                    animated: sticker.animated.unwrap_or(false),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "location" => {
+                let Some(loc) = &self.location else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::LocationMessage {
+                    from,
+                    latitude: loc.latitude,
+                    longitude: loc.longitude,
+                    message_id,
+                    name: loc.name.clone(),
+                    address: loc.address.clone(),
+                    url: loc.url.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "contacts" => WebhookEvent::ContactMessage {
+                from,
+                message_id,
+                contacts: self.contacts.clone().unwrap_or_default(),
+                timestamp,
+                profile_name,
+                context,
+            },
+            "reaction" => {
+                let Some(reaction) = &self.reaction else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::Reaction {
+                    from,
+                    message_id: reaction.message_id.clone(),
+                    emoji: reaction.emoji.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "interactive" => {
+                let Some(interactive) = &self.interactive else {
+                    return WebhookEvent::Unknown;
+                };
+                match interactive.response_type.as_str() {
+                    "button_reply" => {
+                        let Some(br) = &interactive.button_reply else {
+                            return WebhookEvent::Unknown;
+                        };
+                        WebhookEvent::ButtonReply {
+                            from,
+                            button_id: br.id.clone(),
+                            button_title: br.title.clone(),
+                            message_id,
+                            timestamp,
+                            profile_name,
+                            context,
+                        }
+                    }
+                    "list_reply" => {
+                        let Some(lr) = &interactive.list_reply else {
+                            return WebhookEvent::Unknown;
+                        };
+                        WebhookEvent::ListReply {
+                            from,
+                            row_id: lr.id.clone(),
+                            row_title: lr.title.clone(),
+                            message_id,
+                            row_description: lr.description.clone(),
+                            timestamp,
+                            profile_name,
+                            context,
+                        }
+                    }
+                    "nfm_reply" => {
+                        let Some(flow) = &interactive.nfm_reply else {
+                            return WebhookEvent::Unknown;
+                        };
+                        WebhookEvent::FlowReply {
+                            from,
+                            message_id,
+                            name: flow.name.clone(),
+                            body: flow.body.clone(),
+                            response_json: flow.response_json.clone(),
+                            timestamp,
+                            profile_name,
+                            context,
+                        }
+                    }
+                    _ => WebhookEvent::Unknown,
+                }
+            }
+            "button" => {
+                let Some(button) = &self.button else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::QuickReply {
+                    from,
+                    message_id,
+                    text: button.text.clone(),
+                    payload: button.payload.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "order" => {
+                let Some(order) = &self.order else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::OrderMessage {
+                    from,
+                    message_id,
+                    order: order.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "system" => {
+                let Some(system) = &self.system else {
+                    return WebhookEvent::Unknown;
+                };
+                WebhookEvent::SystemMessage {
+                    from,
+                    message_id,
+                    system: system.clone(),
+                    timestamp,
+                    profile_name,
+                    context,
+                }
+            }
+            "request_welcome" => WebhookEvent::RequestWelcome {
+                from,
+                message_id,
+                timestamp,
+                profile_name,
+                context,
+            },
+            "unsupported" | "unknown" => WebhookEvent::UnsupportedMessage {
+                from,
+                message_id,
+                message_type: self.message_type.clone(),
+                errors: self.errors.clone().unwrap_or_default(),
+                timestamp,
+                profile_name,
+                context,
+            },
+            _ => WebhookEvent::Unknown,
+        }
+    }
+}
+
+impl WebhookStatus {
+    /// Event for this status update
+    fn to_event(&self) -> WebhookEvent {
+        let message_id = self.id.clone();
+        let recipient = self.recipient_id.clone();
+        let timestamp = self.timestamp.clone();
+        let conversation = self.conversation.clone();
+        let pricing = self.pricing.clone();
+        let biz_opaque_callback_data = self.biz_opaque_callback_data.clone();
+        match self.status.as_str() {
+            "sent" => WebhookEvent::MessageSent {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                biz_opaque_callback_data,
+            },
+            "delivered" => WebhookEvent::MessageDelivered {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                biz_opaque_callback_data,
+            },
+            "read" => WebhookEvent::MessageRead {
+                message_id,
+                recipient,
+                timestamp,
+                conversation,
+                pricing,
+                biz_opaque_callback_data,
+            },
+            "failed" => {
+                let errors = self.errors.clone().unwrap_or_default();
+                WebhookEvent::MessageFailed {
+                    message_id,
+                    recipient,
+                    error_code: errors.first().map(|e| e.code).unwrap_or(0),
+                    errors,
+                    timestamp,
+                    conversation,
+                    pricing,
+                    biz_opaque_callback_data,
+                }
+            }
+            _ => WebhookEvent::Unknown,
+        }
+    }
+}
+
+impl WebhookChange {
+    /// Event for the value of a change other than `messages`
+    fn field_event(&self) -> Option<WebhookEvent> {
+        let event = match self.field.as_str() {
+            "message_template_status_update" => {
+                self.parse_other().map(WebhookEvent::TemplateStatusUpdate)
+            }
+            "message_template_quality_update" => {
+                self.parse_other().map(WebhookEvent::TemplateQualityUpdate)
+            }
+            "template_category_update" => {
+                self.parse_other().map(WebhookEvent::TemplateCategoryUpdate)
+            }
+            "template_components_update" => self
+                .parse_other()
+                .map(WebhookEvent::TemplateComponentsUpdate),
+            "account_update" => self.parse_other().map(WebhookEvent::AccountUpdate),
+            "account_review_update" => self.parse_other().map(WebhookEvent::AccountReviewUpdate),
+            "phone_number_name_update" => {
+                self.parse_other().map(WebhookEvent::PhoneNumberNameUpdate)
+            }
+            "phone_number_quality_update" => self
+                .parse_other()
+                .map(WebhookEvent::PhoneNumberQualityUpdate),
+            "business_capability_update" => self
+                .parse_other()
+                .map(WebhookEvent::BusinessCapabilityUpdate),
+            "security" => self.parse_other().map(WebhookEvent::Security),
+            _ => return None,
+        };
+
+        Some(event.unwrap_or(WebhookEvent::Unknown))
+    }
+
+    fn parse_other<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(serde_json::Value::Object(self.value.other.clone())).ok()
+    }
+}
+
+/// JSON of a parsed message or status, without the fields it didn't have
+fn raw_value<T: Serialize>(value: &T) -> Option<serde_json::Value> {
+    let mut raw = serde_json::to_value(value).ok()?;
+    if let Some(object) = raw.as_object_mut() {
+        object.retain(|_, field| !field.is_null());
+    }
+    Some(raw)
+}
+
+/// Deserialize a value sent either as a number or as a string
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(serde_json::Number),
+        String(String),
+    }
+
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(number) => number.to_string(),
+        Value::String(string) => string,
+    })
+}
+
+/// Header carrying the payload signature, checked by [`verify_signature`]
+pub const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// Verify webhook signature using HMAC-SHA256
+///
+/// The digest is compared in constant time.
+///
+/// # Arguments
+///
+/// * `payload` - The raw request body
+/// * `signature` - The X-Hub-Signature-256 header value, if present
+/// * `app_secret` - Your Facebook App Secret
+///
+/// # Example
+///
+/// ```rust
+/// use wacloudapi::webhooks::{compute_signature, verify_signature};
+///
+/// let body = br#"{"object":"whatsapp_business_account","entry":[]}"#;
+/// let header = compute_signature(body, "app_secret");
+///
+/// assert!(verify_signature(body, Some(&header), "app_secret").is_ok());
+/// ```
+pub fn verify_signature(
+    payload: &[u8],
+    signature: Option<&str>,
+    app_secret: impl AsRef<[u8]>,
+) -> Result<(), SignatureError> {
+    let signature = signature.ok_or(SignatureError::MissingHeader)?;
+    let digest = signature
+        .strip_prefix("sha256=")
+        .ok_or(SignatureError::MalformedPrefix)?;
+    let expected = decode_hex(digest).ok_or(SignatureError::MalformedDigest)?;
+
+    hmac_sha256(app_secret.as_ref(), payload)
+        .verify_slice(&expected)
+        .map_err(|_| SignatureError::Mismatch)
+}
+
+/// Compute the X-Hub-Signature-256 header value for a payload
+///
+/// Returns the signature in the `sha256=<hex digest>` form sent by Meta.
+pub fn compute_signature(payload: &[u8], app_secret: impl AsRef<[u8]>) -> String {
+    use std::fmt::Write;
+
+    let digest = hmac_sha256(app_secret.as_ref(), payload)
+        .finalize()
+        .into_bytes();
+    let mut signature = String::with_capacity(7 + digest.len() * 2);
+    signature.push_str("sha256=");
+    for byte in digest {
+        write!(&mut signature, "{:02x}", byte).unwrap();
+    }
+    signature
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Hmac<Sha256> {
+    // HMAC accepts keys of any length, so this never fails
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac
+}
+
+/// Compare two secrets in constant time
+///
+/// Both are hashed with HMAC-SHA256 under the same key, so the comparison
+/// does not depend on where they differ or on their lengths.
+fn secrets_match(given: &[u8], expected: &[u8]) -> bool {
+    let expected = hmac_sha256(b"wacloudapi", expected).finalize().into_bytes();
+    hmac_sha256(b"wacloudapi", given)
+        .verify_slice(&expected)
+        .is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; 32]> {
+    let bytes = hex.as_bytes();
+    if bytes.len() != 64 {
+        return None;
+    }
+
+    let mut out = [0u8; 32];
+    for (i, pair) in bytes.chunks(2).enumerate() {
+        let high = (pair[0] as char).to_digit(16)?;
+        let low = (pair[1] as char).to_digit(16)?;
+        out[i] = (high * 16 + low) as u8;
+    }
+    Some(out)
+}
@@ -0,0 +1,552 @@
+//! Routing of webhook events to async handlers
+//!
+//! A [`WebhookRouter`] maps each [`EventKind`] to one or more async handlers,
+//! so consumers don't have to match on every
+//! [`WebhookEvent`](super::WebhookEvent) themselves. Handlers receive each
+//! event in a [`WebhookEnvelope`], with the number it was sent to and its
+//! sender.
+//! Middleware runs before the handlers and can skip events (deduplication,
+//! authorization) or observe them (logging).
+
+use super::{DedupStore, Deduplicator, EventKind, StatusKind, WebhookEnvelope, WebhookPayload};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Error returned by a handler or middleware
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Result returned by a handler
+pub type HandlerResult = std::result::Result<(), HandlerError>;
+
+/// Future returned by [`Middleware::before`]
+pub type MiddlewareFuture<'a> =
+    Pin<Box<dyn Future<Output = std::result::Result<Next, HandlerError>> + Send + 'a>>;
+
+/// Future returned by [`Middleware::after`]
+pub type MiddlewareAfterFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+type HandlerFuture = Pin<Box<dyn Future<Output = HandlerResult> + Send>>;
+type Handler = Arc<dyn Fn(WebhookEnvelope) -> HandlerFuture + Send + Sync>;
+
+/// Decision of a middleware about an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Next {
+    /// Pass the event on to the next middleware and the handlers
+    Continue,
+    /// Drop the event without calling any handler
+    Skip,
+}
+
+/// What happened to an event during dispatch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// All handlers succeeded
+    Handled,
+    /// At least one handler or middleware failed
+    Failed,
+    /// A middleware skipped the event
+    Skipped,
+    /// No handler and no fallback was registered for the event
+    Unhandled,
+}
+
+/// Hook that runs around every dispatched event
+///
+/// Closures taking `&WebhookEnvelope` and returning a future of
+/// `Result<Next, HandlerError>` implement this trait.
+pub trait Middleware: Send + Sync + 'static {
+    /// Called before the handlers; return [`Next::Skip`] to drop the event
+    ///
+    /// An error drops the event and is reported as a failure.
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a>;
+
+    /// Called after the event was dispatched
+    fn after<'a>(
+        &'a self,
+        _event: &'a WebhookEnvelope,
+        _outcome: Outcome,
+    ) -> MiddlewareAfterFuture<'a> {
+        Box::pin(std::future::ready(()))
+    }
+}
+
+impl<F, Fut> Middleware for F
+where
+    F: Fn(&WebhookEnvelope) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::result::Result<Next, HandlerError>> + Send + 'static,
+{
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+        Box::pin(self(event))
+    }
+}
+
+/// A handler or middleware error, with the event it occurred on
+#[derive(Debug)]
+pub struct HandlerFailure {
+    /// Position of the event in the dispatched batch
+    pub index: usize,
+    /// Kind of the event
+    pub kind: EventKind,
+    /// ID of the message the event is about
+    pub message_id: Option<String>,
+    /// The error
+    pub error: HandlerError,
+}
+
+/// Summary of a dispatched batch of events
+#[derive(Debug, Default)]
+pub struct DispatchReport {
+    /// Events passed to at least one handler or the fallback
+    pub handled: usize,
+    /// Events dropped by middleware
+    pub skipped: usize,
+    /// Events with no matching handler and no fallback
+    pub unhandled: usize,
+    /// Errors returned by handlers and middleware
+    pub failures: Vec<HandlerFailure>,
+}
+
+impl DispatchReport {
+    /// Whether every handler and middleware succeeded
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Dispatches webhook events to async handlers by event kind
+///
+/// Events are dispatched one at a time, in payload order. A failing handler
+/// does not stop the other handlers of the event or the rest of the batch;
+/// its error is collected in the [`DispatchReport`].
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use wacloudapi::webhooks::{StatusKind, WebhookEvent, WebhookPayload, WebhookRouter};
+///
+/// # async fn example(payload: WebhookPayload) {
+/// let router = WebhookRouter::new()
+///     .on_text(|envelope| async move {
+///         if let WebhookEvent::TextMessage { from, text, .. } = envelope.event {
+///             println!("{} says {} to {:?}", from, text, envelope.phone_number_id);
+///         }
+///         Ok(())
+///     })
+///     .on_status(StatusKind::Delivered, |event| async move {
+///         println!("delivered: {:?}", event.message_id());
+///         Ok(())
+///     })
+///     .fallback(|event| async move {
+///         println!("unhandled: {:?}", event.kind());
+///         Ok(())
+///     });
+///
+/// let report = router.dispatch(&payload).await;
+/// for failure in &report.failures {
+///     eprintln!("handler for {:?} failed: {}", failure.kind, failure.error);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct WebhookRouter {
+    handlers: HashMap<EventKind, Vec<Handler>>,
+    fallback: Option<Handler>,
+    middleware: Vec<Arc<dyn Middleware>>,
+}
+
+impl WebhookRouter {
+    /// Create a router without handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a handler for an event kind
+    ///
+    /// Several handlers can be registered for the same kind; they run in
+    /// registration order.
+    pub fn on<F, Fut>(mut self, kind: EventKind, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.handlers
+            .entry(kind)
+            .or_default()
+            .push(boxed_handler(handler));
+        self
+    }
+
+    /// Register a handler for text messages
+    pub fn on_text<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Text, handler)
+    }
+
+    /// Register a handler for image messages
+    pub fn on_image<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Image, handler)
+    }
+
+    /// Register a handler for video messages
+    pub fn on_video<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Video, handler)
+    }
+
+    /// Register a handler for audio messages
+    pub fn on_audio<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Audio, handler)
+    }
+
+    /// Register a handler for document messages
+    pub fn on_document<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Document, handler)
+    }
+
+    /// Register a handler for sticker messages
+    pub fn on_sticker<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Sticker, handler)
+    }
+
+    /// Register a handler for location messages
+    pub fn on_location<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Location, handler)
+    }
+
+    /// Register a handler for contact messages
+    pub fn on_contacts<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Contacts, handler)
+    }
+
+    /// Register a handler for reactions
+    pub fn on_reaction<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Reaction, handler)
+    }
+
+    /// Register a handler for interactive button replies
+    pub fn on_button_reply<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::ButtonReply, handler)
+    }
+
+    /// Register a handler for interactive list replies
+    pub fn on_list_reply<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::ListReply, handler)
+    }
+
+    /// Register a handler for template quick reply buttons
+    pub fn on_quick_reply<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::QuickReply, handler)
+    }
+
+    /// Register a handler for completed WhatsApp Flows
+    pub fn on_flow_reply<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::FlowReply, handler)
+    }
+
+    /// Register a handler for orders
+    pub fn on_order<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Order, handler)
+    }
+
+    /// Register a handler for system messages
+    pub fn on_system<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::System, handler)
+    }
+
+    /// Register a handler for welcome requests
+    pub fn on_request_welcome<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::RequestWelcome, handler)
+    }
+
+    /// Register a handler for unsupported messages
+    pub fn on_unsupported<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Unsupported, handler)
+    }
+
+    /// Register a handler for one message status
+    pub fn on_status<F, Fut>(self, status: StatusKind, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Status(status), handler)
+    }
+
+    /// Register a handler for template status updates
+    pub fn on_template_status_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::TemplateStatus, handler)
+    }
+
+    /// Register a handler for template quality updates
+    pub fn on_template_quality_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::TemplateQuality, handler)
+    }
+
+    /// Register a handler for template category updates
+    pub fn on_template_category_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::TemplateCategory, handler)
+    }
+
+    /// Register a handler for template components updates
+    pub fn on_template_components_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::TemplateComponents, handler)
+    }
+
+    /// Register a handler for business account updates
+    pub fn on_account_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::AccountUpdate, handler)
+    }
+
+    /// Register a handler for business account review updates
+    pub fn on_account_review_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::AccountReview, handler)
+    }
+
+    /// Register a handler for display name updates
+    pub fn on_phone_number_name_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::PhoneNumberName, handler)
+    }
+
+    /// Register a handler for phone number quality updates
+    pub fn on_phone_number_quality_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::PhoneNumberQuality, handler)
+    }
+
+    /// Register a handler for business capability updates
+    pub fn on_business_capability_update<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::BusinessCapability, handler)
+    }
+
+    /// Register a handler for security events
+    pub fn on_security<F, Fut>(self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.on(EventKind::Security, handler)
+    }
+
+    /// Register the handler for events without a matching handler
+    ///
+    /// Replaces any previously registered fallback.
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HandlerResult> + Send + 'static,
+    {
+        self.fallback = Some(boxed_handler(handler));
+        self
+    }
+
+    /// Add a middleware
+    ///
+    /// Middleware runs in registration order before the handlers of every
+    /// event.
+    pub fn middleware(mut self, middleware: impl Middleware) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Skip events that were already dispatched
+    ///
+    /// Adds a [`Deduplicator`] backed by `store` as middleware. Register it
+    /// before other middleware so duplicates are dropped first.
+    pub fn dedup(self, store: impl DedupStore) -> Self {
+        self.middleware(Deduplicator::new(store))
+    }
+
+    /// Dispatch every event of a webhook payload
+    pub async fn dispatch(&self, payload: &WebhookPayload) -> DispatchReport {
+        self.dispatch_events(payload.envelopes()).await
+    }
+
+    /// Dispatch a batch of events
+    ///
+    /// Accepts [`WebhookEnvelope`]s as well as bare
+    /// [`WebhookEvent`](super::WebhookEvent)s, which are dispatched without
+    /// metadata.
+    pub async fn dispatch_events<E>(&self, events: impl IntoIterator<Item = E>) -> DispatchReport
+    where
+        E: Into<WebhookEnvelope>,
+    {
+        let mut report = DispatchReport::default();
+
+        for (index, event) in events.into_iter().map(Into::into).enumerate() {
+            let outcome = self.dispatch_one(index, &event, &mut report).await;
+            match outcome {
+                Outcome::Handled | Outcome::Failed => report.handled += 1,
+                Outcome::Skipped => report.skipped += 1,
+                Outcome::Unhandled => report.unhandled += 1,
+            }
+            for middleware in &self.middleware {
+                middleware.after(&event, outcome).await;
+            }
+        }
+
+        report
+    }
+
+    async fn dispatch_one(
+        &self,
+        index: usize,
+        event: &WebhookEnvelope,
+        report: &mut DispatchReport,
+    ) -> Outcome {
+        let failure = |error| HandlerFailure {
+            index,
+            kind: event.kind(),
+            message_id: event.message_id().map(String::from),
+            error,
+        };
+
+        for middleware in &self.middleware {
+            match middleware.before(event).await {
+                Ok(Next::Continue) => {}
+                Ok(Next::Skip) => return Outcome::Skipped,
+                Err(error) => {
+                    report.failures.push(failure(error));
+                    return Outcome::Failed;
+                }
+            }
+        }
+
+        let handlers = match self.handlers.get(&event.kind()) {
+            Some(handlers) if !handlers.is_empty() => handlers.as_slice(),
+            _ => match &self.fallback {
+                Some(fallback) => std::slice::from_ref(fallback),
+                None => return Outcome::Unhandled,
+            },
+        };
+
+        let mut outcome = Outcome::Handled;
+        for handler in handlers {
+            if let Err(error) = handler(event.clone()).await {
+                report.failures.push(failure(error));
+                outcome = Outcome::Failed;
+            }
+        }
+        outcome
+    }
+}
+
+impl std::fmt::Debug for WebhookRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookRouter")
+            .field("kinds", &self.handlers.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback.is_some())
+            .field("middleware", &self.middleware.len())
+            .finish()
+    }
+}
+
+fn boxed_handler<F, Fut>(handler: F) -> Handler
+where
+    F: Fn(WebhookEnvelope) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = HandlerResult> + Send + 'static,
+{
+    Arc::new(move |event| Box::pin(handler(event)) as HandlerFuture)
+}
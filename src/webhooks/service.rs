@@ -0,0 +1,149 @@
+//! Tower service for the webhook endpoint
+
+use super::{EndpointResponse, VerificationQuery, WebhookEndpoint, SIGNATURE_HEADER};
+use bytes::Bytes;
+use http::header::{ALLOW, CONTENT_LENGTH};
+use http::{Method, Request, Response, StatusCode};
+use http_body::Body;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// Default limit for `POST` bodies (1 MiB)
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Future returned by [`WebhookService`]
+pub type WebhookServiceFuture =
+    Pin<Box<dyn Future<Output = std::result::Result<Response<Full<Bytes>>, Infallible>> + Send>>;
+
+/// [`tower::Service`](tower_service::Service) serving a [`WebhookEndpoint`]
+///
+/// Requires the `tower` feature. Works with any server built on tower, such
+/// as hyper (through `hyper-util`) or tonic, without depending on a specific
+/// web framework.
+///
+/// `GET` requests answer the verification handshake and `POST` requests are
+/// verified, parsed and handed to the endpoint's handler; see
+/// [`WebhookEndpoint`] for the status codes returned. Bodies larger than
+/// [`max_body_size`](Self::max_body_size) are rejected with `413` and other
+/// methods with `405`. The request path is not checked, so the service is
+/// meant to be mounted at the webhook URL.
+///
+/// # Example
+///
+/// ```rust
+/// use wacloudapi::webhooks::{WebhookEndpoint, WebhookEvent, WebhookService};
+///
+/// let (sender, _events) = tokio::sync::mpsc::channel::<WebhookEvent>(100);
+/// let endpoint = WebhookEndpoint::new("verify_token", "app_secret", sender);
+///
+/// // Serve for example with hyper_util::service::TowerToHyperService
+/// let service = WebhookService::new(endpoint).max_body_size(256 * 1024);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    endpoint: WebhookEndpoint,
+    max_body_size: usize,
+}
+
+impl WebhookService {
+    /// Create a service for `endpoint` with the default body size limit
+    pub fn new(endpoint: WebhookEndpoint) -> Self {
+        Self {
+            endpoint,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Set the maximum accepted `POST` body size in bytes
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = bytes;
+        self
+    }
+}
+
+impl<B> Service<Request<B>> for WebhookService
+where
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = WebhookServiceFuture;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let endpoint = self.endpoint.clone();
+        let max_body_size = self.max_body_size;
+        Box::pin(async move { Ok(handle(&endpoint, max_body_size, request).await) })
+    }
+}
+
+async fn handle<B>(
+    endpoint: &WebhookEndpoint,
+    max_body_size: usize,
+    request: Request<B>,
+) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    match *request.method() {
+        Method::GET => {
+            let query = VerificationQuery::from_query(request.uri().query().unwrap_or_default());
+            into_response(endpoint.verify(&query))
+        }
+        Method::POST => {
+            // Reject oversized bodies up front when the length is announced
+            let announced = request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok());
+            if announced.is_some_and(|length| length > max_body_size as u64) {
+                return too_large();
+            }
+
+            let (parts, body) = request.into_parts();
+            let body = match Limited::new(body, max_body_size).collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(error) if error.is::<LengthLimitError>() => return too_large(),
+                Err(_) => return plain(StatusCode::BAD_REQUEST, "failed to read request body"),
+            };
+
+            let signature = parts
+                .headers
+                .get(SIGNATURE_HEADER)
+                .and_then(|value| value.to_str().ok());
+            into_response(endpoint.receive(signature, &body).await)
+        }
+        _ => {
+            let mut response = plain(StatusCode::METHOD_NOT_ALLOWED, "method not allowed");
+            response
+                .headers_mut()
+                .insert(ALLOW, http::HeaderValue::from_static("GET, POST"));
+            response
+        }
+    }
+}
+
+fn too_large() -> Response<Full<Bytes>> {
+    plain(StatusCode::PAYLOAD_TOO_LARGE, "request body too large")
+}
+
+fn into_response(response: EndpointResponse) -> Response<Full<Bytes>> {
+    plain(response.status, response.body)
+}
+
+fn plain(status: StatusCode, body: impl Into<Bytes>) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(body.into()));
+    *response.status_mut() = status;
+    response
+}
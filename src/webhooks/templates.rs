@@ -0,0 +1,127 @@
+//! Payloads of the message template webhook fields
+
+use super::string_or_number;
+use serde::{Deserialize, Serialize};
+
+/// Value of a `message_template_status_update` change
+///
+/// Sent when a template is approved, rejected, paused, disabled, etc.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateStatusUpdate {
+    /// New status (APPROVED, REJECTED, PENDING, PAUSED, DISABLED, FLAGGED,
+    /// REINSTATED, PENDING_DELETION, ...)
+    pub event: String,
+    /// Template ID
+    #[serde(deserialize_with = "string_or_number")]
+    pub message_template_id: String,
+    /// Template name
+    pub message_template_name: String,
+    /// Template language code
+    pub message_template_language: String,
+    /// Reason for a rejection (e.g. "INCORRECT_CATEGORY"), "NONE" otherwise
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Details for paused or disabled templates
+    #[serde(default)]
+    pub other_info: Option<TemplateOtherInfo>,
+    /// Date a flagged template will be disabled
+    #[serde(default)]
+    pub disable_info: Option<TemplateDisableInfo>,
+}
+
+/// Additional details of a template status change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateOtherInfo {
+    /// Short description (e.g. "FIRST_PAUSE")
+    pub title: String,
+    /// Explanation of the change
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// When a flagged template will be disabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateDisableInfo {
+    /// Disable date, as sent by the API
+    pub disable_date: String,
+}
+
+/// Value of a `message_template_quality_update` change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateQualityUpdate {
+    /// Previous quality score (GREEN, YELLOW, RED, UNKNOWN)
+    pub previous_quality_score: String,
+    /// New quality score (GREEN, YELLOW, RED, UNKNOWN)
+    pub new_quality_score: String,
+    /// Template ID
+    #[serde(deserialize_with = "string_or_number")]
+    pub message_template_id: String,
+    /// Template name
+    pub message_template_name: String,
+    /// Template language code
+    pub message_template_language: String,
+}
+
+/// Value of a `template_category_update` change
+///
+/// Sent when a template's category is about to change or has changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateCategoryUpdate {
+    /// Template ID
+    #[serde(deserialize_with = "string_or_number")]
+    pub message_template_id: String,
+    /// Template name
+    pub message_template_name: String,
+    /// Template language code
+    pub message_template_language: String,
+    /// Category before the change
+    #[serde(default)]
+    pub previous_category: Option<String>,
+    /// Category after the change
+    #[serde(default)]
+    pub new_category: Option<String>,
+    /// Category the template will be moved to
+    #[serde(default)]
+    pub correct_category: Option<String>,
+}
+
+/// Value of a `template_components_update` change
+///
+/// Sent when a template's content was edited.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateComponentsUpdate {
+    /// Template ID
+    #[serde(deserialize_with = "string_or_number")]
+    pub message_template_id: String,
+    /// Template name
+    pub message_template_name: String,
+    /// Template language code
+    pub message_template_language: String,
+    /// Body text
+    #[serde(default)]
+    pub message_template_element: Option<String>,
+    /// Header text
+    #[serde(default)]
+    pub message_template_title: Option<String>,
+    /// Footer text
+    #[serde(default)]
+    pub message_template_footer: Option<String>,
+    /// Buttons
+    #[serde(default)]
+    pub message_template_buttons: Option<Vec<TemplateButtonUpdate>>,
+}
+
+/// Button of an updated template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateButtonUpdate {
+    /// Button type (QUICK_REPLY, URL, PHONE_NUMBER, ...)
+    pub message_template_button_type: String,
+    /// Button label
+    pub message_template_button_text: String,
+    /// URL of a URL button
+    #[serde(default)]
+    pub message_template_button_url: Option<String>,
+    /// Phone number of a call button
+    #[serde(default)]
+    pub message_template_button_phone_number: Option<String>,
+}
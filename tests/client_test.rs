@@ -152,3 +152,97 @@ async fn test_authorization_header() {
 
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_builder_defaults() {
+    let client = Client::builder("test_token", "phone_123").build().unwrap();
+
+    assert_eq!(client.api_version(), "v21.0");
+    assert_eq!(
+        client.base_url(),
+        "https://graph.facebook.com/v21.0/phone_123"
+    );
+}
+
+#[test]
+fn test_builder_rejects_invalid_token() {
+    let result = Client::builder("bad\ntoken", "phone_123").build();
+
+    assert!(matches!(result, Err(wacloudapi::Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_builder_user_agent_and_default_headers() {
+    let mock_server = MockServer::start().await;
+    let client = Client::builder(TEST_TOKEN, TEST_PHONE_ID)
+        .base_url(mock_server.uri())
+        .user_agent("my-bot/1.0")
+        .default_header(
+            reqwest::header::HeaderName::from_static("x-tenant"),
+            reqwest::header::HeaderValue::from_static("acme"),
+        )
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .and(header("User-Agent", "my-bot/1.0"))
+        .and(header("X-Tenant", "acme"))
+        .and(header("Authorization", format!("Bearer {}", TEST_TOKEN)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.ua")))
+        .mount(&mock_server)
+        .await;
+
+    let result = client.messages().send_text("628123456789", "Hello").await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_builder_timeout() {
+    let mock_server = MockServer::start().await;
+    let client = Client::builder(TEST_TOKEN, TEST_PHONE_ID)
+        .base_url(mock_server.uri())
+        .timeout(std::time::Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(message_response("wamid.slow"))
+                .set_delay(std::time::Duration::from_secs(2)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let result = client.messages().send_text("628123456789", "Hello").await;
+
+    assert!(matches!(result, Err(wacloudapi::Error::Request(e)) if e.is_timeout()));
+}
+
+#[tokio::test]
+async fn test_builder_custom_http_client() {
+    let mock_server = MockServer::start().await;
+    let http = reqwest::Client::builder()
+        .user_agent("custom-client")
+        .build()
+        .unwrap();
+    let client = Client::builder(TEST_TOKEN, TEST_PHONE_ID)
+        .base_url(mock_server.uri())
+        .http_client(http)
+        .build()
+        .unwrap();
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .and(header("User-Agent", "custom-client"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.cc")))
+        .mount(&mock_server)
+        .await;
+
+    let result = client.messages().send_text("628123456789", "Hello").await;
+
+    assert!(result.is_ok());
+}
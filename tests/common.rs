@@ -2,6 +2,8 @@
 
 #![allow(dead_code)]
 
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
 use wacloudapi::Client;
 use wiremock::MockServer;
 
@@ -43,3 +45,26 @@ pub fn message_response(message_id: &str) -> serde_json::Value {
         ]
     })
 }
+
+/// Empty temporary directory, unique to `name` and this test process
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = temp_path(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Temporary path unique to `name` and this test process, removed if it exists
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("wacloudapi-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Hex-encoded SHA-256 of `data`
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
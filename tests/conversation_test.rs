@@ -0,0 +1,194 @@
+//! Tests for the customer service window tracker
+
+mod common;
+
+use common::*;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wacloudapi::conversation::{ConversationWindow, CUSTOMER_SERVICE_WINDOW};
+use wacloudapi::webhooks::{WebhookPayload, WebhookRouter};
+use wacloudapi::Error;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn payload(value: serde_json::Value) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": TEST_WABA_ID,
+            "changes": [{ "value": value, "field": "messages" }]
+        }]
+    }))
+    .unwrap()
+}
+
+fn incoming(from: &str, timestamp: u64) -> WebhookPayload {
+    payload(serde_json::json!({
+        "messaging_product": "whatsapp",
+        "metadata": {
+            "display_phone_number": "628000000000",
+            "phone_number_id": TEST_PHONE_ID
+        },
+        "contacts": [{ "profile": { "name": "Jane" }, "wa_id": from }],
+        "messages": [{
+            "from": from,
+            "id": "wamid.IN",
+            "timestamp": timestamp.to_string(),
+            "type": "text",
+            "text": { "body": "Hi" }
+        }]
+    }))
+}
+
+#[test]
+fn test_incoming_message_opens_window() {
+    let window = ConversationWindow::new();
+    assert!(!window.is_open("628123456789"));
+    assert_eq!(window.closes_at("628123456789"), None);
+
+    let received = unix_now() - 60 * 60;
+    window.handle_payload(&incoming("628123456789", received));
+
+    let closes_at = UNIX_EPOCH + Duration::from_secs(received) + CUSTOMER_SERVICE_WINDOW;
+    assert_eq!(window.closes_at("628123456789"), Some(closes_at));
+    assert!(window.is_open("628123456789"));
+    assert!(window.is_open("+62 812-3456-789"));
+    assert!(!window.is_open_at("628123456789", closes_at));
+
+    let remaining = window.remaining("628123456789").unwrap();
+    assert!(remaining <= Duration::from_secs(23 * 60 * 60));
+    assert!(remaining > Duration::from_secs(22 * 60 * 60));
+
+    // An older message does not shorten the window
+    window.handle_payload(&incoming("628123456789", received - 10 * 60 * 60));
+    assert_eq!(window.closes_at("628123456789"), Some(closes_at));
+}
+
+#[test]
+fn test_window_closes_after_24_hours() {
+    let window = ConversationWindow::new();
+    window.handle_payload(&incoming("628123456789", unix_now() - 25 * 60 * 60));
+
+    assert!(!window.is_open("628123456789"));
+    assert_eq!(window.remaining("628123456789"), None);
+    assert!(window.closes_at("628123456789").is_some());
+
+    window.prune();
+    assert_eq!(window.closes_at("628123456789"), None);
+}
+
+#[test]
+fn test_service_conversation_extends_window() {
+    let window = ConversationWindow::new();
+    let expires = unix_now() + 2 * 60 * 60;
+    let status = |origin: &str, recipient: &str| {
+        payload(serde_json::json!({
+            "messaging_product": "whatsapp",
+            "metadata": {
+                "display_phone_number": "628000000000",
+                "phone_number_id": TEST_PHONE_ID
+            },
+            "statuses": [{
+                "id": "wamid.OUT",
+                "status": "sent",
+                "timestamp": unix_now().to_string(),
+                "recipient_id": recipient,
+                "conversation": {
+                    "id": "CONVERSATION_ID",
+                    "expiration_timestamp": expires.to_string(),
+                    "origin": { "type": origin }
+                }
+            }]
+        }))
+    };
+
+    window.handle_payload(&status("marketing", "628111"));
+    assert!(!window.is_open("628111"));
+
+    window.handle_payload(&status("service", "628222"));
+    assert!(window.is_open("628222"));
+    assert_eq!(
+        window.closes_at("628222"),
+        Some(UNIX_EPOCH + Duration::from_secs(expires))
+    );
+}
+
+#[test]
+fn test_out_of_range_times_are_ignored() {
+    let window = ConversationWindow::new();
+    window.record_incoming("628111", UNIX_EPOCH + Duration::from_secs(i64::MAX as u64));
+    assert_eq!(window.closes_at("628111"), None);
+
+    window.handle_payload(&payload(serde_json::json!({
+        "messaging_product": "whatsapp",
+        "metadata": {
+            "display_phone_number": "628000000000",
+            "phone_number_id": TEST_PHONE_ID
+        },
+        "statuses": [{
+            "id": "wamid.OUT",
+            "status": "sent",
+            "timestamp": unix_now().to_string(),
+            "recipient_id": "628222",
+            "conversation": {
+                "id": "CONVERSATION_ID",
+                "expiration_timestamp": "18446744073709551615",
+                "origin": { "type": "service" }
+            }
+        }]
+    })));
+    assert_eq!(window.closes_at("628222"), None);
+}
+
+#[tokio::test]
+async fn test_guard_blocks_free_form_messages() {
+    let mock_server = MockServer::start().await;
+    let window = ConversationWindow::new();
+    let client = create_test_client(&mock_server).with_conversation_window(window.clone());
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.OK")))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    // Closed window: free-form messages fail without calling the API
+    let error = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::WindowClosed { ref recipient, closed_at: None } if recipient == "628123456789"
+    ));
+    assert!(client
+        .messages()
+        .send_image_url("628123456789", "https://example.com/a.jpg", None)
+        .await
+        .is_err());
+
+    // Templates are always allowed
+    client
+        .messages()
+        .send_template("628123456789", "hello_world", "en_US", None)
+        .await
+        .unwrap();
+
+    // The user writes in through the webhook router
+    let router = WebhookRouter::new().middleware(window);
+    router.dispatch(&incoming("628123456789", unix_now())).await;
+
+    client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+}
@@ -0,0 +1,226 @@
+//! Tests for the delivery tracker
+
+use std::time::Duration;
+use wacloudapi::delivery::{DeliveryState, DeliveryTracker, DeliveryUpdate};
+use wacloudapi::types::MessageResponse;
+use wacloudapi::webhooks::{WebhookEvent, WebhookPayload, WebhookRouter};
+use wacloudapi::Error;
+
+fn response(id: &str) -> MessageResponse {
+    serde_json::from_value(serde_json::json!({
+        "messaging_product": "whatsapp",
+        "contacts": [{ "input": "+62 811-1222-333", "wa_id": "628111222333" }],
+        "messages": [{ "id": id }]
+    }))
+    .unwrap()
+}
+
+fn status(id: &str, status: &str, timestamp: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "status": status,
+        "timestamp": timestamp.to_string(),
+        "recipient_id": "628111222333"
+    })
+}
+
+fn payload(statuses: Vec<serde_json::Value>) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "628123456789",
+                        "phone_number_id": "PHONE_ID"
+                    },
+                    "statuses": statuses
+                },
+                "field": "messages"
+            }]
+        }]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_lifecycle_in_order() {
+    let tracker = DeliveryTracker::new();
+    tracker.record(&response("wamid.1"));
+
+    let delivery = tracker.get("wamid.1").unwrap();
+    assert_eq!(delivery.state, DeliveryState::Accepted);
+    assert_eq!(delivery.recipient.as_deref(), Some("628111222333"));
+
+    let updates = tracker.handle_payload(&payload(vec![
+        status("wamid.1", "sent", 100),
+        status("wamid.1", "delivered", 101),
+        status("wamid.1", "read", 102),
+    ]));
+    assert_eq!(
+        updates
+            .iter()
+            .map(|update| (update.previous, update.state))
+            .collect::<Vec<_>>(),
+        vec![
+            (Some(DeliveryState::Accepted), DeliveryState::Sent),
+            (Some(DeliveryState::Sent), DeliveryState::Delivered),
+            (Some(DeliveryState::Delivered), DeliveryState::Read),
+        ]
+    );
+
+    let delivery = tracker.get("wamid.1").unwrap();
+    assert_eq!(delivery.state, DeliveryState::Read);
+    assert_eq!(delivery.sent_at, Some(100));
+    assert_eq!(delivery.delivered_at, Some(101));
+    assert_eq!(delivery.read_at, Some(102));
+    assert!(tracker.pending().is_empty());
+}
+
+#[test]
+fn test_out_of_order_statuses_never_move_backwards() {
+    let tracker = DeliveryTracker::new();
+
+    // The status arrives before the send call returned
+    let updates = tracker.handle_payload(&payload(vec![
+        status("wamid.1", "delivered", 101),
+        status("wamid.1", "sent", 100),
+    ]));
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].previous, None);
+    assert_eq!(updates[0].state, DeliveryState::Delivered);
+
+    tracker.record(&response("wamid.1"));
+
+    let delivery = tracker.get("wamid.1").unwrap();
+    assert_eq!(delivery.state, DeliveryState::Delivered);
+    assert_eq!(delivery.sent_at, Some(100));
+    assert_eq!(tracker.pending().len(), 1);
+
+    // Read is final, a late failure does not replace it
+    tracker.handle_payload(&payload(vec![
+        status("wamid.1", "read", 102),
+        status("wamid.1", "failed", 103),
+    ]));
+    assert_eq!(tracker.state("wamid.1"), Some(DeliveryState::Read));
+}
+
+#[test]
+fn test_failure_keeps_errors() {
+    let tracker = DeliveryTracker::new();
+    tracker.record(&response("wamid.1"));
+
+    let mut failed = status("wamid.1", "failed", 100);
+    failed["errors"] = serde_json::json!([{
+        "code": 131026,
+        "title": "Message undeliverable",
+        "error_data": { "details": "Recipient is not a WhatsApp user" }
+    }]);
+    tracker.handle_payload(&payload(vec![status("wamid.1", "sent", 99), failed]));
+
+    let delivery = tracker.get("wamid.1").unwrap();
+    assert_eq!(delivery.state, DeliveryState::Failed);
+    assert_eq!(delivery.failed_at, Some(100));
+    assert_eq!(delivery.errors[0].code, 131026);
+    assert_eq!(tracker.in_state(DeliveryState::Failed).len(), 1);
+
+    // Non-status events are ignored
+    let welcome = WebhookEvent::RequestWelcome {
+        from: "628111222333".to_string(),
+        message_id: "wamid.2".to_string(),
+        timestamp: "100".to_string(),
+        profile_name: None,
+        context: None,
+    };
+    assert_eq!(tracker.handle_event(&welcome), None);
+    assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn test_capacity_drops_oldest() {
+    let tracker = DeliveryTracker::with_capacity(2);
+    tracker.track("wamid.1", None);
+    tracker.track("wamid.2", None);
+    tracker.track("wamid.3", None);
+
+    assert_eq!(tracker.len(), 2);
+    assert!(tracker.get("wamid.1").is_none());
+    assert_eq!(
+        tracker
+            .pending()
+            .into_iter()
+            .map(|delivery| delivery.message_id)
+            .collect::<Vec<_>>(),
+        vec!["wamid.2", "wamid.3"]
+    );
+
+    assert!(tracker.remove("wamid.2").is_some());
+    assert_eq!(tracker.len(), 1);
+}
+
+#[tokio::test]
+async fn test_subscribe_and_wait() {
+    let tracker = DeliveryTracker::new();
+    let mut updates = tracker.subscribe();
+    tracker.record(&response("wamid.1"));
+
+    let waiter = {
+        let tracker = tracker.clone();
+        tokio::spawn(async move {
+            tracker
+                .wait_for("wamid.1", DeliveryState::Delivered, Duration::from_secs(5))
+                .await
+        })
+    };
+    tokio::task::yield_now().await;
+
+    // Fed through a router, as in a webhook server
+    let router = WebhookRouter::new().middleware(tracker.clone());
+    router
+        .dispatch(&payload(vec![
+            status("wamid.1", "sent", 100),
+            status("wamid.1", "read", 102),
+        ]))
+        .await;
+
+    let delivery = waiter.await.unwrap().unwrap();
+    assert_eq!(delivery.state, DeliveryState::Read);
+
+    assert_eq!(
+        updates.recv().await.unwrap(),
+        DeliveryUpdate {
+            message_id: "wamid.1".to_string(),
+            previous: None,
+            state: DeliveryState::Accepted,
+        }
+    );
+    assert_eq!(updates.recv().await.unwrap().state, DeliveryState::Sent);
+    assert_eq!(updates.recv().await.unwrap().state, DeliveryState::Read);
+}
+
+#[tokio::test]
+async fn test_wait_times_out_and_returns_failures() {
+    let tracker = DeliveryTracker::new();
+    tracker.record(&response("wamid.1"));
+
+    let error = tracker
+        .wait_for("wamid.1", DeliveryState::Read, Duration::from_millis(20))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::DeliveryTimeout {
+            ref message_id,
+            state: Some(DeliveryState::Accepted),
+        } if message_id == "wamid.1"
+    ));
+
+    tracker.handle_payload(&payload(vec![status("wamid.1", "failed", 100)]));
+    let delivery = tracker
+        .wait_for("wamid.1", DeliveryState::Read, Duration::from_millis(20))
+        .await
+        .unwrap();
+    assert_eq!(delivery.state, DeliveryState::Failed);
+}
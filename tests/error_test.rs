@@ -0,0 +1,202 @@
+//! Tests for API error classification
+
+mod common;
+
+use common::*;
+use wacloudapi::error::{ErrorCategory, WhatsAppErrorCode};
+use wacloudapi::retry::RetryPolicy;
+use wacloudapi::Error;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn test_error_code_classification() {
+    let cases = [
+        (131026, ErrorCategory::Message, false),
+        (131047, ErrorCategory::Message, false),
+        (131051, ErrorCategory::Parameter, false),
+        (132001, ErrorCategory::Template, false),
+        (133010, ErrorCategory::Registration, false),
+        (130472, ErrorCategory::Message, false),
+        (130429, ErrorCategory::Throttling, true),
+        (131000, ErrorCategory::Server, true),
+        (250, ErrorCategory::Authorization, false),
+        (999999, ErrorCategory::Unknown, false),
+    ];
+
+    for (code, category, retryable) in cases {
+        let error_code = WhatsAppErrorCode::from_code(code);
+        assert_eq!(error_code.code(), code);
+        assert_eq!(error_code.category(), category, "code {}", code);
+        assert_eq!(error_code.is_retryable(), retryable, "code {}", code);
+    }
+
+    assert_eq!(
+        WhatsAppErrorCode::from_code(131047),
+        WhatsAppErrorCode::ReEngagementRequired
+    );
+    assert_eq!(
+        WhatsAppErrorCode::from_code(999999),
+        WhatsAppErrorCode::Unknown(999999)
+    );
+}
+
+#[test]
+fn test_retry_policy_follows_error_code_classification() {
+    let api_error = |code: i32| {
+        Error::Api(Box::new(
+            serde_json::from_value(serde_json::json!({ "message": "Failed", "code": code }))
+                .unwrap(),
+        ))
+    };
+
+    let policy = RetryPolicy::new();
+    for code in [1, 2, 131000, 131016, 131057, 133004] {
+        assert!(WhatsAppErrorCode::from_code(code).is_retryable());
+        assert!(
+            policy.should_retry(Some(400), &api_error(code), true),
+            "code {}",
+            code
+        );
+    }
+    assert!(!policy.should_retry(Some(400), &api_error(131026), true));
+
+    // A custom list replaces the classification
+    let policy = RetryPolicy::new().retryable_codes(vec![131026]);
+    assert!(policy.should_retry(Some(400), &api_error(131026), true));
+    assert!(!policy.should_retry(Some(400), &api_error(131000), true));
+}
+
+async fn send_with_error(body: serde_json::Value, status: u16) -> Error {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(status).set_body_json(body))
+        .mount(&mock_server)
+        .await;
+
+    client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap_err()
+}
+
+#[tokio::test]
+async fn test_api_error_keeps_details() {
+    let error = send_with_error(
+        serde_json::json!({
+            "error": {
+                "message": "(#131047) Re-engagement message",
+                "type": "OAuthException",
+                "code": 131047,
+                "error_subcode": 2494010,
+                "error_user_title": "Message not sent",
+                "error_user_msg": "More than 24 hours have passed",
+                "error_data": {
+                    "messaging_product": "whatsapp",
+                    "details": "Message failed to send because more than 24 hours have passed"
+                },
+                "fbtrace_id": "trace123"
+            }
+        }),
+        400,
+    )
+    .await;
+
+    let Error::Api(api_error) = &error else {
+        panic!("expected Error::Api, got {:?}", error);
+    };
+    assert_eq!(api_error.error_type.as_deref(), Some("OAuthException"));
+    assert_eq!(api_error.error_subcode, Some(2494010));
+    assert_eq!(
+        api_error.error_user_title.as_deref(),
+        Some("Message not sent")
+    );
+    assert_eq!(
+        api_error.error_user_msg.as_deref(),
+        Some("More than 24 hours have passed")
+    );
+    assert_eq!(api_error.fbtrace_id.as_deref(), Some("trace123"));
+    assert_eq!(
+        error.error_code(),
+        Some(WhatsAppErrorCode::ReEngagementRequired)
+    );
+}
+
+#[tokio::test]
+async fn test_throttling_codes_are_rate_limited() {
+    let error = send_with_error(
+        serde_json::json!({
+            "error": {
+                "message": "Spam rate limit hit",
+                "type": "OAuthException",
+                "code": 131048,
+                "fbtrace_id": "trace456"
+            }
+        }),
+        400,
+    )
+    .await;
+
+    assert!(matches!(error, Error::RateLimited { .. }));
+    assert_eq!(
+        error.error_code(),
+        Some(WhatsAppErrorCode::SpamRateLimitHit)
+    );
+    assert_eq!(
+        error.api_error().unwrap().fbtrace_id.as_deref(),
+        Some("trace456")
+    );
+}
+
+#[tokio::test]
+async fn test_expired_token_keeps_details() {
+    let error = send_with_error(
+        serde_json::json!({
+            "error": {
+                "message": "Error validating access token",
+                "type": "OAuthException",
+                "code": 190,
+                "error_subcode": 463,
+                "fbtrace_id": "trace789"
+            }
+        }),
+        401,
+    )
+    .await;
+
+    let Error::InvalidToken(api_error) = &error else {
+        panic!("expected Error::InvalidToken, got {:?}", error);
+    };
+    assert_eq!(api_error.error_subcode, Some(463));
+    assert_eq!(
+        error.error_code(),
+        Some(WhatsAppErrorCode::AccessTokenExpired)
+    );
+}
+
+#[tokio::test]
+async fn test_non_json_error_body() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+        .mount(&mock_server)
+        .await;
+
+    let error = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap_err();
+
+    let api_error = error.api_error().unwrap();
+    assert_eq!(api_error.code, 502);
+    assert_eq!(api_error.message, "Bad Gateway");
+    assert_eq!(error.error_code(), Some(WhatsAppErrorCode::Unknown(502)));
+}
@@ -0,0 +1,314 @@
+//! Tests for the media cache
+
+mod common;
+
+use bytes::Bytes;
+use common::*;
+use std::time::{Duration, SystemTime};
+use wacloudapi::media_cache::{
+    FileMediaStore, MediaCache, MediaStore, MediaStoreFuture, MemoryMediaStore,
+};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CONTENT: &[u8] = b"\x89PNG not really a PNG";
+
+async fn mount_upload(mock_server: &MockServer, expected: u64) {
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "uploaded_media_123"
+        })))
+        .expect(expected)
+        .mount(mock_server)
+        .await;
+}
+
+async fn mount_download(mock_server: &MockServer, expected: u64) {
+    let sha256 = sha256_hex(CONTENT);
+    Mock::given(method("GET"))
+        .and(path("/v21.0/media_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "url": format!("{}/lookaside/media_123", mock_server.uri()),
+            "mime_type": "image/png",
+            "sha256": sha256,
+            "file_size": CONTENT.len(),
+            "id": "media_123",
+            "messaging_product": "whatsapp"
+        })))
+        .expect(expected)
+        .mount(mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/lookaside/media_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(CONTENT))
+        .expect(expected)
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_repeat_uploads_reuse_media_id() {
+    let mock_server = MockServer::start().await;
+    let cache = MediaCache::new(MemoryMediaStore::new());
+    let client = create_test_client(&mock_server).with_media_cache(cache);
+    mount_upload(&mock_server, 1).await;
+
+    let media = client.media();
+    let first = media
+        .upload_bytes(CONTENT, "logo.png", "image/png")
+        .await
+        .unwrap();
+    let second = media
+        .upload_bytes(CONTENT, "other-name.png", "image/png")
+        .await
+        .unwrap();
+    assert_eq!(first.id, "uploaded_media_123");
+    assert_eq!(second.id, first.id);
+}
+
+#[tokio::test]
+async fn test_expired_media_ids_are_uploaded_again() {
+    let mock_server = MockServer::start().await;
+    let cache = MediaCache::new(MemoryMediaStore::new()).upload_ttl(Duration::ZERO);
+    let client = create_test_client(&mock_server).with_media_cache(cache);
+    mount_upload(&mock_server, 2).await;
+
+    for _ in 0..2 {
+        client
+            .media()
+            .upload_bytes(CONTENT, "logo.png", "image/png")
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_unbounded_upload_ttl_does_not_panic() {
+    let mock_server = MockServer::start().await;
+    let cache = MediaCache::new(MemoryMediaStore::new()).upload_ttl(Duration::MAX);
+    let client = create_test_client(&mock_server).with_media_cache(cache);
+    mount_upload(&mock_server, 1).await;
+
+    for _ in 0..2 {
+        client
+            .media()
+            .upload_bytes(CONTENT, "logo.png", "image/png")
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_file_store_survives_restart() {
+    let mock_server = MockServer::start().await;
+    mount_upload(&mock_server, 1).await;
+    let dir = temp_dir("media-cache-restart");
+    let file = dir.join("logo.png");
+    std::fs::write(&file, CONTENT).unwrap();
+
+    for _ in 0..2 {
+        // A new store and client each time, as after a restart
+        let cache = MediaCache::new(FileMediaStore::open(dir.join("cache")).unwrap());
+        let client = create_test_client(&mock_server).with_media_cache(cache);
+        let response = client.media().upload_file(&file).await.unwrap();
+        assert_eq!(response.id, "uploaded_media_123");
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_downloads_are_served_locally() {
+    let mock_server = MockServer::start().await;
+    let cache = MediaCache::new(MemoryMediaStore::new());
+    let client = create_test_client(&mock_server).with_media_cache(cache.clone());
+    mount_download(&mock_server, 1).await;
+
+    for _ in 0..3 {
+        let content = client.media().download("media_123").await.unwrap();
+        assert_eq!(content, CONTENT);
+    }
+    assert_eq!(
+        cache.content("media_123").await.unwrap(),
+        Some(Bytes::from_static(CONTENT))
+    );
+}
+
+#[tokio::test]
+async fn test_delete_forgets_cached_media() {
+    let mock_server = MockServer::start().await;
+    let cache = MediaCache::new(MemoryMediaStore::new());
+    let client = create_test_client(&mock_server).with_media_cache(cache.clone());
+    mount_download(&mock_server, 2).await;
+    Mock::given(method("DELETE"))
+        .and(path("/v21.0/media_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(success_response()))
+        .mount(&mock_server)
+        .await;
+
+    cache.record_upload("abc", "media_123").await.unwrap();
+    client.media().download("media_123").await.unwrap();
+    client.media().delete("media_123").await.unwrap();
+
+    assert_eq!(cache.media_id("abc").await.unwrap(), None);
+    assert_eq!(cache.content("media_123").await.unwrap(), None);
+    client.media().download("media_123").await.unwrap();
+}
+
+/// Store whose reads miss and whose writes fail, like a full disk
+struct FailingStore;
+
+fn disk_full<'a, T: Send + 'a>() -> MediaStoreFuture<'a, T> {
+    Box::pin(async { Err(std::io::Error::other("disk full").into()) })
+}
+
+impl MediaStore for FailingStore {
+    fn media_id<'a>(&'a self, _: &'a str) -> MediaStoreFuture<'a, Option<String>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn insert_media_id<'a>(
+        &'a self,
+        _: &'a str,
+        _: &'a str,
+        _: SystemTime,
+    ) -> MediaStoreFuture<'a, ()> {
+        disk_full()
+    }
+
+    fn content<'a>(&'a self, _: &'a str) -> MediaStoreFuture<'a, Option<Bytes>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    fn insert_content<'a>(&'a self, _: &'a str, _: Bytes) -> MediaStoreFuture<'a, ()> {
+        disk_full()
+    }
+
+    fn remove<'a>(&'a self, _: &'a str) -> MediaStoreFuture<'a, ()> {
+        disk_full()
+    }
+}
+
+#[tokio::test]
+async fn test_failing_store_does_not_fail_requests() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server).with_media_cache(MediaCache::new(FailingStore));
+    mount_upload(&mock_server, 1).await;
+    mount_download(&mock_server, 1).await;
+    Mock::given(method("DELETE"))
+        .and(path("/v21.0/media_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(success_response()))
+        .mount(&mock_server)
+        .await;
+
+    let media = client.media();
+    let response = media
+        .upload_bytes(CONTENT, "logo.png", "image/png")
+        .await
+        .unwrap();
+    assert_eq!(response.id, "uploaded_media_123");
+    assert_eq!(media.download("media_123").await.unwrap(), CONTENT);
+    assert!(media.delete("media_123").await.unwrap().success);
+}
+
+#[tokio::test]
+async fn test_memory_store_bounds_content() {
+    let store = MemoryMediaStore::with_content_capacity(10);
+    store
+        .insert_content("a", Bytes::from_static(b"1234"))
+        .await
+        .unwrap();
+    store
+        .insert_content("b", Bytes::from_static(b"5678"))
+        .await
+        .unwrap();
+
+    // "a" is used more recently than "b", so "b" is dropped
+    assert!(store.content("a").await.unwrap().is_some());
+    store
+        .insert_content("c", Bytes::from_static(b"90"))
+        .await
+        .unwrap();
+    store
+        .insert_content("d", Bytes::from_static(b"!!"))
+        .await
+        .unwrap();
+    assert_eq!(store.content("b").await.unwrap(), None);
+    assert!(store.content("a").await.unwrap().is_some());
+    assert_eq!(store.content_size(), 8);
+
+    // Content larger than the store is not held
+    store
+        .insert_content("e", Bytes::from_static(b"0123456789ab"))
+        .await
+        .unwrap();
+    assert_eq!(store.content("e").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_file_store_expiry_and_unsafe_keys() {
+    let dir = temp_dir("media-cache-expiry");
+    let store = FileMediaStore::open(&dir).unwrap();
+    let hour = Duration::from_secs(60 * 60);
+
+    store
+        .insert_media_id("abc/+=", "media_1", SystemTime::now() + hour)
+        .await
+        .unwrap();
+    store
+        .insert_media_id("def", "media_2", SystemTime::now() - hour)
+        .await
+        .unwrap();
+    assert_eq!(
+        store.media_id("abc/+=").await.unwrap().as_deref(),
+        Some("media_1")
+    );
+    assert_eq!(store.media_id("def").await.unwrap(), None);
+    assert_eq!(store.media_id("missing").await.unwrap(), None);
+
+    store.remove("media_1").await.unwrap();
+    assert_eq!(store.media_id("abc/+=").await.unwrap(), None);
+
+    // "a/" is escaped to the hex of its bytes, which "x612f" spells out as is
+    store
+        .insert_content("x612f", Bytes::from_static(b"safe"))
+        .await
+        .unwrap();
+    store
+        .insert_content("a/", Bytes::from_static(b"escaped"))
+        .await
+        .unwrap();
+    assert_eq!(store.content("x612f").await.unwrap().unwrap(), "safe");
+    assert_eq!(store.content("a/").await.unwrap().unwrap(), "escaped");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_file_store_concurrent_writes_of_one_key() {
+    let dir = temp_dir("media-cache-concurrent");
+    let store = FileMediaStore::open(&dir).unwrap();
+
+    let writers: Vec<_> = (0..16)
+        .map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                store
+                    .insert_content("media_1", Bytes::from_static(CONTENT))
+                    .await
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.await.unwrap().unwrap();
+    }
+
+    assert_eq!(
+        store.content("media_1").await.unwrap(),
+        Some(Bytes::from_static(CONTENT))
+    );
+    assert_eq!(std::fs::read_dir(dir.join("content")).unwrap().count(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -3,7 +3,12 @@
 mod common;
 
 use common::*;
-use wiremock::matchers::{method, path};
+use futures_util::StreamExt;
+use tokio::io::AsyncReadExt;
+use wacloudapi::error::{MediaMismatch, MediaUploadError};
+use wacloudapi::media::MediaType;
+use wacloudapi::Error;
+use wiremock::matchers::{body_string_contains, header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -91,3 +96,326 @@ async fn test_upload_base64() {
 
     assert_eq!(response.id, "base64_media_123");
 }
+
+#[tokio::test]
+async fn test_upload_file_is_streamed() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+        .and(body_string_contains("name=\"type\"\r\n\r\ntext/plain"))
+        .and(body_string_contains("filename=\"notes.txt\""))
+        .and(body_string_contains("line one\nline two"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file_media_123"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = temp_dir("upload");
+    let file = dir.join("notes.txt");
+    std::fs::write(&file, "line one\nline two").unwrap();
+
+    let response = client.media().upload_file(&file).await.unwrap();
+    assert_eq!(response.id, "file_media_123");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_upload_file_maps_audio_extensions() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    for mime_type in ["audio/amr", "audio/mp4", "audio/ogg"] {
+        Mock::given(method("POST"))
+            .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+            .and(body_string_contains(format!(
+                "name=\"type\"\r\n\r\n{}",
+                mime_type
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": mime_type
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let dir = temp_dir("upload-audio");
+    for (name, mime_type) in [
+        ("voice.amr", "audio/amr"),
+        ("song.M4A", "audio/mp4"),
+        ("note.opus", "audio/ogg"),
+    ] {
+        let file = dir.join(name);
+        std::fs::write(&file, "audio").unwrap();
+        let response = client.media().upload_file(&file).await.unwrap();
+        assert_eq!(response.id, mime_type, "{}", name);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_upload_reader() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+        .and(body_string_contains("Content-Type: audio/ogg; codecs=opus"))
+        .and(body_string_contains("OggS"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "reader_media_123"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Only the declared size is read
+    let reader: &'static [u8] = b"OggS and then some trailing bytes";
+    let response = client
+        .media()
+        .upload_reader(reader, 4, "voice.ogg", "audio/ogg; codecs=opus")
+        .await
+        .unwrap();
+    assert_eq!(response.id, "reader_media_123");
+
+    let requests = mock_server.received_requests().await.unwrap();
+    let body = String::from_utf8_lossy(&requests[0].body);
+    assert!(!body.contains("trailing"));
+}
+
+#[tokio::test]
+async fn test_upload_rejected_before_sending() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let error = client
+        .media()
+        .upload_bytes(b"PK", "archive.zip", "application/zip")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::MediaUpload(MediaUploadError::UnsupportedMimeType {
+            media_type: None,
+            ..
+        })
+    ));
+
+    let error = client
+        .media()
+        .upload_reader(
+            tokio::io::empty(),
+            6 * 1024 * 1024,
+            "photo.png",
+            "image/png",
+        )
+        .await
+        .unwrap_err();
+    match error {
+        Error::MediaUpload(MediaUploadError::TooLarge {
+            media_type,
+            size,
+            max_size,
+        }) => {
+            assert_eq!(media_type, MediaType::Image);
+            assert_eq!(size, 6 * 1024 * 1024);
+            assert_eq!(max_size, 5 * 1024 * 1024);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let error = client
+        .media()
+        .upload_base64("not base64!", "notes.txt", "text/plain")
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::MediaUpload(MediaUploadError::InvalidBase64(_))
+    ));
+}
+
+#[test]
+fn test_media_type_validation() {
+    assert_eq!(MediaType::from_mime("image/webp"), Some(MediaType::Image));
+    assert_eq!(MediaType::from_mime("VIDEO/MP4"), Some(MediaType::Video));
+    assert_eq!(MediaType::from_mime("application/zip"), None);
+
+    assert!(MediaType::Sticker
+        .validate("image/webp", 100 * 1024)
+        .is_ok());
+    assert_eq!(
+        MediaType::Sticker.validate("image/png", 1),
+        Err(MediaUploadError::UnsupportedMimeType {
+            mime_type: "image/png".to_string(),
+            media_type: Some(MediaType::Sticker),
+        })
+    );
+    assert_eq!(
+        MediaType::Sticker
+            .validate("image/webp", 600 * 1024)
+            .unwrap_err()
+            .to_string(),
+        "sticker of 614400 bytes exceeds the 512000 byte limit"
+    );
+}
+
+const CONTENT: &[u8] = b"\xFF\xD8\xFF\xE0 not really a JPEG";
+
+/// Mount the media URL lookup and the authenticated CDN download
+async fn mount_download(mock_server: &MockServer, sha256: &str, file_size: usize) {
+    Mock::given(method("GET"))
+        .and(path("/v21.0/media_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "url": format!("{}/lookaside/media_123", mock_server.uri()),
+            "mime_type": "image/jpeg",
+            "sha256": sha256,
+            "file_size": file_size,
+            "id": "media_123",
+            "messaging_product": "whatsapp"
+        })))
+        .mount(mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/lookaside/media_123"))
+        .and(header("authorization", format!("Bearer {}", TEST_TOKEN)))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(CONTENT))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_download_is_authenticated_and_verified() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_download(&mock_server, &sha256_hex(CONTENT), CONTENT.len()).await;
+
+    let content = client.media().download("media_123").await.unwrap();
+    assert_eq!(content, CONTENT);
+
+    // As a stream of chunks
+    let mut download = client.media().download_stream("media_123").await.unwrap();
+    assert_eq!(download.info().mime_type, "image/jpeg");
+    let mut streamed = Vec::new();
+    while let Some(chunk) = download.next().await {
+        streamed.extend_from_slice(&chunk.unwrap());
+    }
+    assert_eq!(streamed, CONTENT);
+
+    // As an AsyncRead
+    let mut download = client.media().download_stream("media_123").await.unwrap();
+    let mut read = Vec::new();
+    download.read_to_end(&mut read).await.unwrap();
+    assert_eq!(read, CONTENT);
+}
+
+#[tokio::test]
+async fn test_download_rejects_hash_mismatch() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    let wrong = sha256_hex(b"something else");
+    mount_download(&mock_server, &wrong, CONTENT.len()).await;
+
+    let error = client.media().download("media_123").await.unwrap_err();
+    match error {
+        Error::MediaVerification {
+            media_id,
+            mismatch: MediaMismatch::Sha256 { expected, actual },
+        } => {
+            assert_eq!(media_id, "media_123");
+            assert_eq!(expected, wrong);
+            assert_eq!(actual, sha256_hex(CONTENT));
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // Readers get the typed error inside an InvalidData IO error
+    let mut download = client.media().download_stream("media_123").await.unwrap();
+    let error = download.read_to_end(&mut Vec::new()).await.unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let inner = error.into_inner().unwrap().downcast::<Error>().unwrap();
+    assert!(matches!(*inner, Error::MediaVerification { .. }));
+}
+
+#[tokio::test]
+async fn test_download_rejects_size_mismatch() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_download(&mock_server, &sha256_hex(CONTENT), 4).await;
+
+    let error = client.media().download("media_123").await.unwrap_err();
+    assert!(matches!(
+        error,
+        Error::MediaVerification {
+            mismatch: MediaMismatch::Size { expected: 4, .. },
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_download_does_not_trust_reported_size_for_allocation() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_download(&mock_server, &sha256_hex(CONTENT), i64::MAX as usize).await;
+
+    let error = client.media().download("media_123").await.unwrap_err();
+    assert!(matches!(
+        error,
+        Error::MediaVerification {
+            mismatch: MediaMismatch::Size { .. },
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_download_to_file() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_download(&mock_server, &sha256_hex(CONTENT), CONTENT.len()).await;
+
+    let dir = temp_dir("media");
+    let target = dir.join("image.jpg");
+
+    let info = client
+        .media()
+        .download_to_file("media_123", &target)
+        .await
+        .unwrap();
+    assert_eq!(info.id, "media_123");
+    assert_eq!(std::fs::read(&target).unwrap(), CONTENT);
+    assert!(!dir.join("image.jpg.part").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_download_to_file_removes_unverified_content() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_download(&mock_server, &sha256_hex(b"something else"), CONTENT.len()).await;
+
+    let dir = temp_dir("media-bad");
+    let target = dir.join("image.jpg");
+
+    let result = client.media().download_to_file("media_123", &target).await;
+    assert!(matches!(result, Err(Error::MediaVerification { .. })));
+    assert!(!target.exists());
+    assert!(!dir.join("image.jpg.part").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
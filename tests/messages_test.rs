@@ -4,8 +4,9 @@ mod common;
 
 use common::*;
 use wacloudapi::messages::{
-    Button, Contact, ContactName, ContactPhone, ListRow, ListSection, TemplateComponent,
-    TemplateParameter,
+    Button, Contact, ContactName, ContactPhone, Interactive, InteractiveAction, InteractiveBody,
+    InteractiveHeader, ListRow, ListSection, MediaContent, OutgoingMessage, SendOptions,
+    TemplateComponent, TemplateParameter,
 };
 use wiremock::matchers::{body_json, header, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -597,3 +598,105 @@ async fn test_mark_as_read() {
 
     assert!(response.success);
 }
+
+#[tokio::test]
+async fn test_send_outgoing_message_with_options() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .and(body_json(serde_json::json!({
+            "messaging_product": "whatsapp",
+            "recipient_type": "individual",
+            "to": "628123456789",
+            "context": {
+                "message_id": "wamid.original"
+            },
+            "biz_opaque_callback_data": "order-42",
+            "type": "document",
+            "document": {
+                "link": "https://example.com/invoice.pdf",
+                "caption": "Your invoice",
+                "filename": "invoice.pdf"
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.send123")))
+        .mount(&mock_server)
+        .await;
+
+    let message = OutgoingMessage::document(
+        MediaContent::from_link("https://example.com/invoice.pdf")
+            .with_caption("Your invoice")
+            .with_filename("invoice.pdf"),
+    );
+    let options = SendOptions::new()
+        .reply_to("wamid.original")
+        .biz_opaque_callback_data("order-42");
+
+    let response = client
+        .messages()
+        .send_with_options("628123456789", &message, options)
+        .await
+        .unwrap();
+
+    assert_eq!(response.messages[0].id, "wamid.send123");
+}
+
+#[test]
+fn test_outgoing_message_serde_round_trip() {
+    let messages = vec![
+        OutgoingMessage::text("Hello"),
+        OutgoingMessage::text_with_preview("https://example.com"),
+        OutgoingMessage::image(MediaContent::from_id("media_1").with_caption("Photo")),
+        OutgoingMessage::video(MediaContent::from_link("https://example.com/v.mp4")),
+        OutgoingMessage::audio(MediaContent::from_id("media_2")),
+        OutgoingMessage::document(MediaContent::from_id("media_3").with_filename("a.pdf")),
+        OutgoingMessage::sticker(MediaContent::from_id("media_4")),
+        OutgoingMessage::location(-6.2, 106.8),
+        OutgoingMessage::Contacts {
+            contacts: vec![Contact {
+                name: ContactName {
+                    formatted_name: "John Doe".to_string(),
+                    first_name: Some("John".to_string()),
+                    last_name: None,
+                    middle_name: None,
+                    suffix: None,
+                    prefix: None,
+                },
+                phones: None,
+                emails: None,
+                urls: None,
+                addresses: None,
+                org: None,
+                birthday: None,
+            }],
+        },
+        OutgoingMessage::template("hello_world", "en_US", None),
+        OutgoingMessage::interactive(Interactive {
+            interactive_type: "button".to_string(),
+            header: Some(InteractiveHeader::text("Header")),
+            body: InteractiveBody {
+                text: "Choose".to_string(),
+            },
+            footer: None,
+            action: InteractiveAction {
+                button: None,
+                buttons: Some(vec![Button::reply("yes", "Yes")]),
+                sections: None,
+                catalog_id: None,
+                product_retailer_id: None,
+            },
+        }),
+        OutgoingMessage::reaction("wamid.abc", "👍"),
+    ];
+
+    for message in messages {
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["type"], message.message_type());
+        assert!(json.get(message.message_type()).is_some());
+
+        let decoded: OutgoingMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, message);
+    }
+}
@@ -0,0 +1,259 @@
+//! Tests for cursor-based pagination
+
+mod common;
+
+use common::*;
+use futures_util::{StreamExt, TryStreamExt};
+use wacloudapi::pagination::{PageParams, Paginated};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn template(name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "status": "APPROVED",
+        "category": "UTILITY",
+        "language": "en_US",
+        "components": []
+    })
+}
+
+fn blocked_page(ids: &[&str], paging: serde_json::Value) -> serde_json::Value {
+    let data: Vec<_> = ids
+        .iter()
+        .map(|id| serde_json::json!({ "wa_id": id }))
+        .collect();
+    serde_json::json!({ "data": data, "paging": paging })
+}
+
+#[tokio::test]
+async fn test_items_follow_after_cursor() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    let templates_path = format!("/v21.0/{}/message_templates", TEST_WABA_ID);
+
+    Mock::given(method("GET"))
+        .and(path(&templates_path))
+        .and(query_param("limit", "2"))
+        .and(query_param_is_missing("after"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [template("first"), template("second")],
+            "paging": {
+                "cursors": { "before": "c0", "after": "c1" },
+                "next": "https://graph.facebook.com/v21.0/next-page"
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(&templates_path))
+        .and(query_param("limit", "2"))
+        .and(query_param("after", "c1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [template("third")],
+            "paging": {
+                "cursors": { "before": "c1", "after": "c2" }
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let names: Vec<String> = client
+        .templates()
+        .list_paginated(TEST_WABA_ID, PageParams::new().limit(2))
+        .items()
+        .map_ok(|template| template.name)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(names, vec!["first", "second", "third"]);
+}
+
+#[tokio::test]
+async fn test_pages_start_after_cursor() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("GET"))
+        .and(path(format!("/v21.0/{}/block", TEST_PHONE_ID)))
+        .and(query_param("after", "start"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(blocked_page(
+            &["628111"],
+            serde_json::json!({ "cursors": { "before": "start", "after": "end" } }),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let pages: Vec<_> = client
+        .block()
+        .get_blocked_users_paginated(PageParams::new().after("start"))
+        .pages()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].data[0].wa_id, "628111");
+    assert_eq!(
+        pages[0].paging().unwrap().cursors.as_ref().unwrap().after,
+        Some("end".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_pages_backwards_with_before_cursor() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    let block_path = format!("/v21.0/{}/block", TEST_PHONE_ID);
+
+    Mock::given(method("GET"))
+        .and(path(&block_path))
+        .and(query_param("before", "c2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(blocked_page(
+            &["628222"],
+            serde_json::json!({
+                "cursors": { "before": "c1", "after": "c2" },
+                "previous": "https://graph.facebook.com/previous",
+                "next": "https://graph.facebook.com/next"
+            }),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(&block_path))
+        .and(query_param("before", "c1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(blocked_page(
+            &["628111"],
+            serde_json::json!({ "cursors": { "before": "c0", "after": "c1" } }),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let users: Vec<String> = client
+        .block()
+        .get_blocked_users_paginated(PageParams::new().before("c2"))
+        .items()
+        .map_ok(|user| user.wa_id)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(users, vec!["628222", "628111"]);
+}
+
+#[tokio::test]
+async fn test_next_link_without_cursors() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    let qr_path = format!("/v21.0/{}/message_qrdls", TEST_PHONE_ID);
+
+    Mock::given(method("GET"))
+        .and(path(&qr_path))
+        .and(query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "code": "QR1", "prefilled_message": "Hi", "deep_link_url": "https://wa.me/qr/QR1" }],
+            "paging": { "next": format!("{}{}?page=2", mock_server.uri(), qr_path) }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(&qr_path))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "code": "QR2", "prefilled_message": "Hello", "deep_link_url": "https://wa.me/qr/QR2" }]
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let codes: Vec<String> = client
+        .qr_codes()
+        .list_paginated(PageParams::new())
+        .items()
+        .map_ok(|qr| qr.code)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(codes, vec!["QR1", "QR2"]);
+}
+
+#[tokio::test]
+async fn test_error_ends_stream() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    let flows_path = format!("/v21.0/{}/flows", TEST_WABA_ID);
+
+    Mock::given(method("GET"))
+        .and(path(&flows_path))
+        .and(query_param_is_missing("after"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "flow_1", "name": "Signup", "status": "DRAFT" }],
+            "paging": {
+                "cursors": { "after": "c1" },
+                "next": "https://graph.facebook.com/next"
+            }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path(&flows_path))
+        .and(query_param("after", "c1"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "message": "Invalid cursor", "type": "OAuthException", "code": 100 }
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let results: Vec<_> = client
+        .flows()
+        .list_flows_paginated(TEST_WABA_ID, PageParams::new())
+        .items()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].as_ref().unwrap().id, "flow_1");
+    assert!(results[1].is_err());
+}
+
+#[tokio::test]
+async fn test_first_page_only() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("GET"))
+        .and(path(format!("/v21.0/{}/system_users", TEST_WABA_ID)))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": [{ "id": "user_1", "name": "Bot" }],
+            "paging": {
+                "cursors": { "after": "c1" },
+                "next": "https://graph.facebook.com/next"
+            }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let page = client
+        .waba(TEST_WABA_ID)
+        .get_system_users_paginated(PageParams::new().limit(1))
+        .first_page()
+        .await
+        .unwrap();
+
+    assert_eq!(page.data.len(), 1);
+    assert!(page.paging.unwrap().next.is_some());
+}
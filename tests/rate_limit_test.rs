@@ -0,0 +1,111 @@
+//! Tests for the client-side rate limiter
+
+mod common;
+
+use common::*;
+use std::time::{Duration, Instant};
+use wacloudapi::rate_limit::{RateLimiter, MIN_MESSAGES_PER_SECOND};
+use wacloudapi::types::Throughput;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn test_limiter_from_throughput() {
+    let high = RateLimiter::for_throughput(&Throughput {
+        level: "HIGH".to_string(),
+    });
+    let standard = RateLimiter::for_throughput_level("STANDARD");
+    let unknown = RateLimiter::for_throughput_level("NOT_APPLICABLE");
+
+    assert_eq!(high.messages_per_second(), 1000.0);
+    assert_eq!(standard.messages_per_second(), 80.0);
+    assert_eq!(unknown.messages_per_second(), 80.0);
+}
+
+#[test]
+fn test_global_budget() {
+    let limiter = RateLimiter::new(2.0);
+
+    assert!(limiter.try_acquire("628111").is_ok());
+    assert!(limiter.try_acquire("628222").is_ok());
+
+    let wait = limiter.try_acquire("628333").unwrap_err();
+    assert!(wait > Duration::ZERO);
+    assert!(wait <= Duration::from_millis(500));
+}
+
+#[test]
+fn test_degenerate_rates_do_not_panic() {
+    for rate in [0.0, -5.0, f64::MIN_POSITIVE, f64::NAN] {
+        let limiter = RateLimiter::new(rate);
+        assert_eq!(limiter.messages_per_second(), MIN_MESSAGES_PER_SECOND);
+
+        assert!(limiter.try_acquire("628111").is_ok());
+        let wait = limiter.try_acquire("628222").unwrap_err();
+        assert_eq!(wait, Duration::from_secs(60));
+    }
+
+    // A pair interval too long to wait out is capped as well
+    let limiter = RateLimiter::new(100.0).pair_limit(1, Duration::MAX);
+    assert!(limiter.try_acquire("628111").is_ok());
+    assert_eq!(
+        limiter.try_acquire("628111").unwrap_err(),
+        Duration::from_secs(60)
+    );
+}
+
+#[test]
+fn test_pair_limit() {
+    let limiter = RateLimiter::new(100.0).pair_limit(2, Duration::from_secs(6));
+
+    assert!(limiter.try_acquire("628111").is_ok());
+    assert!(limiter.try_acquire("628111").is_ok());
+
+    let wait = limiter.try_acquire("628111").unwrap_err();
+    assert!(wait > Duration::from_secs(5));
+
+    // Other recipients are not affected
+    assert!(limiter.try_acquire("628222").is_ok());
+}
+
+#[tokio::test]
+async fn test_sends_are_queued_not_failed() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server)
+        .with_rate_limiter(RateLimiter::new(100.0).pair_limit(1, Duration::from_millis(150)));
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.rl")))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        client
+            .messages()
+            .send_text("628123456789", "Hello")
+            .await
+            .unwrap();
+    }
+
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
+
+#[tokio::test]
+async fn test_limiter_shared_between_clones() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server).with_rate_limiter(RateLimiter::new(1.0));
+    let clone = client.clone();
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.rl")))
+        .mount(&mock_server)
+        .await;
+
+    client.messages().send_text("628111", "Hi").await.unwrap();
+
+    assert!(clone.rate_limiter().unwrap().try_acquire("628222").is_err());
+}
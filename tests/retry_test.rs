@@ -0,0 +1,239 @@
+//! Tests for request retries
+
+mod common;
+
+use common::*;
+use std::time::Duration;
+use wacloudapi::retry::RetryPolicy;
+use wacloudapi::{Client, Error};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .max_attempts(3)
+        .base_delay(Duration::ZERO)
+        .jitter(false)
+}
+
+fn retrying_client(mock_server: &MockServer, policy: RetryPolicy) -> Client {
+    create_test_client(mock_server).with_retry_policy(policy)
+}
+
+fn phone_number_response() -> serde_json::Value {
+    serde_json::json!({
+        "verified_name": "Test Business",
+        "display_phone_number": "+62 812 3456 789",
+        "id": "phone_123",
+        "quality_rating": "GREEN"
+    })
+}
+
+fn server_error() -> ResponseTemplate {
+    ResponseTemplate::new(500).set_body_json(serde_json::json!({
+        "error": {
+            "message": "An unknown error occurred",
+            "type": "OAuthException",
+            "code": 1,
+            "fbtrace_id": "trace_500"
+        }
+    }))
+}
+
+fn rate_limited() -> ResponseTemplate {
+    ResponseTemplate::new(429).set_body_json(serde_json::json!({
+        "error": {
+            "message": "Rate limit hit",
+            "type": "OAuthException",
+            "code": 130429,
+            "fbtrace_id": "trace_429"
+        }
+    }))
+}
+
+#[test]
+fn test_retry_policy_backoff() {
+    let policy = RetryPolicy::new()
+        .base_delay(Duration::from_millis(100))
+        .max_delay(Duration::from_millis(350))
+        .jitter(false);
+
+    assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+    assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+    assert_eq!(policy.delay_for(3), Duration::from_millis(350));
+    assert_eq!(policy.delay_for(40), Duration::from_millis(350));
+}
+
+#[test]
+fn test_retry_policy_jitter_bounds() {
+    let policy = RetryPolicy::new().base_delay(Duration::from_millis(100));
+
+    for _ in 0..50 {
+        let delay = policy.delay_for(1);
+        assert!(delay >= Duration::from_millis(50));
+        assert!(delay <= Duration::from_millis(100));
+    }
+}
+
+#[tokio::test]
+async fn test_no_retry_by_default() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("GET"))
+        .and(path("/v21.0/phone_123"))
+        .respond_with(server_error())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    assert!(client.phone_numbers().get("phone_123").await.is_err());
+}
+
+#[tokio::test]
+async fn test_get_retried_on_server_error() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/v21.0/phone_123"))
+        .respond_with(server_error())
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/v21.0/phone_123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(phone_number_response()))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let phone = client.phone_numbers().get("phone_123").await.unwrap();
+
+    assert_eq!(phone.id, "phone_123");
+}
+
+#[tokio::test]
+async fn test_retry_gives_up_after_max_attempts() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/v21.0/phone_123"))
+        .respond_with(server_error())
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let result = client.phone_numbers().get("phone_123").await;
+
+    assert!(matches!(result, Err(Error::Api(e)) if e.code == 1));
+}
+
+#[tokio::test]
+async fn test_post_not_retried_on_server_error() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy());
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(server_error())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let result = client.messages().send_text("628123456789", "Hello").await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_post_retried_when_opted_in() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy().retry_non_idempotent(true));
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(server_error())
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.retry")))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+
+    assert_eq!(response.messages[0].id, "wamid.retry");
+}
+
+#[tokio::test]
+async fn test_post_retried_when_rate_limited() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy());
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(rate_limited())
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.after")))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+
+    assert_eq!(response.messages[0].id, "wamid.after");
+}
+
+#[tokio::test]
+async fn test_multipart_upload_retried() {
+    let mock_server = MockServer::start().await;
+    let client = retrying_client(&mock_server, fast_policy());
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+        .respond_with(rate_limited())
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/media", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "media_after_retry"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = client
+        .media()
+        .upload_bytes(&[0xFF, 0xD8, 0xFF, 0xE0], "test.jpg", "image/jpeg")
+        .await
+        .unwrap();
+
+    assert_eq!(response.id, "media_after_retry");
+}
@@ -3,8 +3,13 @@
 mod common;
 
 use common::*;
-use wacloudapi::templates::{CreateTemplate, TemplateCategory, TemplateStatus};
-use wiremock::matchers::{method, path, query_param};
+use wacloudapi::error::TemplateViolation;
+use wacloudapi::templates::{
+    ButtonType, ComponentKind, CreateTemplate, HeaderFormat, ParameterFormat, TemplateButton,
+    TemplateCategory, TemplateComponentDef, TemplateStatus, TextExamples,
+};
+use wacloudapi::Error;
+use wiremock::matchers::{body_partial_json, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -142,7 +147,7 @@ async fn test_create_template() {
         .await;
 
     let template = CreateTemplate::new("my_new_template", TemplateCategory::Utility, "en_US")
-        .with_body("Hello {{1}}!");
+        .with_body_text("Hello {{1}}!", ["John"]);
 
     let response = client
         .templates()
@@ -174,3 +179,343 @@ async fn test_delete_template() {
 
     assert!(response.success);
 }
+
+#[tokio::test]
+async fn test_create_named_template_with_buttons() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/message_templates", TEST_WABA_ID)))
+        .and(body_partial_json(serde_json::json!({
+            "parameter_format": "NAMED",
+            "components": [
+                {
+                    "type": "HEADER",
+                    "format": "TEXT",
+                    "text": "Order {{order_id}}",
+                    "example": {
+                        "header_text_named_params": [
+                            { "param_name": "order_id", "example": "A-1" }
+                        ]
+                    }
+                },
+                {
+                    "type": "BODY",
+                    "text": "Hi {{first_name}}, your order ships {{day}}.",
+                    "example": {
+                        "body_text_named_params": [
+                            { "param_name": "first_name", "example": "Jane" },
+                            { "param_name": "day", "example": "today" }
+                        ]
+                    }
+                },
+                {
+                    "type": "BUTTONS",
+                    "buttons": [
+                        {
+                            "type": "URL",
+                            "text": "Track",
+                            "url": "https://example.com/track/{{1}}",
+                            "example": ["A-1"]
+                        },
+                        { "type": "QUICK_REPLY", "text": "Thanks" }
+                    ]
+                }
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "new_tpl_456",
+            "status": "PENDING",
+            "category": "UTILITY"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let template = CreateTemplate::new("order_update", TemplateCategory::Utility, "en_US")
+        .with_parameter_format(ParameterFormat::Named)
+        .with_text_header(
+            "Order {{order_id}}",
+            TextExamples::named([("order_id", "A-1")]),
+        )
+        .with_body_text(
+            "Hi {{first_name}}, your order ships {{day}}.",
+            TextExamples::named([("first_name", "Jane"), ("day", "today")]),
+        )
+        .with_button(TemplateButton::dynamic_url(
+            "Track",
+            "https://example.com/track/{{1}}",
+            "A-1",
+        ))
+        .with_button(TemplateButton::quick_reply("Thanks"));
+    assert!(template.validate().is_ok());
+
+    let response = client
+        .templates()
+        .create(TEST_WABA_ID, &template)
+        .await
+        .unwrap();
+    assert_eq!(response.id, "new_tpl_456");
+}
+
+#[test]
+fn test_validate_reports_every_violation() {
+    let template = CreateTemplate::new("bad_template", TemplateCategory::Marketing, "en_US")
+        .with_text_header("Hello {{1}} and {{2}}", ["a", "b"])
+        .with_body_text("No placeholders here", ["unused"])
+        .with_footer("Reply {{1}} to stop")
+        .with_buttons(vec![
+            TemplateButton::url("A", "https://example.com/a"),
+            TemplateButton::url("B", "https://example.com/b"),
+            TemplateButton::url("C", "https://example.com/c"),
+            TemplateButton::phone_number("Call", "+628111"),
+            TemplateButton::phone_number("Call again", "+628222"),
+        ]);
+
+    let violations = template.validate().unwrap_err();
+    assert_eq!(
+        violations,
+        vec![
+            TemplateViolation::TooManyPlaceholders {
+                component: ComponentKind::Header,
+                count: 2,
+                max: 1,
+            },
+            TemplateViolation::ExampleCountMismatch {
+                component: ComponentKind::Body,
+                placeholders: 0,
+                examples: 1,
+            },
+            TemplateViolation::TooManyPlaceholders {
+                component: ComponentKind::Footer,
+                count: 1,
+                max: 0,
+            },
+            TemplateViolation::TooManyButtons {
+                button_type: Some(ButtonType::Url),
+                count: 3,
+                max: 2,
+            },
+            TemplateViolation::TooManyButtons {
+                button_type: Some(ButtonType::PhoneNumber),
+                count: 2,
+                max: 1,
+            },
+        ]
+    );
+
+    let too_long = CreateTemplate::new("long", TemplateCategory::Utility, "en_US")
+        .with_header(HeaderFormat::Text, Some("h".repeat(61)))
+        .with_body("b".repeat(1025))
+        .with_buttons(
+            (0..11)
+                .map(|i| TemplateButton::quick_reply(i.to_string()))
+                .collect(),
+        );
+    assert_eq!(
+        too_long.validate().unwrap_err(),
+        vec![
+            TemplateViolation::TextTooLong {
+                component: ComponentKind::Header,
+                length: 61,
+                max: 60,
+            },
+            TemplateViolation::TextTooLong {
+                component: ComponentKind::Body,
+                length: 1025,
+                max: 1024,
+            },
+            TemplateViolation::TooManyButtons {
+                button_type: None,
+                count: 11,
+                max: 10,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_validate_placeholders() {
+    // Placeholders without examples, numbered with a gap
+    let positional = CreateTemplate::new("gaps", TemplateCategory::Utility, "en_US")
+        .with_body("Hi {{1}}, see {{3}} and {{first_name}}");
+    assert_eq!(
+        positional.validate().unwrap_err(),
+        vec![
+            TemplateViolation::ParameterFormatMismatch {
+                component: ComponentKind::Body,
+                placeholder: "first_name".to_string(),
+                format: ParameterFormat::Positional,
+            },
+            TemplateViolation::PlaceholderSequence {
+                component: ComponentKind::Body,
+            },
+            TemplateViolation::ExampleCountMismatch {
+                component: ComponentKind::Body,
+                placeholders: 2,
+                examples: 0,
+            },
+        ]
+    );
+
+    let named = CreateTemplate::new("named", TemplateCategory::Utility, "en_US")
+        .with_parameter_format(ParameterFormat::Named)
+        .with_body_text(
+            "Hi {{first_name}}, {{1}} {{Bad Name}}",
+            TextExamples::named([("last_name", "Doe")]),
+        );
+    assert_eq!(
+        named.validate().unwrap_err(),
+        vec![
+            TemplateViolation::ParameterFormatMismatch {
+                component: ComponentKind::Body,
+                placeholder: "1".to_string(),
+                format: ParameterFormat::Named,
+            },
+            TemplateViolation::InvalidPlaceholder {
+                component: ComponentKind::Body,
+                placeholder: "Bad Name".to_string(),
+            },
+            TemplateViolation::MissingExample {
+                component: ComponentKind::Body,
+                name: "first_name".to_string(),
+            },
+            TemplateViolation::UnusedExample {
+                component: ComponentKind::Body,
+                name: "last_name".to_string(),
+            },
+        ]
+    );
+
+    // Media headers need a sample, and every template a body
+    let media = CreateTemplate::new("media", TemplateCategory::Marketing, "en_US")
+        .with_header(HeaderFormat::Image, None);
+    assert_eq!(
+        media.validate().unwrap_err(),
+        vec![
+            TemplateViolation::MissingHeaderSample {
+                format: HeaderFormat::Image,
+            },
+            TemplateViolation::MissingBody,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_create_rejects_invalid_template() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/message_templates", TEST_WABA_ID)))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let template = CreateTemplate::new("no_example", TemplateCategory::Utility, "en_US")
+        .with_body("Hello {{1}}!")
+        .with_footer("f".repeat(61));
+
+    let error = client
+        .templates()
+        .create(TEST_WABA_ID, &template)
+        .await
+        .unwrap_err();
+    let Error::InvalidTemplate(violations) = &error else {
+        panic!("unexpected error: {error:?}");
+    };
+    assert_eq!(violations.len(), 2);
+    assert_eq!(
+        error.to_string(),
+        "Invalid template: body has 1 placeholders but 0 examples; \
+         footer text is 61 characters long, over the 60 character limit"
+    );
+}
+
+#[tokio::test]
+async fn test_create_passes_other_components_and_buttons_through() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/message_templates", TEST_WABA_ID)))
+        .and(body_partial_json(serde_json::json!({
+            "components": [
+                { "type": "BODY" },
+                { "type": "CAROUSEL" },
+                { "type": "BUTTONS", "buttons": [{ "type": "CATALOG" }, { "type": "SPM" }] }
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "new_tpl_789",
+            "status": "PENDING",
+            "category": "MARKETING"
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let mut template = CreateTemplate::new("spring_catalog", TemplateCategory::Marketing, "en_US")
+        .with_body("Browse our spring collection!");
+    let mut carousel = template.components[0].clone();
+    carousel.component_type = ComponentKind::from("CAROUSEL");
+    carousel.text = None;
+    template.components.push(carousel);
+    let mut catalog = TemplateButton::quick_reply("View catalog");
+    catalog.button_type = ButtonType::from("catalog");
+    let mut single_product = TemplateButton::quick_reply("View");
+    single_product.button_type = ButtonType::from("SPM");
+    let template = template.with_button(catalog).with_button(single_product);
+
+    let buttons = template.components[2].buttons.as_ref().unwrap();
+    assert_eq!(buttons[0].button_type, ButtonType::Catalog);
+    assert_eq!(buttons[1].button_type, ButtonType::Other("SPM".to_string()));
+    assert_eq!(
+        template.components[1].component_type,
+        ComponentKind::Other("CAROUSEL".to_string())
+    );
+    let response = client
+        .templates()
+        .create(TEST_WABA_ID, &template)
+        .await
+        .unwrap();
+    assert_eq!(response.id, "new_tpl_789");
+}
+
+#[test]
+fn test_component_types_keep_unknown_values() {
+    let component: TemplateComponentDef = serde_json::from_value(serde_json::json!({
+        "type": "header",
+        "format": "GIF",
+        "buttons": [{ "type": "url", "text": "Shop" }, { "type": "URLL", "text": "Typo" }]
+    }))
+    .unwrap();
+    assert_eq!(component.component_type, ComponentKind::Header);
+    assert_eq!(
+        component.format,
+        Some(HeaderFormat::Other("GIF".to_string()))
+    );
+    let buttons = component.buttons.as_ref().unwrap();
+    assert_eq!(buttons[0].button_type, ButtonType::Url);
+    assert_eq!(
+        buttons[1].button_type,
+        ButtonType::Other("URLL".to_string())
+    );
+
+    let json = serde_json::to_value(&component).unwrap();
+    assert_eq!(json["type"], "HEADER");
+    assert_eq!(json["format"], "GIF");
+    assert_eq!(json["buttons"][0]["type"], "URL");
+    assert_eq!(json["buttons"][1]["type"], "URLL");
+
+    assert_eq!(
+        TemplateCategory::from("marketing"),
+        TemplateCategory::Marketing
+    );
+    assert_eq!(
+        String::from(TemplateCategory::Other("NEW_CATEGORY".to_string())),
+        "NEW_CATEGORY"
+    );
+}
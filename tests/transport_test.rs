@@ -0,0 +1,145 @@
+//! Tests for pluggable HTTP transports
+
+use std::sync::{Arc, Mutex};
+use wacloudapi::transport::{Body, HttpRequest, HttpResponse, Transport, TransportFuture};
+use wacloudapi::Client;
+
+/// Request captured by the fake transport
+#[derive(Debug, Clone)]
+struct Recorded {
+    method: String,
+    url: String,
+    authorization: Option<String>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+/// In-memory transport that records requests and replies with a canned response
+#[derive(Clone)]
+struct FakeTransport {
+    status: u16,
+    response: &'static str,
+    requests: Arc<Mutex<Vec<Recorded>>>,
+}
+
+impl FakeTransport {
+    fn new(status: u16, response: &'static str) -> Self {
+        Self {
+            status,
+            response,
+            requests: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn requests(&self) -> Vec<Recorded> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for FakeTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let header = |name: &str| {
+                request
+                    .headers
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(String::from)
+            };
+            let recorded = Recorded {
+                method: request.method.to_string(),
+                url: request.url.clone(),
+                authorization: header("authorization"),
+                content_type: header("content-type"),
+                body: request.body.into_bytes().await?.to_vec(),
+            };
+            self.requests.lock().unwrap().push(recorded);
+
+            Ok(HttpResponse::new(
+                reqwest::StatusCode::from_u16(self.status).unwrap(),
+                Body::from(self.response),
+            ))
+        })
+    }
+}
+
+fn client_with(transport: FakeTransport) -> Client {
+    Client::builder("fake_token", "phone_123")
+        .transport(transport)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_custom_transport_receives_requests() {
+    let transport = FakeTransport::new(
+        200,
+        r#"{"messaging_product":"whatsapp","contacts":[],"messages":[{"id":"wamid.fake"}]}"#,
+    );
+    let client = client_with(transport.clone());
+
+    let response = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+
+    assert_eq!(response.messages[0].id, "wamid.fake");
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(
+        requests[0].url,
+        "https://graph.facebook.com/v21.0/phone_123/messages"
+    );
+    assert_eq!(
+        requests[0].authorization.as_deref(),
+        Some("Bearer fake_token")
+    );
+
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["to"], "628123456789");
+    assert_eq!(body["text"]["body"], "Hello");
+}
+
+#[tokio::test]
+async fn test_custom_transport_error_response() {
+    let transport = FakeTransport::new(
+        400,
+        r#"{"error":{"message":"Invalid parameter","type":"OAuthException","code":100}}"#,
+    );
+    let client = client_with(transport);
+
+    let result = client.phone_numbers().get("phone_123").await;
+
+    assert!(matches!(result, Err(wacloudapi::Error::Api(e)) if e.code == 100));
+}
+
+#[tokio::test]
+async fn test_custom_transport_multipart_body() {
+    let transport = FakeTransport::new(200, r#"{"id":"media_fake"}"#);
+    let client = client_with(transport.clone());
+
+    let response = client
+        .media()
+        .upload_bytes(b"hello", "hello.txt", "text/plain")
+        .await
+        .unwrap();
+
+    assert_eq!(response.id, "media_fake");
+
+    let request = &transport.requests()[0];
+    let content_type = request.content_type.clone().unwrap();
+    let boundary = content_type
+        .strip_prefix("multipart/form-data; boundary=")
+        .unwrap();
+    let body = String::from_utf8(request.body.clone()).unwrap();
+
+    assert!(body.starts_with(&format!("--{}\r\n", boundary)));
+    assert!(body.contains("name=\"messaging_product\"\r\n\r\nwhatsapp\r\n"));
+    assert!(body.contains(
+        "name=\"file\"; filename=\"hello.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n"
+    ));
+    assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+}
@@ -0,0 +1,245 @@
+//! Tests for the Resumable Upload API
+
+mod common;
+
+use common::*;
+use wacloudapi::templates::{CreateTemplate, HeaderFormat, TemplateCategory};
+use wacloudapi::Error;
+use wiremock::matchers::{body_bytes, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SESSION_ID: &str = "upload:MTphdHRhY2htZW50";
+const CONTENT: &[u8] = b"0123456789abcdef";
+
+async fn mount_session(mock_server: &MockServer, file_name: &str, file_type: &str) {
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/uploads", TEST_APP_ID)))
+        .and(query_param("file_name", file_name))
+        .and(query_param("file_length", CONTENT.len().to_string()))
+        .and(query_param("file_type", file_type))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": SESSION_ID
+        })))
+        .expect(1)
+        .mount(mock_server)
+        .await;
+}
+
+fn upload_at(offset: usize) -> wiremock::MockBuilder {
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}", SESSION_ID)))
+        .and(header("authorization", format!("OAuth {}", TEST_TOKEN)))
+        .and(header("file_offset", offset.to_string()))
+}
+
+fn handle_response() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({ "h": "2:c2FtcGxl" }))
+}
+
+async fn mount_status(mock_server: &MockServer, offset: usize) {
+    Mock::given(method("GET"))
+        .and(path(format!("/v21.0/{}", SESSION_ID)))
+        .and(header("authorization", format!("OAuth {}", TEST_TOKEN)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": SESSION_ID,
+            "file_offset": offset
+        })))
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_upload_bytes_returns_handle() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_session(&mock_server, "banner.jpg", "image/jpeg").await;
+
+    upload_at(0)
+        .and(body_bytes(CONTENT))
+        .respond_with(handle_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let upload = client
+        .uploads(TEST_APP_ID)
+        .upload_bytes(CONTENT, "banner.jpg", "image/jpeg")
+        .await
+        .unwrap();
+    assert_eq!(upload.h, "2:c2FtcGxl");
+}
+
+#[tokio::test]
+async fn test_interrupted_upload_resumes_from_reported_offset() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_session(&mock_server, "brochure.pdf", "application/pdf").await;
+    mount_status(&mock_server, 6).await;
+
+    upload_at(0)
+        .respond_with(ResponseTemplate::new(503).set_body_json(serde_json::json!({
+            "error": { "message": "Service temporarily unavailable", "code": 2 }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    upload_at(6)
+        .and(body_bytes(&CONTENT[6..]))
+        .respond_with(handle_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = temp_dir("uploads");
+    let file = dir.join("brochure.pdf");
+    std::fs::write(&file, CONTENT).unwrap();
+
+    let upload = client
+        .uploads(TEST_APP_ID)
+        .upload_file(&file)
+        .await
+        .unwrap();
+    assert_eq!(upload.h, "2:c2FtcGxl");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_resume_existing_session() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_status(&mock_server, 10).await;
+
+    upload_at(10)
+        .and(body_bytes(&CONTENT[10..]))
+        .respond_with(handle_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let uploads = client.uploads(TEST_APP_ID);
+    assert_eq!(uploads.status(SESSION_ID).await.unwrap().file_offset, 10);
+
+    let upload = uploads.resume_bytes(SESSION_ID, CONTENT).await.unwrap();
+    assert_eq!(upload.h, "2:c2FtcGxl");
+}
+
+/// Mount a status lookup answered once with `offset`
+async fn mount_status_once(mock_server: &MockServer, offset: usize) {
+    Mock::given(method("GET"))
+        .and(path(format!("/v21.0/{}", SESSION_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": SESSION_ID,
+            "file_offset": offset
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(mock_server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_upload_is_sent_in_chunks() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_session(&mock_server, "brochure.pdf", "application/pdf").await;
+    mount_status_once(&mock_server, 6).await;
+    mount_status_once(&mock_server, 12).await;
+
+    for (start, end) in [(0, 6), (6, 12)] {
+        upload_at(start)
+            .and(body_bytes(&CONTENT[start..end]))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+    upload_at(12)
+        .and(body_bytes(&CONTENT[12..]))
+        .respond_with(handle_response())
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let dir = temp_dir("uploads-chunked");
+    let file = dir.join("brochure.pdf");
+    std::fs::write(&file, CONTENT).unwrap();
+
+    let upload = client
+        .uploads(TEST_APP_ID)
+        .chunk_size(6)
+        .upload_file(&file)
+        .await
+        .unwrap();
+    assert_eq!(upload.h, "2:c2FtcGxl");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_stalled_session_fails() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_session(&mock_server, "banner.jpg", "image/jpeg").await;
+    mount_status(&mock_server, 0).await;
+
+    upload_at(0)
+        .and(body_bytes(&CONTENT[..8]))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let error = client
+        .uploads(TEST_APP_ID)
+        .chunk_size(8)
+        .max_attempts(2)
+        .upload_bytes(CONTENT, "banner.jpg", "image/jpeg")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::UploadStalled { file_offset: 0, .. }));
+}
+
+#[tokio::test]
+async fn test_permanent_errors_are_not_resumed() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+    mount_session(&mock_server, "banner.jpg", "image/jpeg").await;
+
+    upload_at(0)
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "message": "Invalid parameter", "code": 100 }
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let result = client
+        .uploads(TEST_APP_ID)
+        .max_attempts(5)
+        .upload_bytes(CONTENT, "banner.jpg", "image/jpeg")
+        .await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_template_media_header_uses_handle() {
+    let template = CreateTemplate::new("summer_sale", TemplateCategory::Marketing, "en_US")
+        .with_media_header(HeaderFormat::Image, "2:c2FtcGxl")
+        .with_body("Our summer sale starts today!");
+
+    let json = serde_json::to_value(&template).unwrap();
+    assert_eq!(
+        json["components"][0],
+        serde_json::json!({
+            "type": "HEADER",
+            "format": "IMAGE",
+            "example": { "header_handle": ["2:c2FtcGxl"] }
+        })
+    );
+}
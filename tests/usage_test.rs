@@ -0,0 +1,265 @@
+//! Tests for usage headers and Retry-After parsing
+
+mod common;
+
+use common::*;
+use reqwest::header::{HeaderMap, HeaderValue};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use wacloudapi::retry::RetryPolicy;
+use wacloudapi::usage::{parse_retry_after, UsageInfo};
+use wacloudapi::Error;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const APP_USAGE: &str = r#"{"call_count":28,"total_time":25,"total_cputime":25}"#;
+const BUSINESS_USAGE: &str = r#"{"987654321":[{"type":"whatsapp_business_management","call_count":100,"total_cputime":40,"total_time":45,"estimated_time_to_regain_access":19}]}"#;
+
+fn rate_limit_body() -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "message": "Rate limit hit",
+            "type": "OAuthException",
+            "code": 130429,
+            "fbtrace_id": "trace_429"
+        }
+    })
+}
+
+#[test]
+fn test_parse_usage_headers() {
+    let mut headers = HeaderMap::new();
+    assert!(UsageInfo::from_headers(&headers).is_none());
+
+    headers.insert("x-app-usage", HeaderValue::from_static(APP_USAGE));
+    headers.insert(
+        "x-business-use-case-usage",
+        HeaderValue::from_static(BUSINESS_USAGE),
+    );
+
+    let usage = UsageInfo::from_headers(&headers).unwrap();
+    let app = usage.app.unwrap();
+    assert_eq!(app.call_count, 28);
+    assert_eq!(app.max_usage(), 28);
+
+    let business = &usage.business_use_cases["987654321"][0];
+    assert_eq!(business.use_case, "whatsapp_business_management");
+    assert_eq!(business.total_cputime, 40);
+    assert_eq!(business.estimated_time_to_regain_access, 19);
+
+    assert_eq!(usage.max_usage(), 100);
+    assert_eq!(
+        usage.estimated_time_to_regain_access(),
+        Some(Duration::from_secs(19 * 60))
+    );
+}
+
+#[test]
+fn test_malformed_headers_are_ignored() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-app-usage", HeaderValue::from_static("not json"));
+    headers.insert("retry-after", HeaderValue::from_static("soon"));
+
+    assert!(UsageInfo::from_headers(&headers).is_none());
+    assert_eq!(parse_retry_after(&headers), None);
+
+    headers.insert("retry-after", HeaderValue::from_static(" 120 "));
+    assert_eq!(parse_retry_after(&headers), Some(120));
+}
+
+#[test]
+fn test_retry_after_http_date() {
+    let mut headers = HeaderMap::new();
+
+    // Dates in the past mean retrying right away
+    for date in [
+        "Sun, 06 Nov 1994 08:49:37 GMT",
+        "Sunday, 06-Nov-94 08:49:37 GMT",
+        "Sun Nov  6 08:49:37 1994",
+    ] {
+        headers.insert("retry-after", HeaderValue::from_static(date));
+        assert_eq!(parse_retry_after(&headers), Some(0), "{}", date);
+    }
+
+    headers.insert(
+        "retry-after",
+        HeaderValue::from_static("Thu, 01 Jan 2099 00:00:00 GMT"),
+    );
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let expected = 4_070_908_800 - now;
+    let seconds = parse_retry_after(&headers).unwrap();
+    assert!(seconds.abs_diff(expected) <= 1);
+
+    headers.insert(
+        "retry-after",
+        HeaderValue::from_static("Sun, 32 Nov 1994 08:49:37 GMT"),
+    );
+    assert_eq!(parse_retry_after(&headers), None);
+
+    headers.insert(
+        "retry-after",
+        HeaderValue::from_static("Sun, 06 Nov 99999999999999 08:49:37 GMT"),
+    );
+    assert_eq!(parse_retry_after(&headers), None);
+}
+
+#[tokio::test]
+async fn test_client_keeps_latest_usage() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    assert!(client.usage().is_none());
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-app-usage", APP_USAGE)
+                .set_body_json(message_response("wamid.usage")),
+        )
+        .mount(&mock_server)
+        .await;
+
+    client
+        .clone()
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+
+    let usage = client.usage().unwrap();
+    assert_eq!(usage.app.unwrap().call_count, 28);
+    assert!(usage.business_use_cases.is_empty());
+}
+
+#[tokio::test]
+async fn test_rate_limited_error_has_retry_after_and_usage() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "30")
+                .insert_header("x-business-use-case-usage", BUSINESS_USAGE)
+                .set_body_json(rate_limit_body()),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let error = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap_err();
+
+    let Error::RateLimited {
+        retry_after, usage, ..
+    } = &error
+    else {
+        panic!("expected Error::RateLimited, got {:?}", error);
+    };
+    assert_eq!(*retry_after, Some(30));
+    assert_eq!(usage.as_ref().unwrap().max_usage(), 100);
+    assert_eq!(client.usage().unwrap().max_usage(), 100);
+}
+
+#[tokio::test]
+async fn test_retry_after_falls_back_to_regain_access_estimate() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server);
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(429).insert_header("x-business-use-case-usage", BUSINESS_USAGE),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let error = client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::RateLimited {
+            retry_after: Some(1140),
+            error: None,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_retry_waits_for_retry_after() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server).with_retry_policy(
+        RetryPolicy::new()
+            .max_attempts(2)
+            .base_delay(Duration::from_millis(1))
+            .jitter(false),
+    );
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "1")
+                .set_body_json(rate_limit_body()),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(message_response("wamid.ok")))
+        .mount(&mock_server)
+        .await;
+
+    let start = Instant::now();
+    client
+        .messages()
+        .send_text("628123456789", "Hello")
+        .await
+        .unwrap();
+
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_retry_after_beyond_max_delay_is_not_retried() {
+    let mock_server = MockServer::start().await;
+    let client = create_test_client(&mock_server).with_retry_policy(
+        RetryPolicy::new()
+            .max_attempts(3)
+            .max_delay(Duration::from_secs(5)),
+    );
+
+    Mock::given(method("POST"))
+        .and(path(format!("/v21.0/{}/messages", TEST_PHONE_ID)))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "3600")
+                .set_body_json(rate_limit_body()),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let result = client.messages().send_text("628123456789", "Hello").await;
+
+    assert!(matches!(
+        result,
+        Err(Error::RateLimited {
+            retry_after: Some(3600),
+            ..
+        })
+    ));
+}
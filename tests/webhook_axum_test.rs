@@ -0,0 +1,110 @@
+//! Tests for the axum webhook router
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use tower::ServiceExt;
+use wacloudapi::webhooks::{self, compute_signature, WebhookEndpoint, WebhookEvent};
+
+const SECRET: &str = "app_secret";
+
+const PAYLOAD: &str = r#"{
+    "object": "whatsapp_business_account",
+    "entry": [{
+        "id": "WABA_ID",
+        "changes": [{
+            "value": {
+                "messaging_product": "whatsapp",
+                "metadata": {
+                    "display_phone_number": "628123456789",
+                    "phone_number_id": "PHONE_ID"
+                },
+                "statuses": [{
+                    "id": "wamid.1",
+                    "status": "delivered",
+                    "timestamp": "1704067200",
+                    "recipient_id": "628111222333"
+                }]
+            },
+            "field": "messages"
+        }]
+    }]
+}"#;
+
+fn app() -> (axum::Router, tokio::sync::mpsc::Receiver<WebhookEvent>) {
+    let (sender, receiver) = tokio::sync::mpsc::channel(10);
+    let endpoint = WebhookEndpoint::new("my_token", SECRET, sender);
+    (webhooks::axum::router("/webhook", endpoint), receiver)
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_echoes_challenge() {
+    let (app, _) = app();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::get(
+                "/webhook?hub.mode=subscribe&hub.verify_token=my_token&hub.challenge=abc123",
+            )
+            .body(Body::empty())
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_text(response).await, "abc123");
+
+    let response = app
+        .oneshot(
+            Request::get("/webhook?hub.mode=subscribe&hub.verify_token=nope&hub.challenge=abc123")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_post_verifies_and_forwards_events() {
+    let (app, mut receiver) = app();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::post("/webhook")
+                .header(
+                    "X-Hub-Signature-256",
+                    compute_signature(PAYLOAD.as_bytes(), SECRET),
+                )
+                .body(Body::from(PAYLOAD))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(matches!(
+        receiver.recv().await,
+        Some(WebhookEvent::MessageDelivered { .. })
+    ));
+
+    let response = app
+        .oneshot(
+            Request::post("/webhook")
+                .header("X-Hub-Signature-256", "sha256=00")
+                .body(Body::from(PAYLOAD))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert!(receiver.try_recv().is_err());
+}
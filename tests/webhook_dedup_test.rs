@@ -0,0 +1,199 @@
+//! Tests for webhook event deduplication
+
+mod common;
+
+use common::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use wacloudapi::webhooks::{
+    DedupStore, Deduplicator, FileDedupStore, MemoryDedupStore, WebhookPayload, WebhookRouter,
+};
+
+fn payload(messages: serde_json::Value, statuses: serde_json::Value) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "628123456789",
+                        "phone_number_id": "PHONE_ID"
+                    },
+                    "messages": messages,
+                    "statuses": statuses
+                },
+                "field": "messages"
+            }]
+        }]
+    }))
+    .unwrap()
+}
+
+fn text(id: &str) -> serde_json::Value {
+    serde_json::json!({
+        "from": "628111222333",
+        "id": id,
+        "timestamp": "1704067200",
+        "type": "text",
+        "text": { "body": "Hello" }
+    })
+}
+
+fn status(id: &str, status: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "status": status,
+        "timestamp": "1704067200",
+        "recipient_id": "628111222333"
+    })
+}
+
+#[tokio::test]
+async fn test_memory_store_ttl_and_capacity() {
+    let store = MemoryDedupStore::new(2, Duration::from_millis(50));
+
+    assert!(store.insert("a").await.unwrap());
+    assert!(!store.insert("a").await.unwrap());
+    assert!(store.insert("b").await.unwrap());
+
+    // "a" was seen more recently than "b", so "b" is evicted
+    assert!(!store.insert("a").await.unwrap());
+    assert!(store.insert("c").await.unwrap());
+    assert_eq!(store.len(), 2);
+    assert!(store.insert("b").await.unwrap());
+
+    std::thread::sleep(Duration::from_millis(80));
+    assert!(store.insert("c").await.unwrap());
+
+    store.remove("c").await.unwrap();
+    assert!(store.insert("c").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_file_store_survives_reopen() {
+    let path = temp_path("dedup-reopen");
+    let ttl = Duration::from_secs(60);
+
+    let store = FileDedupStore::open(&path, 100, ttl).unwrap();
+    assert!(store.insert("wamid.1:message").await.unwrap());
+    assert!(store.insert("wamid.2:message").await.unwrap());
+    store.remove("wamid.2:message").await.unwrap();
+    drop(store);
+
+    let store = FileDedupStore::open(&path, 100, ttl).unwrap();
+    assert_eq!(store.len(), 1);
+    assert!(!store.insert("wamid.1:message").await.unwrap());
+    assert!(store.insert("wamid.2:message").await.unwrap());
+
+    // The log is compacted instead of growing without bound
+    for i in 0..500 {
+        store
+            .insert(&format!("wamid.{}:read", i % 10))
+            .await
+            .unwrap();
+    }
+    let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+    assert!(lines <= 200, "log has {} lines", lines);
+
+    // Compaction leaves no temporary files behind
+    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+    let leftovers = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let file = entry.file_name().to_string_lossy().into_owned();
+            file.starts_with(&name) && file.ends_with(".tmp")
+        })
+        .count();
+    assert_eq!(leftovers, 0);
+
+    drop(store);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_deduplicator_keys_on_message_and_status() {
+    let dedup = Deduplicator::new(MemoryDedupStore::new(100, Duration::from_secs(60)));
+    let delivery = payload(
+        serde_json::json!([text("wamid.IN")]),
+        serde_json::json!([
+            status("wamid.OUT", "delivered"),
+            status("wamid.OUT", "read")
+        ]),
+    );
+
+    let first = dedup.envelopes(&delivery).await.unwrap();
+    assert_eq!(first.len(), 3);
+    assert_eq!(first[0].dedup_key().as_deref(), Some("wamid.IN:message"));
+    assert_eq!(first[1].dedup_key().as_deref(), Some("wamid.OUT:delivered"));
+    assert_eq!(first[2].dedup_key().as_deref(), Some("wamid.OUT:read"));
+
+    assert!(dedup.events(&delivery).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_reactions_keyed_on_their_own_id() {
+    let reaction = |id: &str, emoji: &str| {
+        serde_json::json!({
+            "from": "628111222333",
+            "id": id,
+            "timestamp": "1704067200",
+            "type": "reaction",
+            "reaction": { "message_id": "wamid.OUT", "emoji": emoji }
+        })
+    };
+    let delivery = payload(
+        serde_json::json!([reaction("wamid.R1", "👍"), reaction("wamid.R2", "")]),
+        serde_json::json!([]),
+    );
+
+    let dedup = Deduplicator::new(MemoryDedupStore::new(100, Duration::from_secs(60)));
+    assert_eq!(dedup.events(&delivery).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_router_skips_redeliveries() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let router = WebhookRouter::new()
+        .dedup(MemoryDedupStore::new(100, Duration::from_secs(60)))
+        .on_text(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        });
+    let delivery = payload(serde_json::json!([text("wamid.1")]), serde_json::json!([]));
+
+    let report = router.dispatch(&delivery).await;
+    assert_eq!(report.handled, 1);
+
+    let report = router.dispatch(&delivery).await;
+    assert_eq!(report.handled, 0);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_router_retries_failed_events() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let router = WebhookRouter::new()
+        .dedup(MemoryDedupStore::new(100, Duration::from_secs(60)))
+        .on_text(move |_| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match attempt {
+                    0 => Err("database unavailable".into()),
+                    _ => Ok(()),
+                }
+            }
+        });
+    let delivery = payload(serde_json::json!([text("wamid.1")]), serde_json::json!([]));
+
+    assert!(!router.dispatch(&delivery).await.is_success());
+    assert!(router.dispatch(&delivery).await.is_success());
+    assert_eq!(router.dispatch(&delivery).await.skipped, 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,122 @@
+//! Tests for the framework-independent webhook endpoint
+
+use reqwest::StatusCode;
+use wacloudapi::webhooks::{
+    compute_signature, VerificationQuery, WebhookEndpoint, WebhookEvent, WebhookPayload,
+    WebhookRouter,
+};
+
+const SECRET: &str = "app_secret";
+
+const PAYLOAD: &str = r#"{
+    "object": "whatsapp_business_account",
+    "entry": [{
+        "id": "WABA_ID",
+        "changes": [{
+            "value": {
+                "messaging_product": "whatsapp",
+                "metadata": {
+                    "display_phone_number": "628123456789",
+                    "phone_number_id": "PHONE_ID"
+                },
+                "messages": [{
+                    "from": "628111222333",
+                    "id": "wamid.1",
+                    "timestamp": "1704067200",
+                    "type": "text",
+                    "text": { "body": "Hello" }
+                }]
+            },
+            "field": "messages"
+        }]
+    }]
+}"#;
+
+fn endpoint_with_channel() -> (
+    WebhookEndpoint,
+    tokio::sync::mpsc::UnboundedReceiver<WebhookEvent>,
+) {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    (WebhookEndpoint::new("my_token", SECRET, sender), receiver)
+}
+
+#[test]
+fn test_verification_handshake() {
+    let (endpoint, _) = endpoint_with_channel();
+
+    let query = VerificationQuery::from_query(
+        "hub.mode=subscribe&hub.verify_token=my_token&hub.challenge=1158201444",
+    );
+    let response = endpoint.verify(&query);
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, "1158201444");
+
+    let query = VerificationQuery::from_query(
+        "hub.mode=subscribe&hub.verify_token=wrong&hub.challenge=1158201444",
+    );
+    assert_eq!(endpoint.verify(&query).status, StatusCode::FORBIDDEN);
+
+    let query = VerificationQuery::from_query(
+        "hub.mode=subscribe&hub.verify_token=my_token_&hub.challenge=1158201444",
+    );
+    assert_eq!(endpoint.verify(&query).status, StatusCode::FORBIDDEN);
+
+    let query = VerificationQuery::from_query("hub.mode=subscribe&hub.verify_token=my_token");
+    assert_eq!(endpoint.verify(&query).status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_receive_sends_events_to_channel() {
+    let (endpoint, mut receiver) = endpoint_with_channel();
+    let signature = compute_signature(PAYLOAD.as_bytes(), SECRET);
+
+    let response = endpoint.receive(Some(&signature), PAYLOAD.as_bytes()).await;
+
+    assert_eq!(response.status, StatusCode::OK);
+    match receiver.try_recv().unwrap() {
+        WebhookEvent::TextMessage { text, .. } => assert_eq!(text, "Hello"),
+        other => panic!("unexpected event: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_receive_rejects_bad_signature_and_json() {
+    let (endpoint, mut receiver) = endpoint_with_channel();
+
+    let response = endpoint.receive(None, PAYLOAD.as_bytes()).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let forged = compute_signature(PAYLOAD.as_bytes(), "other_secret");
+    let response = endpoint.receive(Some(&forged), PAYLOAD.as_bytes()).await;
+    assert_eq!(response.status, StatusCode::UNAUTHORIZED);
+
+    let body = b"not json";
+    let signature = compute_signature(body, SECRET);
+    let response = endpoint.receive(Some(&signature), body).await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+
+    assert!(receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_handler_failure_returns_500() {
+    let signature = compute_signature(PAYLOAD.as_bytes(), SECRET);
+
+    let endpoint = WebhookEndpoint::new("my_token", SECRET, |_payload: WebhookPayload| async {
+        Err("database unavailable".into())
+    });
+    let response = endpoint.receive(Some(&signature), PAYLOAD.as_bytes()).await;
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!response.body.contains("database"));
+
+    let router = WebhookRouter::new().on_text(|_| async { Err("boom".into()) });
+    let endpoint = WebhookEndpoint::new("my_token", SECRET, router);
+    let response = endpoint.receive(Some(&signature), PAYLOAD.as_bytes()).await;
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+
+    // A closed channel can't accept events either
+    let (endpoint, receiver) = endpoint_with_channel();
+    drop(receiver);
+    let response = endpoint.receive(Some(&signature), PAYLOAD.as_bytes()).await;
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+}
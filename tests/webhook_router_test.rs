@@ -0,0 +1,313 @@
+//! Tests for the webhook event router
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use wacloudapi::webhooks::{
+    EventKind, Middleware, MiddlewareAfterFuture, MiddlewareFuture, Next, Outcome, StatusKind,
+    WebhookEnvelope, WebhookEvent, WebhookPayload, WebhookRouter,
+};
+
+fn text(from: &str, id: &str, body: &str) -> WebhookEvent {
+    WebhookEvent::TextMessage {
+        from: from.to_string(),
+        text: body.to_string(),
+        message_id: id.to_string(),
+        timestamp: "1704067200".to_string(),
+        profile_name: None,
+        context: None,
+        referral: None,
+    }
+}
+
+fn delivered(id: &str) -> WebhookEvent {
+    WebhookEvent::MessageDelivered {
+        message_id: id.to_string(),
+        recipient: "628111".to_string(),
+        timestamp: "1704067200".to_string(),
+        conversation: None,
+        pricing: None,
+        biz_opaque_callback_data: None,
+    }
+}
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn recorder(
+    log: &Log,
+    label: &'static str,
+) -> impl Fn(WebhookEnvelope) -> futures_util::future::Ready<wacloudapi::webhooks::HandlerResult>
+       + Send
+       + Sync
+       + 'static {
+    let log = log.clone();
+    move |event| {
+        log.lock()
+            .unwrap()
+            .push(format!("{}:{}", label, event.message_id().unwrap_or("-")));
+        futures_util::future::ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_routes_by_kind() {
+    let log = Log::default();
+    let router = WebhookRouter::new()
+        .on_text(recorder(&log, "text"))
+        .on_status(StatusKind::Delivered, recorder(&log, "delivered"))
+        .on_status(StatusKind::Read, recorder(&log, "read"));
+
+    let report = router
+        .dispatch_events(vec![text("628111", "wamid.1", "Hi"), delivered("wamid.0")])
+        .await;
+
+    assert!(report.is_success());
+    assert_eq!(report.handled, 2);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["text:wamid.1", "delivered:wamid.0"]
+    );
+}
+
+#[tokio::test]
+async fn test_fallback_and_unhandled() {
+    let log = Log::default();
+    let events = vec![text("628111", "wamid.1", "Hi"), delivered("wamid.0")];
+
+    let without_fallback = WebhookRouter::new().on_text(recorder(&log, "text"));
+    let report = without_fallback.dispatch_events(events.clone()).await;
+    assert_eq!(report.handled, 1);
+    assert_eq!(report.unhandled, 1);
+
+    let with_fallback = without_fallback.fallback(recorder(&log, "fallback"));
+    let report = with_fallback.dispatch_events(events).await;
+    assert_eq!(report.handled, 2);
+    assert_eq!(report.unhandled, 0);
+    assert_eq!(log.lock().unwrap().last().unwrap(), "fallback:wamid.0");
+}
+
+#[tokio::test]
+async fn test_handler_errors_do_not_abort_batch() {
+    let log = Log::default();
+    let router = WebhookRouter::new()
+        .on_text(|envelope| async move {
+            match envelope.event {
+                WebhookEvent::TextMessage { text, .. } if text == "boom" => {
+                    Err("handler exploded".into())
+                }
+                _ => Ok(()),
+            }
+        })
+        .on_text(recorder(&log, "second"));
+
+    let report = router
+        .dispatch_events(vec![
+            text("628111", "wamid.1", "boom"),
+            text("628111", "wamid.2", "fine"),
+        ])
+        .await;
+
+    assert!(!report.is_success());
+    assert_eq!(report.handled, 2);
+    assert_eq!(report.failures.len(), 1);
+
+    let failure = &report.failures[0];
+    assert_eq!(failure.index, 0);
+    assert_eq!(failure.kind, EventKind::Text);
+    assert_eq!(failure.message_id.as_deref(), Some("wamid.1"));
+    assert_eq!(failure.error.to_string(), "handler exploded");
+
+    // The second handler still ran for both events
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["second:wamid.1", "second:wamid.2"]
+    );
+}
+
+#[tokio::test]
+async fn test_closure_middleware_can_skip_and_fail() {
+    let log = Log::default();
+    let allowed = Arc::new(HashSet::from(["628111".to_string()]));
+
+    let router = WebhookRouter::new()
+        .middleware(move |event: &WebhookEnvelope| {
+            let user = event.user().map(String::from);
+            let allowed = allowed.clone();
+            async move {
+                match user {
+                    Some(user) if allowed.contains(&user) => Ok(Next::Continue),
+                    Some(_) => Ok(Next::Skip),
+                    None => Err("event without user".into()),
+                }
+            }
+        })
+        .on_text(recorder(&log, "text"))
+        .fallback(recorder(&log, "fallback"));
+
+    let report = router
+        .dispatch_events(vec![
+            text("628111", "wamid.1", "Hi"),
+            text("628999", "wamid.2", "Spam"),
+            WebhookEvent::Unknown,
+        ])
+        .await;
+
+    assert_eq!(report.handled, 2);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].kind, EventKind::Unknown);
+    assert_eq!(*log.lock().unwrap(), vec!["text:wamid.1"]);
+}
+
+/// Drops events whose message ID was already seen and records outcomes
+#[derive(Default)]
+struct Dedup {
+    seen: Mutex<HashSet<String>>,
+    outcomes: Mutex<Vec<Outcome>>,
+}
+
+impl Middleware for Dedup {
+    fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+        Box::pin(async move {
+            let id = event.message_id().unwrap_or_default().to_string();
+            if self.seen.lock().unwrap().insert(id) {
+                Ok(Next::Continue)
+            } else {
+                Ok(Next::Skip)
+            }
+        })
+    }
+
+    fn after<'a>(
+        &'a self,
+        _event: &'a WebhookEnvelope,
+        outcome: Outcome,
+    ) -> MiddlewareAfterFuture<'a> {
+        self.outcomes.lock().unwrap().push(outcome);
+        Box::pin(std::future::ready(()))
+    }
+}
+
+#[tokio::test]
+async fn test_struct_middleware_with_after_hook() {
+    let log = Log::default();
+    let dedup = Arc::new(Dedup::default());
+
+    struct Shared(Arc<Dedup>);
+    impl Middleware for Shared {
+        fn before<'a>(&'a self, event: &'a WebhookEnvelope) -> MiddlewareFuture<'a> {
+            self.0.before(event)
+        }
+        fn after<'a>(
+            &'a self,
+            event: &'a WebhookEnvelope,
+            outcome: Outcome,
+        ) -> MiddlewareAfterFuture<'a> {
+            self.0.after(event, outcome)
+        }
+    }
+
+    let router = WebhookRouter::new()
+        .middleware(Shared(dedup.clone()))
+        .on_text(recorder(&log, "text"));
+
+    let report = router
+        .dispatch_events(vec![
+            text("628111", "wamid.1", "Hi"),
+            text("628111", "wamid.1", "Hi"),
+            delivered("wamid.1"),
+        ])
+        .await;
+
+    assert_eq!(report.handled, 1);
+    assert_eq!(report.skipped, 2);
+    assert_eq!(
+        *dedup.outcomes.lock().unwrap(),
+        vec![Outcome::Handled, Outcome::Skipped, Outcome::Skipped]
+    );
+}
+
+#[tokio::test]
+async fn test_dispatch_payload() {
+    let payload: WebhookPayload = serde_json::from_str(
+        r#"{
+            "object": "whatsapp_business_account",
+            "entry": [{
+                "id": "WABA_ID",
+                "changes": [{
+                    "value": {
+                        "messaging_product": "whatsapp",
+                        "metadata": {
+                            "display_phone_number": "628123456789",
+                            "phone_number_id": "PHONE_ID"
+                        },
+                        "messages": [{
+                            "from": "628111222333",
+                            "id": "wamid.btn",
+                            "timestamp": "1704067200",
+                            "type": "interactive",
+                            "interactive": {
+                                "type": "button_reply",
+                                "button_reply": { "id": "yes", "title": "Yes" }
+                            }
+                        }]
+                    },
+                    "field": "messages"
+                }]
+            }]
+        }"#,
+    )
+    .unwrap();
+
+    let log = Log::default();
+    let numbers = log.clone();
+    let router = WebhookRouter::new()
+        .on_button_reply(recorder(&log, "button"))
+        .on_button_reply(move |envelope| {
+            let number = envelope.phone_number_id.unwrap_or_default();
+            numbers.lock().unwrap().push(format!("to:{}", number));
+            async { Ok(()) }
+        });
+
+    let report = router.dispatch(&payload).await;
+
+    assert!(report.is_success());
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["button:wamid.btn", "to:PHONE_ID"]
+    );
+}
+
+#[tokio::test]
+async fn test_template_status_handler() {
+    let payload: WebhookPayload = serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "event": "APPROVED",
+                    "message_template_id": 12345,
+                    "message_template_name": "welcome",
+                    "message_template_language": "en_US",
+                    "reason": "NONE"
+                },
+                "field": "message_template_status_update"
+            }]
+        }]
+    }))
+    .unwrap();
+
+    let log = Log::default();
+    let names = log.clone();
+    let router = WebhookRouter::new().on_template_status_update(move |envelope| {
+        if let WebhookEvent::TemplateStatusUpdate(update) = envelope.event {
+            names.lock().unwrap().push(update.message_template_name);
+        }
+        async { Ok(()) }
+    });
+
+    let report = router.dispatch(&payload).await;
+
+    assert_eq!(report.handled, 1);
+    assert_eq!(*log.lock().unwrap(), vec!["welcome"]);
+}
@@ -0,0 +1,174 @@
+//! Tests for the tower webhook service
+#![cfg(feature = "tower")]
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use reqwest::{Method, StatusCode};
+use tower::ServiceExt;
+use wacloudapi::webhooks::{compute_signature, WebhookEndpoint, WebhookEvent, WebhookService};
+
+const SECRET: &str = "app_secret";
+
+const PAYLOAD: &str = r#"{
+    "object": "whatsapp_business_account",
+    "entry": [{
+        "id": "WABA_ID",
+        "changes": [{
+            "value": {
+                "messaging_product": "whatsapp",
+                "metadata": {
+                    "display_phone_number": "628123456789",
+                    "phone_number_id": "PHONE_ID"
+                },
+                "messages": [{
+                    "from": "628111222333",
+                    "id": "wamid.1",
+                    "timestamp": "1704067200",
+                    "type": "text",
+                    "text": { "body": "Hello" }
+                }]
+            },
+            "field": "messages"
+        }]
+    }]
+}"#;
+
+fn service() -> (
+    WebhookService,
+    tokio::sync::mpsc::UnboundedReceiver<WebhookEvent>,
+) {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    let endpoint = WebhookEndpoint::new("my_token", SECRET, sender);
+    (WebhookService::new(endpoint), receiver)
+}
+
+fn request(method: Method, uri: &str) -> http::request::Builder {
+    http::Request::builder().method(method).uri(uri)
+}
+
+async fn call(
+    service: WebhookService,
+    request: http::Request<Full<Bytes>>,
+) -> (StatusCode, String) {
+    let response = service.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_get_echoes_challenge() {
+    let (service, _) = service();
+
+    let (status, body) = call(
+        service.clone(),
+        request(
+            Method::GET,
+            "/webhook?hub.mode=subscribe&hub.verify_token=my_token&hub.challenge=42",
+        )
+        .body(Full::default())
+        .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "42");
+
+    let (status, _) = call(
+        service,
+        request(Method::GET, "/webhook?hub.challenge=42")
+            .body(Full::default())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_post_verifies_and_parses() {
+    let (service, mut receiver) = service();
+
+    let (status, _) = call(
+        service.clone(),
+        request(Method::POST, "/webhook")
+            .header(
+                "X-Hub-Signature-256",
+                compute_signature(PAYLOAD.as_bytes(), SECRET),
+            )
+            .body(Full::from(PAYLOAD))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(WebhookEvent::TextMessage { .. })
+    ));
+
+    let (status, _) = call(
+        service.clone(),
+        request(Method::POST, "/webhook")
+            .body(Full::from(PAYLOAD))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = call(
+        service,
+        request(Method::POST, "/webhook")
+            .header("X-Hub-Signature-256", compute_signature(b"{}", SECRET))
+            .body(Full::from("{}"))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_body_size_limit() {
+    let (service, mut receiver) = service();
+    let service = service.max_body_size(64);
+    let signature = compute_signature(PAYLOAD.as_bytes(), SECRET);
+
+    // Announced length over the limit
+    let (status, _) = call(
+        service.clone(),
+        request(Method::POST, "/webhook")
+            .header("X-Hub-Signature-256", &signature)
+            .header("Content-Length", PAYLOAD.len())
+            .body(Full::from(PAYLOAD))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    // No announced length, the body itself is over the limit
+    let (status, _) = call(
+        service,
+        request(Method::POST, "/webhook")
+            .header("X-Hub-Signature-256", &signature)
+            .body(Full::from(PAYLOAD))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_other_methods_not_allowed() {
+    let (service, _) = service();
+
+    let response = service
+        .oneshot(
+            request(Method::PUT, "/webhook")
+                .body(Full::<Bytes>::default())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()["allow"], "GET, POST");
+}
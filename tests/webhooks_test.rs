@@ -1,6 +1,9 @@
 //! Tests for Webhooks parsing
 
-use wacloudapi::webhooks::{WebhookEvent, WebhookPayload};
+use wacloudapi::error::SignatureError;
+use wacloudapi::webhooks::{
+    compute_signature, verify_signature, EventKind, StatusKind, WebhookEvent, WebhookPayload,
+};
 
 #[test]
 fn test_parse_text_message_webhook() {
@@ -41,10 +44,17 @@ fn test_parse_text_message_webhook() {
             from,
             text,
             message_id,
+            timestamp,
+            profile_name,
+            context,
+            ..
         } => {
             assert_eq!(from, "628111222333");
             assert_eq!(text, "Hello, World!");
             assert_eq!(message_id, "wamid.HBgM...");
+            assert_eq!(timestamp, "1704067200");
+            assert_eq!(profile_name.as_deref(), Some("John Doe"));
+            assert!(context.is_none());
         }
         _ => panic!("Expected TextMessage event"),
     }
@@ -248,14 +258,129 @@ fn test_parse_message_status_webhook() {
         WebhookEvent::MessageDelivered {
             message_id,
             recipient,
+            timestamp,
+            conversation,
+            pricing,
+            ..
         } => {
             assert_eq!(message_id, "wamid.STATUS123");
             assert_eq!(recipient, "628111222333");
+            assert_eq!(timestamp, "1704067200");
+            assert!(conversation.is_none());
+            assert!(pricing.is_none());
         }
         _ => panic!("Expected MessageDelivered event"),
     }
 }
 
+fn status_payload(status: serde_json::Value) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "628123456789",
+                        "phone_number_id": "PHONE_ID"
+                    },
+                    "statuses": [status]
+                },
+                "field": "messages"
+            }]
+        }]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_parse_sent_status_with_conversation_and_pricing() {
+    let webhook = status_payload(serde_json::json!({
+        "id": "wamid.SENT",
+        "status": "sent",
+        "timestamp": "1704067200",
+        "recipient_id": "628111222333",
+        "biz_opaque_callback_data": "order-42",
+        "conversation": {
+            "id": "CONVERSATION_ID",
+            "expiration_timestamp": "1704153600",
+            "origin": { "type": "utility" }
+        },
+        "pricing": {
+            "billable": true,
+            "pricing_model": "PMP",
+            "category": "utility",
+            "type": "regular"
+        }
+    }));
+
+    match webhook.events().remove(0) {
+        WebhookEvent::MessageSent {
+            message_id,
+            timestamp,
+            conversation,
+            pricing,
+            biz_opaque_callback_data,
+            ..
+        } => {
+            assert_eq!(message_id, "wamid.SENT");
+            assert_eq!(timestamp, "1704067200");
+            let conversation = conversation.unwrap();
+            assert_eq!(conversation.id, "CONVERSATION_ID");
+            assert_eq!(conversation.origin_type(), Some("utility"));
+            assert_eq!(
+                conversation.expiration_timestamp.as_deref(),
+                Some("1704153600")
+            );
+            let pricing = pricing.unwrap();
+            assert!(pricing.billable);
+            assert_eq!(pricing.pricing_model, "PMP");
+            assert_eq!(pricing.category, "utility");
+            assert_eq!(pricing.pricing_type.as_deref(), Some("regular"));
+            assert_eq!(biz_opaque_callback_data.as_deref(), Some("order-42"));
+        }
+        other => panic!("Expected MessageSent event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_failed_status_keeps_all_errors() {
+    let webhook = status_payload(serde_json::json!({
+        "id": "wamid.FAILED",
+        "status": "failed",
+        "timestamp": "1704067200",
+        "recipient_id": "628111222333",
+        "errors": [{
+            "code": 131026,
+            "title": "Message undeliverable",
+            "message": "Message undeliverable",
+            "error_data": { "details": "Recipient is not a WhatsApp user" },
+            "href": "https://developers.facebook.com/docs/whatsapp/cloud-api/support/error-codes/"
+        }, {
+            "code": 131047,
+            "title": "Re-engagement message"
+        }]
+    }));
+
+    match webhook.events().remove(0) {
+        WebhookEvent::MessageFailed {
+            error_code, errors, ..
+        } => {
+            assert_eq!(error_code, 131026);
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].title.as_deref(), Some("Message undeliverable"));
+            assert_eq!(
+                errors[0].details(),
+                Some("Recipient is not a WhatsApp user")
+            );
+            assert_eq!(errors[1].code, 131047);
+            assert_eq!(errors[1].details(), None);
+        }
+        other => panic!("Expected MessageFailed event, got {:?}", other),
+    }
+}
+
 #[test]
 fn test_parse_reaction_webhook() {
     let payload = r#"{
@@ -298,6 +423,7 @@ fn test_parse_reaction_webhook() {
             from,
             message_id,
             emoji,
+            ..
         } => {
             assert_eq!(from, "628444555666");
             assert_eq!(message_id, "wamid.TARGET123");
@@ -352,6 +478,7 @@ fn test_parse_location_message_webhook() {
             latitude,
             longitude,
             message_id,
+            ..
         } => {
             assert_eq!(from, "628777888999");
             assert!((*latitude - -6.2088).abs() < 0.0001);
@@ -439,3 +566,715 @@ fn test_empty_webhook() {
 
     assert!(events.is_empty());
 }
+
+fn field_payload(field: &str, value: serde_json::Value) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "time": 1704067200,
+            "changes": [{ "value": value, "field": field }]
+        }]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_parse_template_status_update() {
+    let webhook = field_payload(
+        "message_template_status_update",
+        serde_json::json!({
+            "event": "PAUSED",
+            "message_template_id": 594425479261596_u64,
+            "message_template_name": "order_update",
+            "message_template_language": "en_US",
+            "reason": "NONE",
+            "other_info": {
+                "title": "FIRST_PAUSE",
+                "description": "Paused for 3 hours due to low quality"
+            }
+        }),
+    );
+
+    assert!(webhook.entry[0].changes[0].value.metadata.is_none());
+
+    let events = webhook.events();
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        WebhookEvent::TemplateStatusUpdate(update) => {
+            assert_eq!(update.event, "PAUSED");
+            assert_eq!(update.message_template_id, "594425479261596");
+            assert_eq!(update.message_template_name, "order_update");
+            assert_eq!(update.reason.as_deref(), Some("NONE"));
+            assert_eq!(update.other_info.as_ref().unwrap().title, "FIRST_PAUSE");
+        }
+        other => panic!("Expected TemplateStatusUpdate event, got {:?}", other),
+    }
+    assert_eq!(events[0].kind(), EventKind::TemplateStatus);
+}
+
+#[test]
+fn test_parse_template_quality_and_category_updates() {
+    let quality = field_payload(
+        "message_template_quality_update",
+        serde_json::json!({
+            "previous_quality_score": "GREEN",
+            "new_quality_score": "YELLOW",
+            "message_template_id": "806312974732579",
+            "message_template_name": "welcome",
+            "message_template_language": "en_US"
+        }),
+    );
+    match &quality.events()[0] {
+        WebhookEvent::TemplateQualityUpdate(update) => {
+            assert_eq!(update.previous_quality_score, "GREEN");
+            assert_eq!(update.new_quality_score, "YELLOW");
+            assert_eq!(update.message_template_id, "806312974732579");
+        }
+        other => panic!("Expected TemplateQualityUpdate event, got {:?}", other),
+    }
+
+    let category = field_payload(
+        "template_category_update",
+        serde_json::json!({
+            "message_template_id": 806312974732579_u64,
+            "message_template_name": "welcome",
+            "message_template_language": "en_US",
+            "previous_category": "UTILITY",
+            "new_category": "MARKETING"
+        }),
+    );
+    match &category.events()[0] {
+        WebhookEvent::TemplateCategoryUpdate(update) => {
+            assert_eq!(update.previous_category.as_deref(), Some("UTILITY"));
+            assert_eq!(update.new_category.as_deref(), Some("MARKETING"));
+            assert_eq!(update.correct_category, None);
+        }
+        other => panic!("Expected TemplateCategoryUpdate event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_template_components_update() {
+    let webhook = field_payload(
+        "template_components_update",
+        serde_json::json!({
+            "message_template_id": 12345678,
+            "message_template_name": "shipping",
+            "message_template_language": "en_US",
+            "message_template_title": "Your order",
+            "message_template_element": "Order {{1}} has shipped",
+            "message_template_footer": "Thanks",
+            "message_template_buttons": [{
+                "message_template_button_type": "URL",
+                "message_template_button_text": "Track",
+                "message_template_button_url": "https://example.com/track"
+            }]
+        }),
+    );
+
+    match &webhook.events()[0] {
+        WebhookEvent::TemplateComponentsUpdate(update) => {
+            assert_eq!(update.message_template_id, "12345678");
+            assert_eq!(
+                update.message_template_element.as_deref(),
+                Some("Order {{1}} has shipped")
+            );
+            let buttons = update.message_template_buttons.as_ref().unwrap();
+            assert_eq!(buttons[0].message_template_button_type, "URL");
+            assert_eq!(
+                buttons[0].message_template_button_url.as_deref(),
+                Some("https://example.com/track")
+            );
+        }
+        other => panic!("Expected TemplateComponentsUpdate event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_phone_number_quality_update() {
+    let webhook = field_payload(
+        "phone_number_quality_update",
+        serde_json::json!({
+            "display_phone_number": "15550783881",
+            "event": "DOWNGRADE",
+            "current_limit": "TIER_1K",
+            "old_limit": "TIER_10K"
+        }),
+    );
+
+    let events = webhook.events();
+    match &events[0] {
+        WebhookEvent::PhoneNumberQualityUpdate(update) => {
+            assert_eq!(update.display_phone_number, "15550783881");
+            assert_eq!(update.current_limit.as_deref(), Some("TIER_1K"));
+            assert_eq!(update.old_limit.as_deref(), Some("TIER_10K"));
+            assert!(update.is_degraded());
+        }
+        other => panic!("Expected PhoneNumberQualityUpdate event, got {:?}", other),
+    }
+    assert_eq!(events[0].kind(), EventKind::PhoneNumberQuality);
+}
+
+#[test]
+fn test_parse_phone_number_name_update() {
+    let webhook = field_payload(
+        "phone_number_name_update",
+        serde_json::json!({
+            "display_phone_number": "15550783881",
+            "decision": "REJECTED",
+            "requested_verified_name": "Lucky Shrub",
+            "rejection_reason": "NAME_FORMAT_UNACCEPTABLE"
+        }),
+    );
+
+    match &webhook.events()[0] {
+        WebhookEvent::PhoneNumberNameUpdate(update) => {
+            assert_eq!(update.decision, "REJECTED");
+            assert_eq!(update.requested_verified_name, "Lucky Shrub");
+            assert_eq!(
+                update.rejection_reason.as_deref(),
+                Some("NAME_FORMAT_UNACCEPTABLE")
+            );
+        }
+        other => panic!("Expected PhoneNumberNameUpdate event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_account_update_ban_and_restriction() {
+    let ban = field_payload(
+        "account_update",
+        serde_json::json!({
+            "event": "DISABLED_UPDATE",
+            "ban_info": {
+                "waba_ban_state": "SCHEDULE_FOR_DISABLE",
+                "waba_ban_date": "2024-01-31"
+            }
+        }),
+    );
+    match &ban.events()[0] {
+        WebhookEvent::AccountUpdate(update) => {
+            let ban_info = update.ban_info.as_ref().unwrap();
+            assert_eq!(ban_info.waba_ban_state, "SCHEDULE_FOR_DISABLE");
+            assert_eq!(ban_info.waba_ban_date.as_deref(), Some("2024-01-31"));
+        }
+        other => panic!("Expected AccountUpdate event, got {:?}", other),
+    }
+
+    let restriction = field_payload(
+        "account_update",
+        serde_json::json!({
+            "phone_number": "15550783881",
+            "event": "ACCOUNT_RESTRICTION",
+            "restriction_info": [{
+                "restriction_type": "RESTRICTED_BIZ_INITIATED_MESSAGING",
+                "expiration": "2024-02-01"
+            }]
+        }),
+    );
+    match &restriction.events()[0] {
+        WebhookEvent::AccountUpdate(update) => {
+            assert_eq!(update.phone_number.as_deref(), Some("15550783881"));
+            let restrictions = update.restriction_info.as_ref().unwrap();
+            assert_eq!(
+                restrictions[0].restriction_type,
+                "RESTRICTED_BIZ_INITIATED_MESSAGING"
+            );
+        }
+        other => panic!("Expected AccountUpdate event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_review_capability_and_security_updates() {
+    let review = field_payload(
+        "account_review_update",
+        serde_json::json!({ "decision": "APPROVED" }),
+    );
+    assert!(matches!(
+        &review.events()[0],
+        WebhookEvent::AccountReviewUpdate(update) if update.decision == "APPROVED"
+    ));
+
+    let capability = field_payload(
+        "business_capability_update",
+        serde_json::json!({
+            "max_daily_conversation_per_phone": 1000,
+            "max_phone_numbers_per_business": 2
+        }),
+    );
+    match &capability.events()[0] {
+        WebhookEvent::BusinessCapabilityUpdate(update) => {
+            assert_eq!(update.max_daily_conversation_per_phone, Some(1000));
+            assert_eq!(update.max_phone_numbers_per_business, Some(2));
+        }
+        other => panic!("Expected BusinessCapabilityUpdate event, got {:?}", other),
+    }
+
+    let security = field_payload(
+        "security",
+        serde_json::json!({
+            "display_phone_number": "15550783881",
+            "event": "PIN_CHANGED",
+            "requester": "1234567890"
+        }),
+    );
+    let events = security.events();
+    match &events[0] {
+        WebhookEvent::Security(update) => {
+            assert_eq!(update.event, "PIN_CHANGED");
+            assert_eq!(update.requester.as_deref(), Some("1234567890"));
+        }
+        other => panic!("Expected Security event, got {:?}", other),
+    }
+    assert_eq!(events[0].kind(), EventKind::Security);
+}
+
+#[test]
+fn test_unparseable_field_value_is_unknown() {
+    let webhook = field_payload(
+        "message_template_status_update",
+        serde_json::json!({ "event": "APPROVED" }),
+    );
+    assert_eq!(webhook.events(), vec![WebhookEvent::Unknown]);
+
+    // Fields without typed events are ignored
+    let webhook = field_payload("some_future_field", serde_json::json!({ "foo": 1 }));
+    assert!(webhook.events().is_empty());
+}
+
+fn message_payload(message: serde_json::Value) -> WebhookPayload {
+    serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "628123456789",
+                        "phone_number_id": "PHONE_ID"
+                    },
+                    "contacts": [{
+                        "profile": { "name": "Jane" },
+                        "wa_id": "628111222333"
+                    }],
+                    "messages": [message]
+                },
+                "field": "messages"
+            }]
+        }]
+    }))
+    .unwrap()
+}
+
+fn single_event(message: serde_json::Value) -> WebhookEvent {
+    let mut events = message_payload(message).events();
+    assert_eq!(events.len(), 1);
+    events.remove(0)
+}
+
+#[test]
+fn test_parse_quick_reply_with_context() {
+    let event = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.BTN",
+        "timestamp": "1704067200",
+        "type": "button",
+        "context": { "from": "628123456789", "id": "wamid.TEMPLATE" },
+        "button": { "text": "Track order", "payload": "TRACK_123" }
+    }));
+
+    match event {
+        WebhookEvent::QuickReply {
+            text,
+            payload,
+            context,
+            profile_name,
+            ..
+        } => {
+            assert_eq!(text, "Track order");
+            assert_eq!(payload, "TRACK_123");
+            assert_eq!(
+                context.unwrap().message_id.as_deref(),
+                Some("wamid.TEMPLATE")
+            );
+            assert_eq!(profile_name.as_deref(), Some("Jane"));
+        }
+        other => panic!("Expected QuickReply event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_flow_reply() {
+    let event = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.FLOW",
+        "timestamp": "1704067200",
+        "type": "interactive",
+        "interactive": {
+            "type": "nfm_reply",
+            "nfm_reply": {
+                "name": "flow",
+                "body": "Sent",
+                "response_json": "{\"flow_token\":\"abc\",\"size\":\"M\"}"
+            }
+        }
+    }));
+
+    match event {
+        WebhookEvent::FlowReply {
+            response_json,
+            body,
+            ..
+        } => {
+            let response: serde_json::Value = serde_json::from_str(&response_json).unwrap();
+            assert_eq!(response["size"], "M");
+            assert_eq!(body.as_deref(), Some("Sent"));
+        }
+        other => panic!("Expected FlowReply event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_order_with_numeric_price() {
+    let event = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.ORDER",
+        "timestamp": "1704067200",
+        "type": "order",
+        "order": {
+            "catalog_id": "CATALOG_ID",
+            "text": "Please deliver today",
+            "product_items": [
+                { "product_retailer_id": "SKU1", "quantity": 2, "item_price": 12.5, "currency": "USD" }
+            ]
+        }
+    }));
+
+    match event {
+        WebhookEvent::OrderMessage { order, .. } => {
+            assert_eq!(order.catalog_id, "CATALOG_ID");
+            assert_eq!(order.product_items[0].quantity, 2);
+            assert_eq!(order.product_items[0].item_price, "12.5");
+        }
+        other => panic!("Expected OrderMessage event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_system_unsupported_and_welcome() {
+    let system = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.SYS",
+        "timestamp": "1704067200",
+        "type": "system",
+        "system": {
+            "body": "User changed from 628111222333 to 628999888777",
+            "new_wa_id": "628999888777",
+            "type": "user_changed_number"
+        }
+    }));
+    match system {
+        WebhookEvent::SystemMessage { system, .. } => {
+            assert_eq!(system.new_wa_id.as_deref(), Some("628999888777"));
+            assert_eq!(system.system_type.as_deref(), Some("user_changed_number"));
+        }
+        other => panic!("Expected SystemMessage event, got {:?}", other),
+    }
+
+    let unsupported = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.UNS",
+        "timestamp": "1704067200",
+        "type": "unsupported",
+        "errors": [{
+            "code": 131051,
+            "title": "Message type unknown",
+            "message": "Message type unknown",
+            "error_data": { "details": "Message type is currently not supported." }
+        }]
+    }));
+    match unsupported {
+        WebhookEvent::UnsupportedMessage { errors, .. } => {
+            assert_eq!(errors[0].code, 131051);
+            assert_eq!(
+                errors[0].error_data.as_ref().unwrap().details,
+                "Message type is currently not supported."
+            );
+        }
+        other => panic!("Expected UnsupportedMessage event, got {:?}", other),
+    }
+
+    let welcome = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.WELCOME",
+        "timestamp": "1704067200",
+        "type": "request_welcome"
+    }));
+    assert_eq!(welcome.kind(), EventKind::RequestWelcome);
+    assert_eq!(welcome.user(), Some("628111222333"));
+}
+
+#[test]
+fn test_parse_referral_and_contact_cards() {
+    let referral = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.AD",
+        "timestamp": "1704067200",
+        "type": "text",
+        "text": { "body": "Is this available?" },
+        "referral": {
+            "source_url": "https://fb.me/ad",
+            "source_type": "ad",
+            "source_id": "AD_ID",
+            "headline": "Summer sale"
+        }
+    }));
+    match referral {
+        WebhookEvent::TextMessage { referral, .. } => {
+            let referral = referral.unwrap();
+            assert_eq!(referral.source_type, "ad");
+            assert_eq!(referral.headline.as_deref(), Some("Summer sale"));
+        }
+        other => panic!("Expected TextMessage event, got {:?}", other),
+    }
+
+    let contacts = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.CARD",
+        "timestamp": "1704067200",
+        "type": "contacts",
+        "contacts": [{
+            "name": { "formatted_name": "John Smith", "first_name": "John", "last_name": "Smith" },
+            "phones": [{ "phone": "+1 555 0100", "type": "WORK", "wa_id": "15550100" }],
+            "emails": [{ "email": "john@example.com", "type": "WORK" }],
+            "org": { "company": "Acme", "title": "Engineer" },
+            "birthday": "1990-01-31"
+        }]
+    }));
+    match contacts {
+        WebhookEvent::ContactMessage { contacts, .. } => {
+            assert_eq!(contacts.len(), 1);
+            let card = &contacts[0];
+            assert_eq!(card.name.formatted_name, "John Smith");
+            assert_eq!(
+                card.phones.as_ref().unwrap()[0].wa_id.as_deref(),
+                Some("15550100")
+            );
+            assert_eq!(card.emails.as_ref().unwrap()[0].email, "john@example.com");
+            assert_eq!(card.org.as_ref().unwrap().company.as_deref(), Some("Acme"));
+            assert_eq!(card.birthday.as_deref(), Some("1990-01-31"));
+        }
+        other => panic!("Expected ContactMessage event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_voice_note() {
+    let event = single_event(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.VOICE",
+        "timestamp": "1704067200",
+        "type": "audio",
+        "audio": {
+            "id": "MEDIA_ID",
+            "mime_type": "audio/ogg; codecs=opus",
+            "sha256": "abc",
+            "voice": true
+        }
+    }));
+
+    match event {
+        WebhookEvent::AudioMessage {
+            media_id,
+            mime_type,
+            voice,
+            ..
+        } => {
+            assert_eq!(media_id, "MEDIA_ID");
+            assert_eq!(mime_type, "audio/ogg; codecs=opus");
+            assert!(voice);
+        }
+        other => panic!("Expected AudioMessage event, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_signature_rfc4231_vectors() {
+    // RFC 4231 test cases 1, 2, 3, 4, 6 and 7 (case 5 uses a truncated output)
+    let cases: Vec<(Vec<u8>, Vec<u8>, &str)> = vec![
+        (
+            vec![0x0b; 20],
+            b"Hi There".to_vec(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        ),
+        (
+            b"Jefe".to_vec(),
+            b"what do ya want for nothing?".to_vec(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        ),
+        (
+            vec![0xaa; 20],
+            vec![0xdd; 50],
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+        ),
+        (
+            (0x01..=0x19).collect(),
+            vec![0xcd; 50],
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+        ),
+        (
+            vec![0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ),
+        (
+            vec![0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.".to_vec(),
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ),
+    ];
+
+    for (key, data, digest) in cases {
+        let header = format!("sha256={}", digest);
+        assert_eq!(compute_signature(&data, &key), header);
+        assert_eq!(verify_signature(&data, Some(&header), &key), Ok(()));
+    }
+}
+
+#[test]
+fn test_signature_accepts_uppercase_hex() {
+    let body = b"{\"object\":\"whatsapp_business_account\"}";
+    let header = compute_signature(body, "secret")
+        .to_uppercase()
+        .replace("SHA256=", "sha256=");
+
+    assert_eq!(verify_signature(body, Some(&header), "secret"), Ok(()));
+}
+
+#[test]
+fn test_signature_errors() {
+    let body = b"{\"object\":\"whatsapp_business_account\"}";
+    let header = compute_signature(body, "secret");
+
+    assert_eq!(
+        verify_signature(body, None, "secret"),
+        Err(SignatureError::MissingHeader)
+    );
+    assert_eq!(
+        verify_signature(body, Some(header.trim_start_matches("sha256=")), "secret"),
+        Err(SignatureError::MalformedPrefix)
+    );
+    assert_eq!(
+        verify_signature(body, Some("sha256=abc"), "secret"),
+        Err(SignatureError::MalformedDigest)
+    );
+    assert_eq!(
+        verify_signature(body, Some(&header), "other_secret"),
+        Err(SignatureError::Mismatch)
+    );
+    assert_eq!(
+        verify_signature(b"tampered", Some(&header), "secret"),
+        Err(SignatureError::Mismatch)
+    );
+}
+
+#[test]
+fn test_envelope_carries_message_metadata() {
+    let webhook = message_payload(serde_json::json!({
+        "from": "628111222333",
+        "id": "wamid.1",
+        "timestamp": "1704067200",
+        "type": "text",
+        "text": { "body": "Hello" },
+        "new_field": { "beta": true }
+    }));
+
+    let envelopes = webhook.envelopes();
+    assert_eq!(envelopes.len(), 1);
+
+    let envelope = &envelopes[0];
+    assert_eq!(envelope.kind(), EventKind::Text);
+    assert_eq!(envelope.waba_id, "WABA_ID");
+    assert_eq!(envelope.phone_number_id.as_deref(), Some("PHONE_ID"));
+    assert_eq!(
+        envelope.display_phone_number.as_deref(),
+        Some("628123456789")
+    );
+    assert_eq!(envelope.timestamp, Some(1704067200));
+    assert_eq!(
+        envelope.time(),
+        Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1704067200))
+    );
+    assert_eq!(envelope.sender_name.as_deref(), Some("Jane"));
+    assert_eq!(envelope.sender_wa_id.as_deref(), Some("628111222333"));
+
+    // Unmodeled fields stay available, fields the message lacks are left out
+    let raw = envelope.raw.as_ref().unwrap();
+    assert_eq!(raw["new_field"]["beta"], true);
+    assert_eq!(raw["text"]["body"], "Hello");
+    assert!(raw.get("image").is_none());
+
+    assert_eq!(webhook.events(), vec![envelope.event.clone()]);
+
+    // Timestamps out of range have no time
+    let mut far = envelope.clone();
+    far.timestamp = Some(u64::MAX);
+    assert_eq!(far.time(), None);
+}
+
+#[test]
+fn test_envelope_for_status_and_field_changes() {
+    let webhook: WebhookPayload = serde_json::from_value(serde_json::json!({
+        "object": "whatsapp_business_account",
+        "entry": [{
+            "id": "WABA_ID",
+            "changes": [{
+                "value": {
+                    "messaging_product": "whatsapp",
+                    "metadata": {
+                        "display_phone_number": "628123456789",
+                        "phone_number_id": "PHONE_ID"
+                    },
+                    "statuses": [{
+                        "id": "wamid.1",
+                        "status": "read",
+                        "timestamp": "1704067300",
+                        "recipient_id": "628111222333"
+                    }]
+                },
+                "field": "messages"
+            }]
+        }, {
+            "id": "OTHER_WABA",
+            "time": 1704067400,
+            "changes": [{
+                "value": { "decision": "APPROVED" },
+                "field": "account_review_update"
+            }]
+        }]
+    }))
+    .unwrap();
+
+    let envelopes = webhook.envelopes();
+    assert_eq!(envelopes.len(), 2);
+
+    let status = &envelopes[0];
+    assert_eq!(status.kind(), EventKind::Status(StatusKind::Read));
+    assert_eq!(status.phone_number_id.as_deref(), Some("PHONE_ID"));
+    assert_eq!(status.timestamp, Some(1704067300));
+    assert_eq!(status.sender_wa_id, None);
+    assert_eq!(status.user(), Some("628111222333"));
+
+    let review = &envelopes[1];
+    assert_eq!(review.kind(), EventKind::AccountReview);
+    assert_eq!(review.waba_id, "OTHER_WABA");
+    assert_eq!(review.phone_number_id, None);
+    assert_eq!(review.timestamp, Some(1704067400));
+    assert_eq!(
+        review.raw,
+        Some(serde_json::json!({ "decision": "APPROVED" }))
+    );
+}